@@ -0,0 +1,141 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Benchmark of the semantic analysis.
+//!
+//! Run with `cargo bench --bench analysis`, the number of threads is set by the
+//! RAYON_NUM_THREADS environment variable.
+//!
+//! Two projects are measured:
+//! - The example_project libraries, which requires the git submodules of example_project
+//!   to be checked out. The project is skipped otherwise.
+//! - The vhdl_libraries together with generated packages written to a temporary folder.
+//!   Each generated package uses ieee.numeric_std and up to three earlier packages
+//!   and declares functions calling the functions of the earlier packages.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use vhdl_lang::{Config, Message, Project};
+
+const NUM_ITERATIONS: usize = 10;
+const NUM_GENERATED_PACKAGES: usize = 400;
+const NUM_GENERATED_FUNCTIONS: usize = 20;
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut messages: Vec<Message> = Vec::new();
+    let libraries_config = read_config(&root.join("vhdl_libraries").join("vhdl_ls.toml"));
+
+    let example_project = root.join("example_project");
+    if example_project.join("vunit").join("vunit").exists() {
+        let mut config = libraries_config.clone();
+        config.append(
+            &read_config(&example_project.join("vhdl_ls.toml")),
+            &mut messages,
+        );
+        bench("example_project", &config);
+    } else {
+        println!("Skipping example_project since its git submodules are not checked out");
+    }
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let mut config = libraries_config;
+    config.append(&generate_packages(tempdir.path()), &mut messages);
+    bench(
+        &format!("{} generated packages", NUM_GENERATED_PACKAGES),
+        &config,
+    );
+}
+
+fn read_config(file_name: &Path) -> Config {
+    Config::read_file_path(file_name, &mut Vec::new()).expect("Failed to read configuration")
+}
+
+fn bench(name: &str, config: &Config) {
+    let mut messages: Vec<Message> = Vec::new();
+    let mut durations: Vec<Duration> = Vec::with_capacity(NUM_ITERATIONS);
+    let mut num_files = 0;
+    for _ in 0..NUM_ITERATIONS {
+        // The analysis is measured on a new project since the result of the previous analysis is kept
+        let mut project = Project::from_config(config, &mut messages);
+        num_files = project.files().count();
+
+        let start = Instant::now();
+        let _diagnostics = project.analyse();
+        durations.push(start.elapsed());
+    }
+
+    durations.sort();
+    println!(
+        "Analysis of {} ({} files) with {} threads: min {} ms, median {} ms, max {} ms",
+        name,
+        num_files,
+        rayon::current_num_threads(),
+        durations[0].as_millis(),
+        durations[NUM_ITERATIONS / 2].as_millis(),
+        durations[NUM_ITERATIONS - 1].as_millis()
+    );
+}
+
+/// The earlier packages used by a generated package
+fn dependencies(idx: usize) -> Vec<usize> {
+    let mut result = Vec::new();
+    for dependency in [idx / 3, idx / 2, idx.saturating_sub(1)].iter() {
+        if *dependency < idx && !result.contains(dependency) {
+            result.push(*dependency);
+        }
+    }
+    result
+}
+
+/// Write the generated packages to the folder and return the configuration of their library
+fn generate_packages(folder: &Path) -> Config {
+    for idx in 0..NUM_GENERATED_PACKAGES {
+        let dependencies = dependencies(idx);
+        let mut code = String::new();
+
+        writeln!(code, "library ieee;").unwrap();
+        writeln!(code, "use ieee.numeric_std.all;").unwrap();
+        for dependency in dependencies.iter() {
+            writeln!(code, "use work.pkg{}.all;", dependency).unwrap();
+        }
+
+        writeln!(code, "package pkg{} is", idx).unwrap();
+        for fun in 0..NUM_GENERATED_FUNCTIONS {
+            writeln!(
+                code,
+                "  function fun{}_{}(value : unsigned) return unsigned;",
+                idx, fun
+            )
+            .unwrap();
+        }
+        writeln!(code, "end package;").unwrap();
+
+        writeln!(code, "package body pkg{} is", idx).unwrap();
+        for fun in 0..NUM_GENERATED_FUNCTIONS {
+            writeln!(
+                code,
+                "  function fun{}_{}(value : unsigned) return unsigned is",
+                idx, fun
+            )
+            .unwrap();
+            writeln!(code, "  begin").unwrap();
+            let mut expression = String::from("value");
+            for dependency in dependencies.iter() {
+                expression = format!("fun{}_{}({}) + {}", dependency, fun, expression, fun);
+            }
+            writeln!(code, "    return {};", expression).unwrap();
+            writeln!(code, "  end function;").unwrap();
+        }
+        writeln!(code, "end package body;").unwrap();
+
+        std::fs::write(folder.join(format!("pkg{}.vhd", idx)), code).unwrap();
+    }
+
+    Config::from_str("[libraries]\ngenerated.files = ['*.vhd']", folder)
+        .expect("Failed to create configuration")
+}
@@ -6,14 +6,18 @@
 
 #[macro_use]
 mod analyze;
+mod attribute;
 mod concurrent;
+mod configuration;
 mod declarative;
 mod design_unit;
 mod lock;
 mod named_entity;
 mod names;
+mod psl;
 mod region;
 mod root;
+mod schedule;
 mod semantic;
 mod sequential;
 mod target;
@@ -8,6 +8,7 @@ use super::region::*;
 use super::root::*;
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use fnv::FnvHashSet;
 use std::cell::RefCell;
 use std::sync::Arc;
@@ -18,8 +19,12 @@ pub enum AnalysisError {
 }
 
 impl AnalysisError {
-    pub fn not_fatal_error(pos: impl AsRef<SrcPos>, msg: impl Into<String>) -> AnalysisError {
-        AnalysisError::NotFatal(Diagnostic::error(pos, msg))
+    pub fn not_fatal_error(
+        pos: impl AsRef<SrcPos>,
+        msg: impl Into<String>,
+        code: ErrorCode,
+    ) -> AnalysisError {
+        AnalysisError::NotFatal(Diagnostic::error(pos, msg).with_code(code))
     }
 }
 
@@ -38,7 +43,10 @@ impl CircularDependencyError {
 
     pub fn push_into(self, diagnostics: &mut dyn DiagnosticHandler) {
         if let Some(pos) = self.reference {
-            diagnostics.push(Diagnostic::error(pos, "Found circular dependency"));
+            diagnostics.push(
+                Diagnostic::error(pos, "Found circular dependency")
+                    .with_code(ErrorCode::CircularDependency),
+            );
         }
     }
 }
@@ -93,19 +101,25 @@ pub(super) struct AnalyzeContext<'a> {
     //  - for example when doing 'use library.all' the file is sensitive to adding/removing
     //    anything from library
     current_unit: UnitId,
+    standard: VHDLStandard,
     uses: RefCell<FnvHashSet<UnitId>>,
     missing_primary: RefCell<FnvHashSet<(Symbol, Symbol)>>,
     uses_library_all: RefCell<FnvHashSet<Symbol>>,
 }
 
 impl<'a> AnalyzeContext<'a> {
-    pub fn new(root: &'a DesignRoot, current_unit: &UnitId) -> AnalyzeContext<'a> {
+    pub fn new(
+        root: &'a DesignRoot,
+        current_unit: &UnitId,
+        standard: VHDLStandard,
+    ) -> AnalyzeContext<'a> {
         AnalyzeContext {
             work_sym: root.symbol_utf8("work"),
             std_sym: root.symbol_utf8("std"),
             standard_sym: root.symbol_utf8("standard"),
             root,
             current_unit: current_unit.clone(),
+            standard,
             uses: RefCell::new(FnvHashSet::default()),
             missing_primary: RefCell::new(FnvHashSet::default()),
             uses_library_all: RefCell::new(FnvHashSet::default()),
@@ -124,6 +138,11 @@ impl<'a> AnalyzeContext<'a> {
         &self.current_unit
     }
 
+    /// The VHDL standard of the design unit being analyzed
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
     pub fn symbol_utf8(&self, name: &str) -> Symbol {
         self.root.symbol_utf8(name)
     }
@@ -299,13 +318,16 @@ impl<'a> AnalyzeContext<'a> {
             }
         }
 
-        Err(AnalysisError::NotFatal(Diagnostic::error(
-            pos,
-            format!(
-                "No primary unit '{}' within library '{}'",
-                primary_name, library_name
-            ),
-        )))
+        Err(AnalysisError::NotFatal(
+            Diagnostic::error(
+                pos,
+                format!(
+                    "No primary unit '{}' within library '{}'",
+                    primary_name, library_name
+                ),
+            )
+            .with_code(ErrorCode::Unresolved),
+        ))
     }
 
     pub fn expect_standard_package_analysis(&self) -> FatalResult<UnitReadGuard<'a>> {
@@ -321,6 +343,19 @@ impl<'a> AnalyzeContext<'a> {
         }
     }
 
+    /// Lookup a type declared in the standard package such as BOOLEAN
+    pub fn standard_type(&self, name: &str) -> FatalResult<Arc<NamedEntity>> {
+        let standard = self.expect_standard_package_analysis()?;
+        Ok(standard
+            .result()
+            .region
+            .lookup_immediate(&self.symbol_utf8(name).into())
+            .unwrap()
+            .clone()
+            .into_non_overloaded()
+            .unwrap())
+    }
+
     pub fn get_primary_analysis(
         &self,
         use_pos: &SrcPos,
@@ -331,4 +366,20 @@ impl<'a> AnalyzeContext<'a> {
         let unit = self.get_primary_unit_kind(library_name, name, kind)?;
         Some(self.get_analysis(Some(use_pos), unit))
     }
+
+    pub fn get_architecture_analysis(
+        &self,
+        use_pos: &SrcPos,
+        library_name: &Symbol,
+        entity_name: &Symbol,
+        name: &Symbol,
+    ) -> Option<FatalResult<UnitReadGuard<'a>>> {
+        let units = self.root.get_library_units(library_name)?;
+        let unit = units.get(&UnitKey::Secondary(entity_name.clone(), name.clone()))?;
+        if unit.kind() == AnyKind::Secondary(SecondaryKind::Architecture) {
+            Some(self.get_analysis(Some(use_pos), unit))
+        } else {
+            None
+        }
+    }
 }
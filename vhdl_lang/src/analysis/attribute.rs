@@ -0,0 +1,611 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::ast::*;
+use crate::data::*;
+use analyze::*;
+use declarative::is_of_entity_class;
+use region::*;
+use std::sync::Arc;
+
+/// The type of an attribute name when it is known
+pub enum AttributeType {
+    /// A value of the type
+    Value(Arc<NamedEntity>),
+    /// A value of the universal integer type such as 'length or 'pos
+    UniversalInteger,
+    /// An implicit signal of the type such as 'stable or 'delayed
+    Signal(Arc<NamedEntity>),
+    /// A type or subtype such as 'base or 'subtype
+    Type(Arc<NamedEntity>),
+    /// A range such as 'range or 'reverse_range
+    Range,
+}
+
+impl AttributeType {
+    /// Returns a diagnostic if the value of the attribute does not match the target type
+    pub fn check_target_type(
+        &self,
+        pos: &SrcPos,
+        attr: &AttributeName,
+        target_type: &NamedEntity,
+    ) -> Option<Diagnostic> {
+        let target_base = target_type.base_type();
+
+        let describe = match self {
+            AttributeType::Value(ref type_mark) | AttributeType::Signal(ref type_mark) => {
+                if type_mark.base_type() == target_base {
+                    return None;
+                }
+                type_mark.describe()
+            }
+            AttributeType::UniversalInteger => {
+                if matches!(
+                    target_base.kind(),
+                    NamedEntityKind::IntegerType(..) | NamedEntityKind::InterfaceType
+                ) {
+                    return None;
+                }
+                "universal_integer".to_owned()
+            }
+            AttributeType::Type(..) | AttributeType::Range => return None,
+        };
+
+        Some(
+            Diagnostic::error(
+                pos,
+                format!(
+                    "'{}' of {} does not match {}",
+                    attr,
+                    describe,
+                    target_type.describe()
+                ),
+            )
+            .with_code(ErrorCode::TypeMismatch),
+        )
+    }
+}
+
+/// LRM 16.2 Predefined attributes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PredefinedAttribute {
+    Base,
+    Left,
+    Right,
+    High,
+    Low,
+    Ascending,
+    Image,
+    Value,
+    Pos,
+    Val,
+    Succ,
+    Pred,
+    LeftOf,
+    RightOf,
+    Length,
+    Range,
+    ReverseRange,
+    Element,
+    Subtype,
+    DesignatedSubtype,
+    Index,
+    Reflect,
+    Converse,
+    Delayed,
+    Stable,
+    Quiet,
+    Transaction,
+    Event,
+    Active,
+    LastEvent,
+    LastActive,
+    LastValue,
+    Driving,
+    DrivingValue,
+    SimpleName,
+    InstanceName,
+    PathName,
+}
+
+impl PredefinedAttribute {
+    fn from_name(name: &str) -> Option<PredefinedAttribute> {
+        use PredefinedAttribute::*;
+        Some(match name.to_ascii_lowercase().as_str() {
+            "base" => Base,
+            "left" => Left,
+            "right" => Right,
+            "high" => High,
+            "low" => Low,
+            "ascending" => Ascending,
+            "image" => Image,
+            "value" => Value,
+            "pos" => Pos,
+            "val" => Val,
+            "succ" => Succ,
+            "pred" => Pred,
+            "leftof" => LeftOf,
+            "rightof" => RightOf,
+            "length" => Length,
+            "range" => Range,
+            "reverse_range" => ReverseRange,
+            "element" => Element,
+            "subtype" => Subtype,
+            "designated_subtype" => DesignatedSubtype,
+            "index" => Index,
+            "reflect" => Reflect,
+            "converse" => Converse,
+            "delayed" => Delayed,
+            "stable" => Stable,
+            "quiet" => Quiet,
+            "transaction" => Transaction,
+            "event" => Event,
+            "active" => Active,
+            "last_event" => LastEvent,
+            "last_active" => LastActive,
+            "last_value" => LastValue,
+            "driving" => Driving,
+            "driving_value" => DrivingValue,
+            "simple_name" => SimpleName,
+            "instance_name" => InstanceName,
+            "path_name" => PathName,
+            _ => return None,
+        })
+    }
+
+    /// The kind of prefix the attribute requires, None if any prefix is allowed
+    fn required_prefix(self) -> Option<RequiredPrefix> {
+        use PredefinedAttribute::*;
+        match self {
+            Base | Value | Pos | Val | Succ | Pred | LeftOf | RightOf | DesignatedSubtype => {
+                Some(RequiredPrefix::Type)
+            }
+            Left | Right | High | Low | Ascending => Some(RequiredPrefix::ArrayOrType),
+            Range | ReverseRange => Some(RequiredPrefix::TypeOrObject),
+            Image | Reflect => Some(RequiredPrefix::TypeOrObject),
+            Length | Element => Some(RequiredPrefix::Array),
+            Subtype => Some(RequiredPrefix::Object),
+            Delayed | Stable | Quiet | Transaction | Event | Active | LastEvent | LastActive
+            | LastValue | Driving | DrivingValue => Some(RequiredPrefix::Signal),
+            Index | Converse | SimpleName | InstanceName | PathName => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RequiredPrefix {
+    Type,
+    TypeOrObject,
+    ArrayOrType,
+    Array,
+    Object,
+    Signal,
+}
+
+impl RequiredPrefix {
+    fn describe(self) -> &'static str {
+        match self {
+            RequiredPrefix::Type => "type",
+            RequiredPrefix::TypeOrObject => "type or object",
+            RequiredPrefix::ArrayOrType => "array or type",
+            RequiredPrefix::Array => "array",
+            RequiredPrefix::Object => "object",
+            RequiredPrefix::Signal => "signal",
+        }
+    }
+
+    /// Conservative check, a prefix which is not known is always considered a match
+    fn is_satisfied_by(self, prefix: &PrefixKind) -> bool {
+        match prefix {
+            PrefixKind::Unknown => true,
+            PrefixKind::Other => false,
+            PrefixKind::Type(ref type_mark) => match self {
+                RequiredPrefix::Type
+                | RequiredPrefix::TypeOrObject
+                | RequiredPrefix::ArrayOrType => true,
+                RequiredPrefix::Array => !matches!(as_array(type_mark), ArrayKind::NotArray),
+                RequiredPrefix::Object | RequiredPrefix::Signal => false,
+            },
+            PrefixKind::Object {
+                ref type_mark,
+                is_signal,
+            } => match self {
+                RequiredPrefix::Type => false,
+                RequiredPrefix::TypeOrObject | RequiredPrefix::Object => true,
+                RequiredPrefix::ArrayOrType | RequiredPrefix::Array => {
+                    if let Some(ref type_mark) = type_mark {
+                        !matches!(as_array(type_mark), ArrayKind::NotArray)
+                    } else {
+                        true
+                    }
+                }
+                RequiredPrefix::Signal => *is_signal != Some(false),
+            },
+        }
+    }
+}
+
+/// What the prefix of an attribute name denotes
+enum PrefixKind {
+    Type(Arc<NamedEntity>),
+    // The type and whether it is a signal are not always known
+    Object {
+        type_mark: Option<Arc<NamedEntity>>,
+        is_signal: Option<bool>,
+    },
+    // A named entity which is neither a type nor an object such as a label or a package
+    Other,
+    Unknown,
+}
+
+struct Prefix {
+    // The named entity when the prefix is a name
+    named: Option<Arc<NamedEntity>>,
+    kind: PrefixKind,
+}
+
+impl Prefix {
+    fn unknown() -> Prefix {
+        Prefix {
+            named: None,
+            kind: PrefixKind::Unknown,
+        }
+    }
+
+    fn from_named_entity(ent: Arc<NamedEntity>) -> Prefix {
+        let kind = match ent.actual_kind() {
+            kind if kind.is_type() => PrefixKind::Type(ent.clone()),
+            NamedEntityKind::Object(ref object) => PrefixKind::Object {
+                type_mark: Some(object.subtype.type_mark().clone()),
+                is_signal: Some(object.class == ObjectClass::Signal),
+            },
+            NamedEntityKind::ObjectAlias {
+                ref base_object,
+                ref type_mark,
+            } => PrefixKind::Object {
+                type_mark: Some(type_mark.clone()),
+                is_signal: Some(base_object.class() == ObjectClass::Signal),
+            },
+            NamedEntityKind::DeferredConstant(ref subtype) => PrefixKind::Object {
+                type_mark: Some(subtype.type_mark().clone()),
+                is_signal: Some(false),
+            },
+            // The record element of a signal is also a signal
+            NamedEntityKind::ElementDeclaration(ref subtype) => PrefixKind::Object {
+                type_mark: Some(subtype.type_mark().clone()),
+                is_signal: None,
+            },
+            NamedEntityKind::LoopParameter
+            | NamedEntityKind::File
+            | NamedEntityKind::InterfaceFile(..) => PrefixKind::Object {
+                type_mark: None,
+                is_signal: Some(false),
+            },
+            NamedEntityKind::PslParameter
+            | NamedEntityKind::View(..)
+            | NamedEntityKind::PhysicalLiteral(..) => PrefixKind::Unknown,
+            _ => PrefixKind::Other,
+        };
+
+        Prefix {
+            named: Some(ent),
+            kind,
+        }
+    }
+
+    fn from_attribute_type(attr_type: Option<AttributeType>) -> Prefix {
+        let kind = match attr_type {
+            Some(AttributeType::Type(type_mark)) => PrefixKind::Type(type_mark),
+            Some(AttributeType::Value(type_mark)) => PrefixKind::Object {
+                type_mark: Some(type_mark),
+                is_signal: Some(false),
+            },
+            Some(AttributeType::Signal(type_mark)) => PrefixKind::Object {
+                type_mark: Some(type_mark),
+                is_signal: Some(true),
+            },
+            Some(AttributeType::UniversalInteger) | Some(AttributeType::Range) | None => {
+                PrefixKind::Unknown
+            }
+        };
+
+        Prefix { named: None, kind }
+    }
+
+    fn type_mark(&self) -> Option<&Arc<NamedEntity>> {
+        match self.kind {
+            PrefixKind::Type(ref type_mark) => Some(type_mark),
+            PrefixKind::Object {
+                type_mark: Some(ref type_mark),
+                ..
+            } => Some(type_mark),
+            _ => None,
+        }
+    }
+}
+
+enum ArrayKind<'e> {
+    Array {
+        indexes: &'e [Option<Arc<NamedEntity>>],
+        elem_type: &'e Arc<NamedEntity>,
+    },
+    NotArray,
+    Unknown,
+}
+
+/// Access types to arrays are implicitly dereferenced
+fn as_array(type_mark: &NamedEntity) -> ArrayKind<'_> {
+    let base_type = type_mark.base_type();
+    let base_type: &NamedEntity = if let NamedEntityKind::AccessType(ref subtype) = base_type.kind()
+    {
+        subtype.base_type()
+    } else {
+        base_type
+    };
+
+    match base_type.kind() {
+        NamedEntityKind::ArrayType {
+            ref indexes,
+            ref elem_type,
+            ..
+        } => ArrayKind::Array { indexes, elem_type },
+        NamedEntityKind::InterfaceType | NamedEntityKind::IncompleteType(..) => ArrayKind::Unknown,
+        _ => ArrayKind::NotArray,
+    }
+}
+
+/// The index type of the dimension given by the optional attribute parameter
+fn index_type(
+    indexes: &[Option<Arc<NamedEntity>>],
+    dimension: &Option<Box<WithPos<Expression>>>,
+) -> Option<Arc<NamedEntity>> {
+    let dimension = match dimension {
+        None => 1,
+        Some(ref expr) => {
+            if let Expression::Literal(Literal::AbstractLiteral(AbstractLiteral::Integer(value))) =
+                expr.item
+            {
+                value as usize
+            } else {
+                return None;
+            }
+        }
+    };
+
+    indexes
+        .get(dimension.checked_sub(1)?)
+        .and_then(|index| index.clone())
+}
+
+impl<'a> AnalyzeContext<'a> {
+    /// LRM 8.6 Attribute names
+    /// Returns the type of the attribute name when it is known
+    pub fn analyze_attribute_name(
+        &self,
+        region: &Region<'_>,
+        attr: &mut AttributeName,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<AttributeType>> {
+        let AttributeName {
+            name,
+            signature,
+            attr,
+            expr,
+        } = attr;
+
+        let prefix = if let Name::Attribute(ref mut inner) = name.item {
+            Prefix::from_attribute_type(self.analyze_attribute_name(region, inner, diagnostics)?)
+        } else {
+            match self.resolve_name(region, &name.pos, &mut name.item, diagnostics)? {
+                Some(NamedEntities::Single(ent)) => Prefix::from_named_entity(ent),
+                _ => Prefix::unknown(),
+            }
+        };
+
+        if let Some(ref mut signature) = signature {
+            if let Err(err) = self.resolve_signature(region, signature) {
+                err.add_to(diagnostics)?;
+            }
+        }
+
+        attr.clear_reference();
+        if let Some(predefined) = PredefinedAttribute::from_name(&attr.item.name().name_utf8()) {
+            if let (Some(required), Some(ref ent)) = (predefined.required_prefix(), &prefix.named) {
+                if !required.is_satisfied_by(&prefix.kind) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &name.pos,
+                            format!(
+                                "Expected {} as prefix of attribute '{}', got {}",
+                                required.describe(),
+                                attr.item.name(),
+                                ent.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::MismatchedKinds),
+                    );
+                }
+            }
+            self.analyze_predefined_attribute(region, predefined, &prefix, expr, diagnostics)
+        } else {
+            self.analyze_user_defined_attribute(region, &prefix, attr, expr, diagnostics)
+        }
+    }
+
+    /// LRM 16.2 Predefined attributes
+    fn analyze_predefined_attribute(
+        &self,
+        region: &Region<'_>,
+        predefined: PredefinedAttribute,
+        prefix: &Prefix,
+        expr: &mut Option<Box<WithPos<Expression>>>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<AttributeType>> {
+        use PredefinedAttribute::*;
+
+        // The parameter type when it is known
+        let param_type = match predefined {
+            Image | Pos | Succ | Pred | LeftOf | RightOf => match prefix.kind {
+                PrefixKind::Type(ref type_mark) => Some(type_mark.clone()),
+                _ => None,
+            },
+            Value => Some(self.standard_type("STRING")?),
+            Delayed | Stable | Quiet => Some(self.standard_type("TIME")?),
+            _ => None,
+        };
+
+        if let Some(ref mut expr) = expr {
+            if let Some(ref param_type) = param_type {
+                self.analyze_expression_with_target_type(region, param_type, expr, diagnostics)?;
+            } else {
+                self.analyze_expression(region, expr, diagnostics)?;
+            }
+        }
+
+        let prefix_type = match prefix.kind {
+            PrefixKind::Type(ref type_mark) => Some(type_mark),
+            _ => None,
+        };
+
+        Ok(match predefined {
+            Base => prefix_type.map(|type_mark| AttributeType::Type(base_type(type_mark).clone())),
+            Left | Right | High | Low => {
+                prefix
+                    .type_mark()
+                    .and_then(|type_mark| match as_array(type_mark) {
+                        ArrayKind::Array { indexes, .. } => {
+                            index_type(indexes, expr).map(AttributeType::Value)
+                        }
+                        ArrayKind::NotArray => {
+                            prefix_type.map(|type_mark| AttributeType::Value(type_mark.clone()))
+                        }
+                        ArrayKind::Unknown => None,
+                    })
+            }
+            Value | Val | Succ | Pred | LeftOf | RightOf => {
+                prefix_type.map(|type_mark| AttributeType::Value(type_mark.clone()))
+            }
+            Ascending | Event | Active | Driving => {
+                Some(AttributeType::Value(self.standard_type("BOOLEAN")?))
+            }
+            Image | SimpleName | InstanceName | PathName => {
+                Some(AttributeType::Value(self.standard_type("STRING")?))
+            }
+            LastEvent | LastActive => Some(AttributeType::Value(self.standard_type("TIME")?)),
+            Pos | Length => Some(AttributeType::UniversalInteger),
+            Range | ReverseRange => Some(AttributeType::Range),
+            Element => prefix
+                .type_mark()
+                .and_then(|type_mark| match as_array(type_mark) {
+                    ArrayKind::Array { elem_type, .. } => {
+                        Some(AttributeType::Type(elem_type.clone()))
+                    }
+                    _ => None,
+                }),
+            Subtype => match prefix.kind {
+                PrefixKind::Object {
+                    type_mark: Some(ref type_mark),
+                    ..
+                } => Some(AttributeType::Type(type_mark.clone())),
+                _ => None,
+            },
+            DesignatedSubtype => prefix_type.and_then(|type_mark| {
+                if let NamedEntityKind::AccessType(ref subtype) = type_mark.base_type().kind() {
+                    Some(AttributeType::Type(subtype.type_mark().clone()))
+                } else {
+                    None
+                }
+            }),
+            Delayed => prefix
+                .type_mark()
+                .map(|type_mark| AttributeType::Signal(type_mark.clone())),
+            Stable | Quiet => Some(AttributeType::Signal(self.standard_type("BOOLEAN")?)),
+            Transaction => Some(AttributeType::Signal(self.standard_type("BIT")?)),
+            LastValue | DrivingValue => match prefix.kind {
+                PrefixKind::Object {
+                    type_mark: Some(ref type_mark),
+                    ..
+                } => Some(AttributeType::Value(type_mark.clone())),
+                _ => None,
+            },
+            Index | Reflect | Converse => None,
+        })
+    }
+
+    /// LRM 7.2 User-defined attributes are given a value by an attribute specification
+    fn analyze_user_defined_attribute(
+        &self,
+        region: &Region<'_>,
+        prefix: &Prefix,
+        attr: &mut WithRef<Ident>,
+        expr: &mut Option<Box<WithPos<Expression>>>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<AttributeType>> {
+        if let Some(ref mut expr) = expr {
+            self.analyze_expression(region, expr, diagnostics)?;
+        }
+
+        let attribute = region
+            .lookup_within(
+                attr.item.pos(),
+                &Designator::Identifier(attr.item.name().clone()),
+            )
+            .map_err(AnalysisError::NotFatal)
+            .and_then(|entities| {
+                self.resolve_non_overloaded(
+                    entities,
+                    attr.item.pos(),
+                    &|kind| matches!(kind, NamedEntityKind::Attribute(..)),
+                    "attribute",
+                )
+            });
+
+        let attribute = match attribute {
+            Ok(attribute) => attribute,
+            Err(err) => {
+                err.add_to(diagnostics)?;
+                return Ok(None);
+            }
+        };
+        attr.set_unique_reference(&attribute);
+
+        // Attribute specifications are only known for named entities declared within the region
+        if let Some(ref ent) = prefix.named {
+            if let Some(specifications) = region.lookup_attribute_specifications(ent) {
+                let is_specified = specifications.iter().any(|spec| {
+                    spec.attribute == attribute.id()
+                        && match spec.target {
+                            SpecifiedFor::Entity(id) => id == ent.id(),
+                            SpecifiedFor::Class(entity_class) => {
+                                is_of_entity_class(ent.actual_kind(), entity_class)
+                            }
+                        }
+                });
+
+                if !is_specified {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            attr.item.pos(),
+                            format!(
+                                "Attribute '{}' has not been specified for {}",
+                                attr.item.name(),
+                                ent.describe()
+                            ),
+                        )
+                        .with_code(ErrorCode::Unresolved),
+                    );
+                }
+            }
+        }
+
+        if let NamedEntityKind::Attribute(ref type_mark) = attribute.kind() {
+            Ok(Some(AttributeType::Value(type_mark.clone())))
+        } else {
+            Ok(None)
+        }
+    }
+}
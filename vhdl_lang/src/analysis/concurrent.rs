@@ -12,6 +12,7 @@ use crate::ast::*;
 use crate::data::*;
 use analyze::*;
 use region::*;
+use std::sync::Arc;
 use target::AssignmentType;
 
 impl<'a> AnalyzeContext<'a> {
@@ -40,10 +41,31 @@ impl<'a> AnalyzeContext<'a> {
 
         match statement.statement {
             ConcurrentStatement::Block(ref mut block) => {
+                let mut region = parent.nested();
                 if let Some(ref mut guard_condition) = block.guard_condition {
-                    self.analyze_expression(parent, guard_condition, diagnostics)?;
+                    let boolean = self.standard_type("BOOLEAN")?;
+                    self.analyze_expression_with_target_type(
+                        parent,
+                        &boolean,
+                        guard_condition,
+                        diagnostics,
+                    )?;
+
+                    // The guard condition implicitly declares a signal GUARD of type BOOLEAN
+                    region.add_named_entity(
+                        Arc::new(NamedEntity::new(
+                            self.symbol_utf8("GUARD"),
+                            NamedEntityKind::Object(Object {
+                                class: ObjectClass::Signal,
+                                mode: None,
+                                has_default: true,
+                                subtype: Subtype::new(boolean),
+                            }),
+                            Some(&guard_condition.pos),
+                        )),
+                        diagnostics,
+                    );
                 }
-                let mut region = parent.nested();
                 if let Some(ref mut list) = block.header.generic_clause {
                     self.analyze_interface_list(&mut region, list, diagnostics)?;
                 }
@@ -122,7 +144,25 @@ impl<'a> AnalyzeContext<'a> {
             }
             ConcurrentStatement::Assignment(ref mut assign) => {
                 // @TODO more delaymechanism
-                let ConcurrentSignalAssignment { target, rhs, .. } = assign;
+                let ConcurrentSignalAssignment {
+                    target,
+                    rhs,
+                    guarded,
+                    ..
+                } = assign;
+                if *guarded
+                    && parent
+                        .lookup_within(&target.pos, &self.symbol_utf8("GUARD").into())
+                        .is_err()
+                {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &target,
+                            "Guarded signal assignment requires a visible GUARD signal",
+                        )
+                        .with_code(ErrorCode::Unresolved),
+                    );
+                }
                 self.analyze_waveform_assignment(
                     parent,
                     target,
@@ -156,6 +196,9 @@ impl<'a> AnalyzeContext<'a> {
                     self.analyze_expression(parent, expr, diagnostics)?;
                 }
             }
+            ConcurrentStatement::Psl(ref mut directive) => {
+                self.analyze_psl_directive(parent, directive, diagnostics)?;
+            }
         };
         Ok(())
     }
@@ -0,0 +1,377 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+// These fields are better explicit than .. since we are forced to consider if new fields should be searched
+#![allow(clippy::unneeded_field_pattern)]
+
+use super::*;
+use crate::ast::*;
+use crate::data::*;
+use analyze::*;
+use region::*;
+
+impl<'a> AnalyzeContext<'a> {
+    /// LRM 3.4 Configuration declarations
+    /// Analyze the declarative part and block configuration of a configuration of entity
+    pub fn analyze_configuration_body(
+        &self,
+        root_region: &Region<'_>,
+        entity: &NamedEntity,
+        unit: &mut ConfigurationDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let mut region = root_region.nested();
+
+        for item in unit.decl.iter_mut() {
+            match item {
+                ConfigurationDeclarativeItem::Use(ref mut use_clause) => {
+                    self.analyze_use_clause(&mut region, &mut use_clause.item, diagnostics)?;
+                }
+                ConfigurationDeclarativeItem::Attribute(ref mut attr_spec) => {
+                    self.analyze_attribute_specification(&mut region, attr_spec, diagnostics)?;
+                }
+                ConfigurationDeclarativeItem::Group(ref mut group) => {
+                    self.analyze_group_declaration(&region, group, diagnostics)?;
+                    region.add(&group.ident, NamedEntityKind::Group, diagnostics);
+                }
+            }
+        }
+
+        let entity_name = entity.designator().expect_identifier();
+        self.analyze_architecture_block_configuration(
+            &region,
+            self.work_library_name(),
+            entity_name,
+            &mut unit.block_config,
+            diagnostics,
+        )
+    }
+
+    /// The outermost block configuration of an entity names one of its architectures
+    fn analyze_architecture_block_configuration(
+        &self,
+        region: &Region<'_>,
+        library_name: &Symbol,
+        entity_name: &Symbol,
+        block_config: &mut BlockConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let block_spec = &block_config.block_spec;
+        let arch_name = match block_spec.item {
+            Name::Designator(ref designator) => match designator.item {
+                Designator::Identifier(ref sym) => Some(sym.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let arch_name = if let Some(arch_name) = arch_name {
+            arch_name
+        } else {
+            diagnostics.push(
+                Diagnostic::error(
+                    block_spec,
+                    "Block specification of an architecture must be a simple name",
+                )
+                .with_code(ErrorCode::MismatchedKinds),
+            );
+            return self.analyze_block_configuration_items(region, None, block_config, diagnostics);
+        };
+
+        let entity = if let Some(entity) = self.get_primary_analysis(
+            &block_spec.pos,
+            library_name,
+            entity_name,
+            PrimaryKind::Entity,
+        ) {
+            entity?
+        } else {
+            return self.analyze_block_configuration_items(region, None, block_config, diagnostics);
+        };
+
+        let architecture = if let Some(architecture) =
+            self.get_architecture_analysis(&block_spec.pos, library_name, entity_name, &arch_name)
+        {
+            architecture?
+        } else {
+            diagnostics.push(
+                Diagnostic::error(
+                    block_spec,
+                    format!(
+                        "No architecture '{}' for entity '{}'",
+                        arch_name, entity_name
+                    ),
+                )
+                .with_code(ErrorCode::Unresolved),
+            );
+            return self.analyze_block_configuration_items(region, None, block_config, diagnostics);
+        };
+
+        let arch_root_region = Region::extend(
+            &architecture.result().root_region,
+            Some(&entity.result().root_region),
+        );
+        let arch_region = Region::extend(&architecture.result().region, Some(&arch_root_region));
+
+        self.analyze_block_configuration_items(
+            region,
+            Some((&arch_region, &arch_name)),
+            block_config,
+            diagnostics,
+        )
+    }
+
+    /// The scope is the region of the configured architecture together with its name
+    /// It is None when the configured block is not known, such as within nested blocks
+    fn analyze_block_configuration_items(
+        &self,
+        region: &Region<'_>,
+        scope: Option<(&Region<'_>, &Symbol)>,
+        block_config: &mut BlockConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let BlockConfiguration {
+            block_spec: _,
+            use_clauses,
+            items,
+        } = block_config;
+
+        let mut region = region.nested();
+        for use_clause in use_clauses.iter_mut() {
+            self.analyze_use_clause(&mut region, &mut use_clause.item, diagnostics)?;
+        }
+
+        for item in items.iter_mut() {
+            match item {
+                ConfigurationItem::Block(ref mut block_config) => {
+                    if let Some((scope, arch_name)) = scope {
+                        let block_spec = &block_config.block_spec;
+                        if let Name::Designator(ref designator) = block_spec.item {
+                            if !is_label(scope.lookup_immediate(&designator.item)) {
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        block_spec,
+                                        format!(
+                                            "No block or generate statement '{}' within architecture '{}'",
+                                            designator.item, arch_name
+                                        ),
+                                    )
+                                    .with_code(ErrorCode::Unresolved),
+                                );
+                            }
+                        }
+                    }
+                    // @TODO the declarations of nested blocks are not known here
+                    self.analyze_block_configuration_items(
+                        &region,
+                        None,
+                        block_config,
+                        diagnostics,
+                    )?;
+                }
+                ConfigurationItem::Component(ref mut component_config) => {
+                    self.analyze_component_configuration(
+                        &region,
+                        scope,
+                        component_config,
+                        diagnostics,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// LRM 3.4.3 Component configuration
+    fn analyze_component_configuration(
+        &self,
+        region: &Region<'_>,
+        scope: Option<(&Region<'_>, &Symbol)>,
+        component_config: &mut ComponentConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let ComponentConfiguration {
+            spec,
+            bind_ind,
+            vunit_bind_inds: _,
+            block_config,
+        } = component_config;
+
+        if let Some((scope, arch_name)) = scope {
+            if let InstantiationList::Labels(ref mut labels) = spec.instantiation_list {
+                for label in labels.iter_mut() {
+                    label.clear_reference();
+                    let designator = Designator::Identifier(label.item.name().clone());
+                    match scope.lookup_immediate(&designator) {
+                        Some(NamedEntities::Single(ent))
+                            if matches!(ent.kind(), NamedEntityKind::Label) =>
+                        {
+                            label.set_unique_reference(ent);
+                        }
+                        _ => {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    label.item.pos(),
+                                    format!(
+                                        "No instance '{}' within architecture '{}'",
+                                        label.item.name(),
+                                        arch_name
+                                    ),
+                                )
+                                .with_code(ErrorCode::Unresolved),
+                            );
+                        }
+                    }
+                }
+            }
+
+            let component_name = &mut spec.component_name;
+            if let Err(err) =
+                self.resolve_selected_name(scope, component_name)
+                    .and_then(|entities| {
+                        self.resolve_non_overloaded(
+                            entities,
+                            component_name.suffix_pos(),
+                            &|kind| matches!(kind, NamedEntityKind::Component),
+                            "component",
+                        )
+                    })
+            {
+                err.add_to(diagnostics)?;
+            }
+        }
+
+        let bound_entity = if let Some(ref mut bind_ind) = bind_ind {
+            self.analyze_binding_indication(region, bind_ind, diagnostics)?
+        } else {
+            None
+        };
+
+        if let Some(ref mut block_config) = block_config {
+            if let Some((library_name, entity_name)) = bound_entity {
+                self.analyze_architecture_block_configuration(
+                    region,
+                    &library_name,
+                    &entity_name,
+                    block_config,
+                    diagnostics,
+                )?;
+            } else {
+                self.analyze_block_configuration_items(region, None, block_config, diagnostics)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// LRM 7.3.2 Binding indication
+    /// Returns the library and entity name of the bound entity when known
+    fn analyze_binding_indication(
+        &self,
+        region: &Region<'_>,
+        bind_ind: &mut BindingIndication,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<(Symbol, Symbol)>> {
+        let BindingIndication {
+            entity_aspect,
+            // @TODO check generic and port maps
+            generic_map: _,
+            port_map: _,
+        } = bind_ind;
+
+        match entity_aspect {
+            Some(EntityAspect::Entity(ref mut entity_name, ref architecture_name)) => {
+                let entity =
+                    match self
+                        .resolve_selected_name(region, entity_name)
+                        .and_then(|entities| {
+                            self.resolve_non_overloaded(
+                                entities,
+                                entity_name.suffix_pos(),
+                                &|kind| matches!(kind, NamedEntityKind::Entity(..)),
+                                "entity",
+                            )
+                        }) {
+                        Ok(entity) => entity,
+                        Err(err) => {
+                            err.add_to(diagnostics)?;
+                            return Ok(None);
+                        }
+                    };
+
+                // The library is only known when the entity name is selected
+                let library_name = if let Some(library_name) = library_of(entity_name) {
+                    library_name
+                } else {
+                    return Ok(None);
+                };
+                let entity_name = entity.designator().expect_identifier().clone();
+
+                if let Some(ref architecture_name) = architecture_name {
+                    if let Some(architecture) = self.get_architecture_analysis(
+                        architecture_name.pos(),
+                        &library_name,
+                        &entity_name,
+                        architecture_name.name(),
+                    ) {
+                        architecture?;
+                    } else {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                architecture_name.pos(),
+                                format!(
+                                    "No architecture '{}' for entity '{}'",
+                                    architecture_name.name(),
+                                    entity_name
+                                ),
+                            )
+                            .with_code(ErrorCode::Unresolved),
+                        );
+                        return Ok(None);
+                    }
+                }
+
+                Ok(Some((library_name, entity_name)))
+            }
+            Some(EntityAspect::Configuration(ref mut config_name)) => {
+                if let Err(err) =
+                    self.resolve_selected_name(region, config_name)
+                        .and_then(|entities| {
+                            self.resolve_non_overloaded(
+                                entities,
+                                config_name.suffix_pos(),
+                                &|kind| matches!(kind, NamedEntityKind::Configuration(..)),
+                                "configuration",
+                            )
+                        })
+                {
+                    err.add_to(diagnostics)?;
+                }
+                Ok(None)
+            }
+            Some(EntityAspect::Open) | None => Ok(None),
+        }
+    }
+}
+
+fn is_label(entities: Option<&NamedEntities>) -> bool {
+    matches!(entities, Some(NamedEntities::Single(ent)) if matches!(ent.kind(), NamedEntityKind::Label))
+}
+
+/// The library of a selected name such as lib.ent after it has been resolved
+fn library_of(name: &WithPos<SelectedName>) -> Option<Symbol> {
+    if let SelectedName::Selected(ref prefix, _) = name.item {
+        if let SelectedName::Designator(ref designator) = prefix.item {
+            if let Some(ref library) = designator.reference {
+                if matches!(library.kind(), NamedEntityKind::Library) {
+                    return Some(library.designator().expect_identifier().clone());
+                }
+            }
+        }
+    }
+    None
+}
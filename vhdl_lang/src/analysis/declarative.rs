@@ -9,6 +9,7 @@ use super::*;
 use crate::ast;
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use analyze::*;
 use arc_swap::ArcSwapWeak;
 use fnv::FnvHashMap;
@@ -51,7 +52,8 @@ impl<'a> AnalyzeContext<'a> {
                                             "Missing full type declaration of incomplete type '{}'",
                                             type_decl.ident.name()
                                         ),
-                                        );
+                                        )
+                                        .with_code(ErrorCode::MissingFullTypeDeclaration);
                                         error.add_related(type_decl.ident.pos(), "The full type declaration shall occur immediately within the same declarative part");
                                         diagnostics.push(error);
                                         type_decl.ident.pos()
@@ -190,7 +192,8 @@ impl<'a> AnalyzeContext<'a> {
                                     let mut diagnostic = Diagnostic::error(
                                         name,
                                         "Could not find declaration with given signature",
-                                    );
+                                    )
+                                    .with_code(ErrorCode::InvalidSignature);
                                     for ent in overloaded.entities() {
                                         if let Some(pos) = ent.decl_pos() {
                                             diagnostic.add_related(
@@ -209,9 +212,12 @@ impl<'a> AnalyzeContext<'a> {
                             }
                         }
                     } else {
-                        diagnostics.error(
-                            name,
-                            "Signature required for alias of subprogram and enum literals",
+                        diagnostics.push(
+                            Diagnostic::error(
+                                name,
+                                "Signature required for alias of subprogram and enum literals",
+                            )
+                            .with_code(ErrorCode::InvalidSignature),
                         );
                         return Ok(None);
                     }
@@ -226,6 +232,201 @@ impl<'a> AnalyzeContext<'a> {
         ))))
     }
 
+    /// LRM 6.10 Group declarations
+    pub fn analyze_group_declaration(
+        &self,
+        region: &Region<'_>,
+        group: &mut GroupDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let GroupDeclaration {
+            ident,
+            template_name,
+            constituents,
+        } = group;
+
+        let template = match self.resolve_selected_name(region, template_name) {
+            Ok(entities) => self.resolve_non_overloaded(
+                entities,
+                &template_name.pos,
+                &|kind| matches!(kind, NamedEntityKind::GroupTemplate(..)),
+                "group template",
+            ),
+            Err(err) => Err(err),
+        };
+
+        let entity_classes = match template {
+            Ok(ent) => match ent.actual_kind() {
+                NamedEntityKind::GroupTemplate(entity_classes) => entity_classes.clone(),
+                _ => unreachable!(),
+            },
+            Err(err) => {
+                err.add_to(diagnostics)?;
+                return Ok(());
+            }
+        };
+
+        let is_repeated = entity_classes
+            .last()
+            .map(|entry| entry.is_repeated)
+            .unwrap_or(false);
+
+        let count_ok = if is_repeated {
+            constituents.len() + 1 >= entity_classes.len()
+        } else {
+            constituents.len() == entity_classes.len()
+        };
+
+        if !count_ok {
+            diagnostics.push(
+                Diagnostic::error(
+                    &ident,
+                    format!(
+                        "Group '{}' has {} constituents, expected {}{}",
+                        ident.item,
+                        constituents.len(),
+                        if is_repeated { "at least " } else { "" },
+                        if is_repeated {
+                            entity_classes.len() - 1
+                        } else {
+                            entity_classes.len()
+                        }
+                    ),
+                )
+                .with_code(ErrorCode::MismatchedKinds),
+            );
+        }
+
+        for (idx, constituent) in constituents.iter_mut().enumerate() {
+            let entity_class = match entity_classes.get(idx).or_else(|| {
+                if is_repeated {
+                    entity_classes.last()
+                } else {
+                    None
+                }
+            }) {
+                Some(entry) => entry.entity_class,
+                None => break,
+            };
+
+            // Labels of statements are declared after the declarative part
+            if entity_class == EntityClass::Label {
+                continue;
+            }
+
+            if let Some(NamedEntities::Single(ent)) =
+                self.resolve_name(region, &constituent.pos, &mut constituent.item, diagnostics)?
+            {
+                if !is_of_entity_class(ent.actual_kind(), entity_class) {
+                    let mut error = Diagnostic::error(
+                        &constituent,
+                        format!("Expected {}, got {}", entity_class, ent.describe()),
+                    )
+                    .with_code(ErrorCode::MismatchedKinds);
+                    if let Some(decl_pos) = ent.decl_pos() {
+                        error.add_related(decl_pos, "Defined here");
+                    }
+                    diagnostics.push(error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// LRM 7.2 Attribute specification
+    pub fn analyze_attribute_specification(
+        &self,
+        region: &mut Region<'_>,
+        attr_spec: &mut AttributeSpecification,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let AttributeSpecification {
+            ident,
+            entity_name,
+            entity_class,
+            expr,
+        } = attr_spec;
+
+        ident.clear_reference();
+        let attribute = region
+            .lookup_within(
+                ident.item.pos(),
+                &Designator::Identifier(ident.item.name().clone()),
+            )
+            .map_err(AnalysisError::NotFatal)
+            .and_then(|entities| {
+                self.resolve_non_overloaded(
+                    entities,
+                    ident.item.pos(),
+                    &|kind| matches!(kind, NamedEntityKind::Attribute(..)),
+                    "attribute",
+                )
+            });
+        let attribute = match attribute {
+            Ok(ent) => {
+                ident.set_unique_reference(&ent);
+                Some(ent)
+            }
+            Err(err) => {
+                err.add_to(diagnostics)?;
+                None
+            }
+        };
+
+        match entity_name {
+            EntityName::Name(ref mut tag) => {
+                tag.designator.item.clear_reference();
+                // Design units, subprograms, literals and labels are not resolved
+                // since they are overloaded or not yet declared
+                if is_resolved_entity_class(*entity_class) {
+                    let entity_class = *entity_class;
+                    let ent = region
+                        .lookup_within(&tag.designator.pos, &tag.designator.item.item)
+                        .map_err(AnalysisError::NotFatal)
+                        .and_then(|entities| {
+                            self.resolve_non_overloaded(
+                                entities,
+                                &tag.designator.pos,
+                                &|kind| is_of_entity_class(kind, entity_class),
+                                &entity_class.to_string(),
+                            )
+                        });
+                    match ent {
+                        Ok(ent) => {
+                            tag.designator.item.set_unique_reference(&ent);
+                            if let Some(ref attribute) = attribute {
+                                region.add_attribute_specification(
+                                    attribute,
+                                    SpecifiedFor::Entity(ent.id()),
+                                );
+                            }
+                        }
+                        Err(err) => err.add_to(diagnostics)?,
+                    }
+                } else if let Some(ref attribute) = attribute {
+                    // Conservatively consider the attribute specified for the whole entity class
+                    region
+                        .add_attribute_specification(attribute, SpecifiedFor::Class(*entity_class));
+                }
+            }
+            EntityName::All | EntityName::Others => {
+                if let Some(ref attribute) = attribute {
+                    region
+                        .add_attribute_specification(attribute, SpecifiedFor::Class(*entity_class));
+                }
+            }
+        }
+
+        if let Some(NamedEntityKind::Attribute(ref type_mark)) =
+            attribute.as_ref().map(|attribute| attribute.kind())
+        {
+            self.analyze_expression_with_target_type(region, type_mark, expr, diagnostics)
+        } else {
+            self.analyze_expression(region, expr, diagnostics)
+        }
+    }
+
     fn analyze_declaration(
         &self,
         region: &mut Region<'_>,
@@ -303,14 +504,48 @@ impl<'a> AnalyzeContext<'a> {
             }
             Declaration::Attribute(ref mut attr) => match attr {
                 Attribute::Declaration(ref mut attr_decl) => {
-                    if let Err(err) = self.resolve_type_mark(region, &mut attr_decl.type_mark) {
-                        err.add_to(diagnostics)?;
+                    match self.resolve_type_mark(region, &mut attr_decl.type_mark) {
+                        Ok(type_mark) => {
+                            region.add(
+                                &attr_decl.ident,
+                                NamedEntityKind::Attribute(type_mark),
+                                diagnostics,
+                            );
+                        }
+                        Err(err) => err.add_to(diagnostics)?,
                     }
-                    region.add(&attr_decl.ident, NamedEntityKind::Attribute, diagnostics);
                 }
-                // @TODO Ignored for now
-                Attribute::Specification(..) => {}
+                Attribute::Specification(ref mut attr_spec) => {
+                    self.analyze_attribute_specification(region, attr_spec, diagnostics)?;
+                }
             },
+            Declaration::GroupTemplate(ref mut template) => {
+                region.add(
+                    &template.ident,
+                    NamedEntityKind::GroupTemplate(template.entity_classes.clone()),
+                    diagnostics,
+                );
+            }
+            Declaration::Group(ref mut group) => {
+                self.analyze_group_declaration(region, group, diagnostics)?;
+                region.add(&group.ident, NamedEntityKind::Group, diagnostics);
+            }
+            Declaration::Disconnection(ref mut disconnection) => {
+                let DisconnectionSpecification {
+                    signal_list,
+                    type_mark,
+                    after,
+                } = disconnection;
+                if let GuardedSignalList::Names(ref mut names) = signal_list {
+                    for name in names.iter_mut() {
+                        self.resolve_name(region, &name.pos, &mut name.item, diagnostics)?;
+                    }
+                }
+                if let Err(err) = self.resolve_type_mark(region, type_mark) {
+                    err.add_to(diagnostics)?;
+                }
+                self.analyze_expression(region, after, diagnostics)?;
+            }
             Declaration::SubprogramBody(ref mut body) => {
                 let mut subpgm_region = region.nested();
 
@@ -383,12 +618,115 @@ impl<'a> AnalyzeContext<'a> {
                 }
             }
             Declaration::Configuration(..) => {}
+            Declaration::View(ref mut view) => {
+                self.analyze_mode_view_declaration(region, view, diagnostics)?;
+            }
+            Declaration::Psl(ref mut psl_decl) => {
+                self.analyze_psl_declaration(region, psl_decl, diagnostics)?;
+            }
+            Declaration::DefaultClock(ref mut clock) => {
+                self.analyze_expression(region, clock, diagnostics)?;
+            }
             Declaration::Type(..) => unreachable!("Handled elsewhere"),
         };
 
         Ok(())
     }
 
+    /// LRM 6.5.2 Mode view declarations
+    fn analyze_mode_view_declaration(
+        &self,
+        region: &mut Region<'_>,
+        view: &mut ModeViewDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let ModeViewDeclaration {
+            ident,
+            subtype_indication,
+            elements,
+        } = view;
+
+        let subtype = self.resolve_subtype_indication(region, subtype_indication, diagnostics);
+
+        let record_type = match subtype {
+            Ok(ref subtype) => {
+                let base_type = subtype.base_type();
+                if let NamedEntityKind::RecordType(..) = base_type.kind() {
+                    Some(base_type.clone())
+                } else {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            &subtype_indication.type_mark,
+                            format!("Expected record type, got {}", base_type.describe()),
+                        )
+                        .with_code(ErrorCode::TypeMismatch),
+                    );
+                    None
+                }
+            }
+            Err(_) => None,
+        };
+
+        for ModeViewElement { names, mode } in elements.iter_mut() {
+            for name in names.iter_mut() {
+                name.clear_reference();
+                if let Some(ref record_type) = record_type {
+                    if let NamedEntityKind::RecordType(ref record_region) = record_type.kind() {
+                        let designator = Designator::Identifier(name.item.item.clone());
+                        if let Some(visible) = record_region.lookup_selected(&designator) {
+                            name.set_reference(visible);
+                        } else {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &name.item,
+                                    format!(
+                                        "No declaration of '{}' within {}",
+                                        designator,
+                                        record_type.describe()
+                                    ),
+                                )
+                                .with_code(ErrorCode::Unresolved),
+                            );
+                        }
+                    }
+                }
+            }
+
+            if let ElementMode::View(_, ref mut view_name) = mode {
+                if let Err(err) = self.resolve_mode_view_name(region, view_name) {
+                    err.add_to(diagnostics)?;
+                }
+            }
+        }
+
+        match subtype {
+            Ok(subtype) => region.add(&*ident, NamedEntityKind::View(subtype), diagnostics),
+            Err(err) => err.add_to(diagnostics)?,
+        }
+        Ok(())
+    }
+
+    /// Resolve the name of a mode view and return the subtype it applies to
+    /// The 'converse of a mode view applies to the same subtype
+    fn resolve_mode_view_name(
+        &self,
+        region: &Region<'_>,
+        view_name: &mut ModeViewName,
+    ) -> AnalysisResult<Subtype> {
+        let entities = self.resolve_selected_name(region, &mut view_name.name)?;
+        let ent = self.resolve_non_overloaded(
+            entities,
+            view_name.name.suffix_pos(),
+            &NamedEntityKind::is_view,
+            "view",
+        )?;
+
+        match ent.actual_kind() {
+            NamedEntityKind::View(ref subtype) => Ok(subtype.clone()),
+            _ => unreachable!("Checked by resolve_non_overloaded"),
+        }
+    }
+
     fn analyze_type_declaration(
         &self,
         parent: &mut Region<'_>,
@@ -457,20 +795,26 @@ impl<'a> AnalyzeContext<'a> {
                         };
 
                         if !is_ok {
-                            diagnostics.push(Diagnostic::error(
-                                type_decl.ident.pos(),
-                                format!("'{}' is not a protected type", &type_decl.ident.item),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    type_decl.ident.pos(),
+                                    format!("'{}' is not a protected type", &type_decl.ident.item),
+                                )
+                                .with_code(ErrorCode::MismatchedKinds),
+                            );
                         }
                     }
                     None => {
-                        diagnostics.push(Diagnostic::error(
-                            type_decl.ident.pos(),
-                            format!(
-                                "No declaration of protected type '{}'",
-                                &type_decl.ident.item
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                type_decl.ident.pos(),
+                                format!(
+                                    "No declaration of protected type '{}'",
+                                    &type_decl.ident.item
+                                ),
+                            )
+                            .with_code(ErrorCode::Unresolved),
+                        );
                     }
                 };
             }
@@ -601,8 +945,7 @@ impl<'a> AnalyzeContext<'a> {
                 ));
 
                 let mut implicit = Vec::new();
-                if !self.is_standard_package() {
-                    // @TODO analyze standard package separately
+                if self.has_implicit_to_string() {
                     let to_string = Arc::new(self.create_to_string(type_ent.clone()));
                     parent.add_named_entity(to_string.clone(), diagnostics);
                     implicit.push(Arc::downgrade(&to_string));
@@ -669,8 +1012,7 @@ impl<'a> AnalyzeContext<'a> {
                 ));
 
                 let mut implicit = Vec::new();
-                if !self.is_standard_package() {
-                    // @TODO analyze standard package separately
+                if self.has_implicit_to_string() {
                     let to_string = Arc::new(self.create_to_string(type_ent.clone()));
                     parent.add_named_entity(to_string.clone(), diagnostics);
                     implicit.push(Arc::downgrade(&to_string));
@@ -795,6 +1137,12 @@ impl<'a> AnalyzeContext<'a> {
         implicit
     }
 
+    /// The implicit TO_STRING function was added in VHDL-2008
+    fn has_implicit_to_string(&self) -> bool {
+        // @TODO analyze standard package separately
+        !self.is_standard_package() && self.standard() >= VHDLStandard::VHDL2008
+    }
+
     /// Create implicit TO_STRING
     /// function TO_STRING (VALUE: T) return STRING;
     pub fn create_to_string(&self, type_ent: Arc<NamedEntity>) -> NamedEntity {
@@ -917,6 +1265,37 @@ impl<'a> AnalyzeContext<'a> {
                     Some(&object_decl.ident.pos),
                 )
             }
+            InterfaceDeclaration::View(ref mut view_decl) => {
+                let ModeViewIndication {
+                    name,
+                    subtype_indication,
+                    ..
+                } = &mut view_decl.view;
+
+                let view_subtype = self.resolve_mode_view_name(region, name);
+
+                // An explicit subtype indication takes precedence over the subtype of the view
+                let subtype = if let Some(ref mut subtype_indication) = subtype_indication {
+                    if let Err(err) = view_subtype {
+                        err.add_to(diagnostics)?;
+                    }
+                    self.resolve_subtype_indication(region, subtype_indication, diagnostics)?
+                } else {
+                    view_subtype?
+                };
+
+                NamedEntity::new(
+                    view_decl.ident.name().clone(),
+                    NamedEntityKind::Object(Object {
+                        class: ObjectClass::Signal,
+                        // The element modes are given by the view
+                        mode: Some(Mode::InOut),
+                        subtype,
+                        has_default: false,
+                    }),
+                    Some(&view_decl.ident.pos),
+                )
+            }
             InterfaceDeclaration::Type(ref ident) => NamedEntity::new(
                 ident.name().clone(),
                 NamedEntityKind::InterfaceType,
@@ -1129,4 +1508,67 @@ fn signature_error(pos: impl AsRef<SrcPos>) -> Diagnostic {
         pos,
         "Alias should only have a signature for subprograms and enum literals",
     )
+    .with_code(ErrorCode::InvalidSignature)
+}
+
+/// Entity classes whose named entities can be resolved when analyzing an attribute specification
+fn is_resolved_entity_class(entity_class: EntityClass) -> bool {
+    matches!(
+        entity_class,
+        EntityClass::Signal
+            | EntityClass::Variable
+            | EntityClass::Constant
+            | EntityClass::Type
+            | EntityClass::Subtype
+            | EntityClass::Component
+            | EntityClass::File
+            | EntityClass::Group
+            | EntityClass::Property
+            | EntityClass::Sequence
+    )
+}
+
+/// Conservative check that a named entity belongs to an entity class,
+/// entity classes that cannot be checked are always considered a match
+pub fn is_of_entity_class(kind: &NamedEntityKind, entity_class: EntityClass) -> bool {
+    match entity_class {
+        EntityClass::Signal => matches!(
+            kind,
+            NamedEntityKind::Object(Object {
+                class: ObjectClass::Signal,
+                ..
+            })
+        ),
+        EntityClass::Variable => matches!(
+            kind,
+            NamedEntityKind::Object(Object {
+                class: ObjectClass::Variable,
+                ..
+            }) | NamedEntityKind::Object(Object {
+                class: ObjectClass::SharedVariable,
+                ..
+            })
+        ),
+        EntityClass::Constant => {
+            kind.is_deferred_constant()
+                || matches!(
+                    kind,
+                    NamedEntityKind::Object(Object {
+                        class: ObjectClass::Constant,
+                        ..
+                    })
+                )
+        }
+        EntityClass::Type => kind.is_type() && !matches!(kind, NamedEntityKind::Subtype(..)),
+        EntityClass::Subtype => matches!(kind, NamedEntityKind::Subtype(..)),
+        EntityClass::Component => matches!(kind, NamedEntityKind::Component),
+        EntityClass::File => matches!(
+            kind,
+            NamedEntityKind::File | NamedEntityKind::InterfaceFile(..)
+        ),
+        EntityClass::Group => matches!(kind, NamedEntityKind::Group),
+        EntityClass::Property => matches!(kind, NamedEntityKind::Property),
+        EntityClass::Sequence => matches!(kind, NamedEntityKind::Sequence),
+        _ => true,
+    }
 }
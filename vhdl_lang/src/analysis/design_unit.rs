@@ -39,10 +39,13 @@ impl<'a> AnalyzeContext<'a> {
                 AnyPrimaryUnit::Context(unit) => {
                     self.analyze_context(unit, root_region, region, diagnostics)
                 }
+                AnyPrimaryUnit::VerificationUnit(unit) => {
+                    self.analyze_verification_unit(id, unit, root_region, region, diagnostics)
+                }
             },
             AnyDesignUnit::Secondary(unit) => match unit {
                 AnySecondaryUnit::Architecture(unit) => {
-                    self.analyze_architecture(id, unit, diagnostics)
+                    self.analyze_architecture(id, unit, root_region, region, diagnostics)
                 }
                 AnySecondaryUnit::PackageBody(unit) => self.analyze_package_body(unit, diagnostics),
             },
@@ -104,16 +107,20 @@ impl<'a> AnalyzeContext<'a> {
                     if primary_pos.source == secondary_pos.source
                         && primary_pos.start() > secondary_pos.start()
                     {
-                        diagnostics.push(Diagnostic::error(
-                            secondary_pos,
-                            capitalize(&format!(
-                                "{} declared before {}",
-                                self.current_unit_id().describe(),
-                                named_entity.describe()
-                            )),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                secondary_pos,
+                                capitalize(&format!(
+                                    "{} declared before {}",
+                                    self.current_unit_id().describe(),
+                                    named_entity.describe()
+                                )),
+                            )
+                            .with_code(ErrorCode::DeclarationOrder),
+                        );
                     }
                 }
+                self.analyze_configuration_body(&root_region, &named_entity, unit, diagnostics)?;
             }
             Err(err) => {
                 err.add_to(diagnostics)?;
@@ -152,11 +159,24 @@ impl<'a> AnalyzeContext<'a> {
         }
         self.analyze_declarative_part(&mut primary_region, &mut unit.decl, diagnostics)?;
 
+        let public_region = if unit.private_decl.is_some() {
+            Some(primary_region.clone())
+        } else {
+            None
+        };
+
+        if let Some(ref mut private_decl) = unit.private_decl {
+            self.analyze_declarative_part(&mut primary_region, private_decl, diagnostics)?;
+        }
+
         if !self.has_package_body() {
             primary_region.close(diagnostics);
         }
 
-        *region = primary_region.without_parent();
+        *region = match public_region {
+            Some(public_region) => public_region.with_private(primary_region).without_parent(),
+            None => primary_region.without_parent(),
+        };
 
         Ok(())
     }
@@ -204,6 +224,8 @@ impl<'a> AnalyzeContext<'a> {
         &self,
         id: EntityId,
         unit: &mut ArchitectureBody,
+        arch_root_region: &mut Region<'_>,
+        arch_region: &mut Region<'_>,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalNullResult {
         unit.entity_name.clear_reference();
@@ -244,6 +266,152 @@ impl<'a> AnalyzeContext<'a> {
         self.analyze_declarative_part(&mut region, &mut unit.decl, diagnostics)?;
         self.analyze_concurrent_part(&mut region, &mut unit.statements, diagnostics)?;
         region.close(diagnostics);
+
+        // Verification units bound to the architecture see its declarations
+        *arch_region = region.without_parent();
+        *arch_root_region = root_region.without_parent();
+        Ok(())
+    }
+
+    /// PSL verification units are bound to an entity and optionally an architecture
+    /// in which case all their declarations are visible
+    fn analyze_verification_unit(
+        &self,
+        id: EntityId,
+        unit: &mut VerificationUnit,
+        root_region: &mut Region<'_>,
+        region: &mut Region<'_>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let VerificationUnit {
+            context_clause,
+            kind: _,
+            ident,
+            entity_name,
+            architecture_name,
+            inherits,
+            decl,
+            statements,
+        } = unit;
+
+        let entity = if let Some(ref mut entity_name) = entity_name {
+            entity_name.clear_reference();
+            let entity = self.lookup_primary_unit(
+                &entity_name.item,
+                PrimaryKind::Entity,
+                ident.pos(),
+                diagnostics,
+            )?;
+            let entity = if let Some(entity) = entity {
+                entity
+            } else {
+                return Ok(());
+            };
+
+            if let Some(ref named_entity) = entity.result().ent {
+                entity_name.set_unique_reference(named_entity);
+            }
+            Some(entity)
+        } else {
+            None
+        };
+
+        let architecture = match (&entity, architecture_name) {
+            (Some(_), Some(ref mut architecture_name)) => {
+                let entity_name = &entity_name.as_ref().unwrap().item;
+                if let Some(architecture) = self.get_architecture_analysis(
+                    architecture_name.item.pos(),
+                    self.work_library_name(),
+                    entity_name.name(),
+                    architecture_name.item.name(),
+                ) {
+                    Some(architecture?)
+                } else {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            architecture_name.item.pos(),
+                            format!(
+                                "No architecture '{}' for entity '{}'",
+                                architecture_name.item.name(),
+                                entity_name.name()
+                            ),
+                        )
+                        .with_code(ErrorCode::Unresolved),
+                    );
+                    return Ok(());
+                }
+            }
+            _ => None,
+        };
+
+        let mut unit_root_region = match (&entity, &architecture) {
+            (Some(entity), Some(architecture)) => Region::extend(
+                &architecture.result().root_region,
+                Some(&entity.result().root_region),
+            ),
+            (Some(entity), None) => Region::default().with_parent(&entity.result().root_region),
+            (None, _) => {
+                let mut unit_root_region = Region::default();
+                self.add_implicit_context_clause(&mut unit_root_region)?;
+                unit_root_region
+            }
+        };
+        self.analyze_context_clause(&mut unit_root_region, context_clause, diagnostics)?;
+
+        let mut unit_region = match (&entity, &architecture) {
+            (_, Some(architecture)) => {
+                Region::extend(&architecture.result().region, Some(&unit_root_region))
+            }
+            (Some(entity), None) => {
+                Region::extend(&entity.result().region, Some(&unit_root_region))
+            }
+            (None, None) => unit_root_region.nested(),
+        };
+
+        // Verification unit name is visible
+        unit_region.make_potentially_visible(
+            Some(ident.pos()),
+            Arc::new(NamedEntity::new_with_id(
+                id,
+                Designator::Identifier(ident.name().clone()),
+                NamedEntityKind::Label,
+                Some(ident.pos()),
+            )),
+        );
+
+        for inherit in inherits.iter_mut() {
+            inherit.clear_reference();
+            match self.lookup_in_library(
+                self.work_library_name(),
+                inherit.item.pos(),
+                &Designator::Identifier(inherit.item.name().clone()),
+            ) {
+                Ok(ent) => {
+                    if let NamedEntityKind::VerificationUnit(ref inherited) = ent.kind() {
+                        inherit.set_unique_reference(&ent);
+                        unit_region
+                            .make_all_potentially_visible(Some(inherit.item.pos()), inherited);
+                    } else {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                inherit.item.pos(),
+                                format!("{} is not a verification unit", ent.describe()),
+                            )
+                            .with_code(ErrorCode::MismatchedKinds),
+                        );
+                    }
+                }
+                Err(err) => err.add_to(diagnostics)?,
+            }
+        }
+
+        self.analyze_declarative_part(&mut unit_region, decl, diagnostics)?;
+        self.analyze_concurrent_part(&mut unit_region, statements, diagnostics)?;
+
+        *region = unit_region.without_parent();
+        if entity.is_none() {
+            *root_region = unit_root_region.without_parent();
+        }
         Ok(())
     }
 
@@ -299,15 +467,18 @@ impl<'a> AnalyzeContext<'a> {
             ) {
                 data?
             } else {
-                diagnostics.push(Diagnostic::error(
-                    primary_ident.pos(),
-                    format!(
-                        "No {} '{}' within library '{}'",
-                        primary_kind.describe(),
-                        primary_ident.name(),
-                        self.work_library_name()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        primary_ident.pos(),
+                        format!(
+                            "No {} '{}' within library '{}'",
+                            primary_kind.describe(),
+                            primary_ident.name(),
+                            self.work_library_name()
+                        ),
+                    )
+                    .with_code(ErrorCode::Unresolved),
+                );
                 return Ok(None);
             }
         };
@@ -315,15 +486,18 @@ impl<'a> AnalyzeContext<'a> {
         let primary_pos = unit.pos();
         if primary_pos.source == secondary_pos.source && primary_pos.start() > secondary_pos.start()
         {
-            diagnostics.push(Diagnostic::error(
-                secondary_pos,
-                format!(
-                    "{} declared before {} '{}'",
-                    capitalize(&self.current_unit_id().describe()),
-                    primary_kind.describe(),
-                    unit.name()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    secondary_pos,
+                    format!(
+                        "{} declared before {} '{}'",
+                        capitalize(&self.current_unit_id().describe()),
+                        primary_kind.describe(),
+                        unit.name()
+                    ),
+                )
+                .with_code(ErrorCode::DeclarationOrder),
+            );
         }
 
         Ok(Some(unit))
@@ -366,6 +540,7 @@ impl<'a> AnalyzeContext<'a> {
                              AnalysisError::not_fatal_error(
                                  &prefix.pos,
                                  format!("{} does not denote a library", ent.first().describe()),
+                                 ErrorCode::MismatchedKinds,
                              )
                     )
                     .and_then(|library_ent| match library_ent.kind() {
@@ -375,6 +550,7 @@ impl<'a> AnalyzeContext<'a> {
                                 Err(AnalysisError::not_fatal_error(
                                     &prefix.pos,
                                     format!("Configuration must be within the same library '{}' as the corresponding entity", self.work_library_name()),
+                                    ErrorCode::InvalidConfiguration,
                                 ))
                             } else {
                                 let primary_ent = self.lookup_in_library(library_name, &designator.pos, designator.designator())?;
@@ -390,6 +566,7 @@ impl<'a> AnalyzeContext<'a> {
                                         Err(AnalysisError::not_fatal_error(
                                             designator,
                                             format!("{} does not denote an entity", primary_ent.describe()),
+                                            ErrorCode::MismatchedKinds,
                                         ))
                                     }
                                 }
@@ -399,7 +576,8 @@ impl<'a> AnalyzeContext<'a> {
                         _ => {
                             Err(AnalysisError::not_fatal_error(
                                 &prefix.pos,
-                                format!("{} does not denote a library", library_ent.describe())
+                                format!("{} does not denote a library", library_ent.describe()),
+                                ErrorCode::MismatchedKinds,
                             ))
                         }
                     })
@@ -414,11 +592,16 @@ impl<'a> AnalyzeContext<'a> {
     ) -> AnalysisResult<Arc<NamedEntity>> {
         match self.resolve_context_item_name(region, prefix)? {
             UsedNames::Single(visible) => visible.into_non_overloaded().map_err(|_| {
-                AnalysisError::not_fatal_error(&prefix, "Invalid prefix of a selected name")
+                AnalysisError::not_fatal_error(
+                    &prefix,
+                    "Invalid prefix of a selected name",
+                    ErrorCode::InvalidName,
+                )
             }),
             UsedNames::AllWithin(..) => Err(AnalysisError::not_fatal_error(
                 &prefix,
                 "'.all' may not be the prefix of a selected name",
+                ErrorCode::InvalidName,
             )),
         }
     }
@@ -455,6 +638,7 @@ impl<'a> AnalyzeContext<'a> {
             | Name::External(..) => Err(AnalysisError::not_fatal_error(
                 &name.pos,
                 "Invalid selected name",
+                ErrorCode::InvalidName,
             )),
         }
     }
@@ -470,17 +654,23 @@ impl<'a> AnalyzeContext<'a> {
                 ContextItem::Library(LibraryClause { ref name_list }) => {
                     for library_name in name_list.iter() {
                         if self.work_sym == library_name.item {
-                            diagnostics.push(Diagnostic::hint(
-                                &library_name,
-                                "Library clause not necessary for current working library",
-                            ))
+                            diagnostics.push(
+                                Diagnostic::hint(
+                                    &library_name,
+                                    "Library clause not necessary for current working library",
+                                )
+                                .with_code(ErrorCode::UnnecessaryLibraryClause),
+                            )
                         } else if let Some(library) = self.get_library(&library_name.item) {
                             region.make_potentially_visible(Some(&library_name.pos), library);
                         } else {
-                            diagnostics.push(Diagnostic::error(
-                                &library_name,
-                                format!("No such library '{}'", library_name.item),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &library_name,
+                                    format!("No such library '{}'", library_name.item),
+                                )
+                                .with_code(ErrorCode::Unresolved),
+                            );
                         }
                     }
                 }
@@ -492,10 +682,13 @@ impl<'a> AnalyzeContext<'a> {
                         match name.item {
                             Name::Selected(..) => {}
                             _ => {
-                                diagnostics.push(Diagnostic::error(
-                                    &name.pos,
-                                    "Context reference must be a selected name",
-                                ));
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        &name.pos,
+                                        "Context reference must be a selected name",
+                                    )
+                                    .with_code(ErrorCode::InvalidName),
+                                );
                                 continue;
                             }
                         }
@@ -513,13 +706,16 @@ impl<'a> AnalyzeContext<'a> {
                                     }
                                     _ => {
                                         if let Name::Selected(_, ref suffix) = name.item {
-                                            diagnostics.push(Diagnostic::error(
-                                                &suffix,
-                                                format!(
-                                                    "{} does not denote a context declaration",
-                                                    ent.describe()
-                                                ),
-                                            ));
+                                            diagnostics.push(
+                                                Diagnostic::error(
+                                                    &suffix,
+                                                    format!(
+                                                        "{} does not denote a context declaration",
+                                                        ent.describe()
+                                                    ),
+                                                )
+                                                .with_code(ErrorCode::MismatchedKinds),
+                                            );
                                         }
                                     }
                                 }
@@ -550,10 +746,10 @@ impl<'a> AnalyzeContext<'a> {
                 Name::Selected(..) => {}
                 Name::SelectedAll(..) => {}
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        &name.pos,
-                        "Use clause must be a selected name",
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(&name.pos, "Use clause must be a selected name")
+                            .with_code(ErrorCode::InvalidName),
+                    );
                     continue;
                 }
             }
@@ -578,7 +774,13 @@ impl<'a> AnalyzeContext<'a> {
                             region.make_all_potentially_visible(Some(&name.pos), primary_region);
                         }
                         _ => {
-                            diagnostics.error(visibility_pos, "Invalid prefix for selected name");
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    visibility_pos,
+                                    "Invalid prefix for selected name",
+                                )
+                                .with_code(ErrorCode::InvalidName),
+                            );
                         }
                     }
                 }
@@ -608,6 +810,7 @@ impl<'a> AnalyzeContext<'a> {
                     "'{}' is not an uninstantiated generic package",
                     package_name
                 ),
+                ErrorCode::MismatchedKinds,
             ))
         }
     }
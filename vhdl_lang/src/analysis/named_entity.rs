@@ -23,7 +23,8 @@ pub enum NamedEntityKind {
     File,
     InterfaceFile(Arc<NamedEntity>),
     Component,
-    Attribute,
+    // The type of the attribute value
+    Attribute(Arc<NamedEntity>),
     SubprogramDecl(Signature),
     Subprogram(Signature),
     EnumLiteral(Signature),
@@ -60,6 +61,16 @@ pub enum NamedEntityKind {
     PackageInstance(Arc<Region<'static>>),
     Context(Arc<Region<'static>>),
     LocalPackageInstance(Arc<Region<'static>>),
+    // VHDL-2019 mode view of the record subtype
+    View(Subtype),
+    // PSL named properties and sequences and their formal parameters
+    Property,
+    Sequence,
+    PslParameter,
+    VerificationUnit(Arc<Region<'static>>),
+    // The entity class entries of the group template
+    GroupTemplate(Vec<EntityClassEntry>),
+    Group,
 }
 
 impl NamedEntityKind {
@@ -78,6 +89,10 @@ impl NamedEntityKind {
         )
     }
 
+    pub fn is_view(&self) -> bool {
+        matches!(self, NamedEntityKind::View(..))
+    }
+
     pub fn is_protected_type(&self) -> bool {
         matches!(self, NamedEntityKind::ProtectedType(..))
     }
@@ -124,7 +139,7 @@ impl NamedEntityKind {
             ElementDeclaration(..) => "element declaration",
             RecordType(..) => "record type",
             Component => "component",
-            Attribute => "attribute",
+            Attribute(..) => "attribute",
             SubprogramDecl(signature) | Subprogram(signature) => {
                 if signature.return_type.is_some() {
                     "function"
@@ -154,6 +169,13 @@ impl NamedEntityKind {
             PackageInstance(..) => "package instance",
             Context(..) => "context",
             LocalPackageInstance(..) => "package instance",
+            View(..) => "view",
+            Property => "property",
+            Sequence => "sequence",
+            PslParameter => "parameter",
+            VerificationUnit(..) => "verification unit",
+            GroupTemplate(..) => "group template",
+            Group => "group",
         }
     }
 }
@@ -488,9 +510,14 @@ impl NamedEntity {
         )
     }
 
-    pub fn error(&self, diagnostics: &mut dyn DiagnosticHandler, message: impl Into<String>) {
+    pub fn error(
+        &self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        message: impl Into<String>,
+        code: ErrorCode,
+    ) {
         if let Some(ref pos) = self.decl_pos {
-            diagnostics.push(Diagnostic::error(pos, message));
+            diagnostics.push(Diagnostic::error(pos, message).with_code(code));
         }
     }
 
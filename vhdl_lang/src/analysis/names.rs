@@ -124,7 +124,9 @@ impl<'a> AnalyzeContext<'a> {
                             }
                             NamedEntities::Overloaded(..) => {
                                 // Probably a protected type method, this can never be aliased or a target
-                                Err(Diagnostic::error(name_pos, err_msg).into())
+                                Err(Diagnostic::error(name_pos, err_msg)
+                                    .with_code(ErrorCode::InvalidName)
+                                    .into())
                             }
                         }
                     }
@@ -137,13 +139,17 @@ impl<'a> AnalyzeContext<'a> {
                             NamedEntities::Overloaded(..) => {
                                 // Probably a protected type method, this can never be aliased or a target
                                 // Likely a user error
-                                Err(Diagnostic::error(name_pos, err_msg).into())
+                                Err(Diagnostic::error(name_pos, err_msg)
+                                    .with_code(ErrorCode::InvalidName)
+                                    .into())
                             }
                         }
                     }
                     ResolvedName::Overloaded(..) => {
                         // Overloaded suffix of overloaded name is not possible
-                        Err(Diagnostic::error(name_pos, err_msg).into())
+                        Err(Diagnostic::error(name_pos, err_msg)
+                            .with_code(ErrorCode::InvalidName)
+                            .into())
                     }
                 }
             }
@@ -198,7 +204,9 @@ impl<'a> AnalyzeContext<'a> {
                     for expr in indexes.iter_mut() {
                         self.analyze_expression(region, expr, diagnostics)?;
                     }
-                    Err(Diagnostic::error(&prefix.pos, err_msg).into())
+                    Err(Diagnostic::error(&prefix.pos, err_msg)
+                        .with_code(ErrorCode::InvalidName)
+                        .into())
                 }
             }
 
@@ -218,14 +226,35 @@ impl<'a> AnalyzeContext<'a> {
                 self.analyze_discrete_range(region, drange.as_mut(), diagnostics)?;
                 res
             }
-            Name::Attribute(..) => Err(Diagnostic::error(name_pos, err_msg).into()),
+            Name::Attribute(ref mut attr) if is_converse(attr) => {
+                // The converse of a mode view can be aliased
+                let resolved = self.resolve_object_prefix(
+                    region,
+                    &attr.name.pos,
+                    &mut attr.name.item,
+                    err_msg,
+                    diagnostics,
+                )?;
+
+                match resolved {
+                    ResolvedName::NonObject(ref ent) if ent.actual_kind().is_view() => Ok(resolved),
+                    _ => Err(Diagnostic::error(name_pos, err_msg)
+                        .with_code(ErrorCode::InvalidName)
+                        .into()),
+                }
+            }
+            Name::Attribute(..) => Err(Diagnostic::error(name_pos, err_msg)
+                .with_code(ErrorCode::InvalidName)
+                .into()),
 
             Name::FunctionCall(ref mut fcall) => {
                 if let Some(indexed_name) = fcall.to_indexed() {
                     *name = indexed_name;
                     self.resolve_object_prefix(region, name_pos, name, err_msg, diagnostics)
                 } else {
-                    Err(Diagnostic::error(name_pos, err_msg).into())
+                    Err(Diagnostic::error(name_pos, err_msg)
+                        .with_code(ErrorCode::InvalidName)
+                        .into())
                 }
             }
             Name::External(ref mut ename) => {
@@ -239,3 +268,15 @@ impl<'a> AnalyzeContext<'a> {
         }
     }
 }
+
+/// The 'converse attribute of a mode view
+fn is_converse(attr: &AttributeName) -> bool {
+    attr.signature.is_none()
+        && attr.expr.is_none()
+        && attr
+            .attr
+            .item
+            .item
+            .name_utf8()
+            .eq_ignore_ascii_case("converse")
+}
@@ -0,0 +1,237 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+// These fields are better explicit than .. since we are forced to consider if new fields should be searched
+#![allow(clippy::unneeded_field_pattern)]
+
+use super::*;
+use crate::ast::*;
+use crate::data::*;
+use analyze::*;
+use region::*;
+
+/// The PSL built-in functions which may be called within a PSL boolean
+/// next is a reserved word of VHDL and is parsed as the next operator instead
+const BUILTIN_FUNCTIONS: [&str; 9] = [
+    "rose",
+    "fell",
+    "prev",
+    "stable",
+    "onehot",
+    "onehot0",
+    "isunknown",
+    "countones",
+    "ended",
+];
+
+fn is_builtin_function(name: &Name) -> bool {
+    if let Name::FunctionCall(ref fcall) = name {
+        if let Name::Designator(ref designator) = fcall.name.item {
+            if let Designator::Identifier(ref sym) = designator.item {
+                let name = sym.name_utf8().to_ascii_lowercase();
+                return BUILTIN_FUNCTIONS.contains(&name.as_str());
+            }
+        }
+    }
+    false
+}
+
+impl<'a> AnalyzeContext<'a> {
+    pub fn analyze_psl_directive(
+        &self,
+        region: &Region<'_>,
+        directive: &mut PslDirective,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let PslDirective {
+            kind: _,
+            expression,
+            report,
+            severity,
+        } = directive;
+        self.analyze_psl_expression(region, expression, diagnostics)?;
+        if let Some(expr) = report {
+            self.analyze_expression(region, expr, diagnostics)?;
+        }
+        if let Some(expr) = severity {
+            self.analyze_expression(region, expr, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    pub fn analyze_psl_declaration(
+        &self,
+        region: &mut Region<'_>,
+        decl: &mut PslDeclaration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        let PslDeclaration {
+            kind,
+            ident,
+            parameters,
+            expression,
+        } = decl;
+
+        let mut body_region = region.nested();
+        for param in parameters.iter() {
+            body_region.add(
+                param.ident.clone(),
+                NamedEntityKind::PslParameter,
+                diagnostics,
+            );
+        }
+        self.analyze_psl_expression(&body_region, expression, diagnostics)?;
+
+        let kind = match kind {
+            PslDeclarationKind::Property => NamedEntityKind::Property,
+            PslDeclarationKind::Sequence => NamedEntityKind::Sequence,
+        };
+        region.add(ident.clone(), kind, diagnostics);
+        Ok(())
+    }
+
+    pub fn analyze_psl_expression(
+        &self,
+        region: &Region<'_>,
+        expr: &mut WithPos<PslExpression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        match expr.item {
+            PslExpression::Boolean(ref mut expr) => {
+                self.analyze_psl_boolean(region, expr, diagnostics)?;
+            }
+            PslExpression::Sequence(ref mut sere) => {
+                self.analyze_psl_expression(region, sere, diagnostics)?;
+            }
+            PslExpression::Unary(ref mut unary) => {
+                let PslUnaryExpression {
+                    op: _,
+                    strong: _,
+                    event,
+                    count,
+                    operand,
+                } = unary.as_mut();
+                if let Some(event) = event {
+                    self.analyze_psl_builtins(region, &event.pos, &mut event.item, diagnostics)?;
+                }
+                if let Some(count) = count {
+                    self.analyze_psl_count(region, count, diagnostics)?;
+                }
+                self.analyze_psl_expression(region, operand, diagnostics)?;
+            }
+            PslExpression::Binary(ref mut binary) => {
+                let PslBinaryExpression {
+                    op: _,
+                    strong: _,
+                    left,
+                    right,
+                } = binary.as_mut();
+                self.analyze_psl_expression(region, left, diagnostics)?;
+                self.analyze_psl_expression(region, right, diagnostics)?;
+            }
+            PslExpression::Repetition(ref mut repetition) => {
+                let PslRepetition {
+                    kind: _,
+                    count,
+                    operand,
+                } = repetition.as_mut();
+                if let Some(operand) = operand {
+                    self.analyze_psl_expression(region, operand, diagnostics)?;
+                }
+                if let Some(count) = count {
+                    self.analyze_psl_count(region, count, diagnostics)?;
+                }
+            }
+            PslExpression::Clocked(ref mut expr, ref mut clock) => {
+                self.analyze_psl_expression(region, expr, diagnostics)?;
+                self.analyze_expression(region, clock, diagnostics)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn analyze_psl_count(
+        &self,
+        region: &Region<'_>,
+        count: &mut PslCount,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        match count {
+            PslCount::Number(ref mut expr) => self.analyze_expression(region, expr, diagnostics),
+            PslCount::Range(ref mut low, ref mut high) => {
+                self.analyze_expression(region, low, diagnostics)?;
+                if let Some(high) = high {
+                    self.analyze_expression(region, high, diagnostics)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// A VHDL boolean within a PSL expression
+    /// which may also be an instance of a named property or sequence
+    fn analyze_psl_boolean(
+        &self,
+        region: &Region<'_>,
+        expr: &mut WithPos<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        if let Expression::Name(ref mut name) = expr.item {
+            if let Name::FunctionCall(ref mut fcall) = name.as_mut() {
+                if let Name::Designator(ref mut designator) = fcall.name.item {
+                    if let Ok(NamedEntities::Single(ent)) =
+                        region.lookup_within(&fcall.name.pos, &designator.item)
+                    {
+                        if matches!(
+                            ent.kind(),
+                            NamedEntityKind::Property | NamedEntityKind::Sequence
+                        ) {
+                            designator.set_unique_reference(&ent);
+                            return self.analyze_assoc_elems(
+                                region,
+                                &mut fcall.parameters,
+                                diagnostics,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        self.analyze_psl_builtins(region, &expr.pos, &mut expr.item, diagnostics)
+    }
+
+    /// A VHDL expression within a PSL boolean where the PSL built-in functions are recognized,
+    /// only the arguments of the built-in functions are analyzed
+    fn analyze_psl_builtins(
+        &self,
+        region: &Region<'_>,
+        pos: &SrcPos,
+        expr: &mut Expression,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalNullResult {
+        match expr {
+            Expression::Binary(_, ref mut left, ref mut right) => {
+                self.analyze_psl_builtins(region, &left.pos, &mut left.item, diagnostics)?;
+                self.analyze_psl_builtins(region, &right.pos, &mut right.item, diagnostics)
+            }
+            Expression::Unary(_, ref mut inner) => {
+                self.analyze_psl_builtins(region, &inner.pos, &mut inner.item, diagnostics)
+            }
+            Expression::Name(ref mut name) if is_builtin_function(name) => {
+                if let Name::FunctionCall(ref mut fcall) = name.as_mut() {
+                    for AssociationElement { actual, .. } in fcall.parameters.iter_mut() {
+                        if let ActualPart::Expression(ref mut expr) = actual.item {
+                            self.analyze_psl_builtins(region, &actual.pos, expr, diagnostics)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            _ => self.analyze_expression_pos(region, pos, expr, diagnostics),
+        }
+    }
+}
@@ -92,7 +92,8 @@ impl OverloadedName {
                         ent.designator(),
                         ent.signature().unwrap().describe()
                     ),
-                );
+                )
+                .with_code(ErrorCode::DuplicateDeclaration);
                 if let Some(old_pos) = old_ent.decl_pos() {
                     diagnostic.add_related(old_pos, "Previously defined here");
                 }
@@ -186,6 +187,21 @@ impl NamedEntities {
     }
 }
 
+/// LRM 7.2 Attribute specification
+/// A user-defined attribute which has been specified for named entities of the region
+#[derive(Clone)]
+pub struct SpecifiedAttribute {
+    pub attribute: EntityId,
+    pub target: SpecifiedFor,
+}
+
+#[derive(Clone)]
+pub enum SpecifiedFor {
+    Entity(EntityId),
+    // Attribute specifications using others or all
+    Class(EntityClass),
+}
+
 #[derive(Copy, Clone, PartialEq)]
 enum RegionKind {
     PackageDeclaration,
@@ -205,7 +221,10 @@ pub struct Region<'a> {
     visibility: Visibility,
     entities: FnvHashMap<Designator, NamedEntities>,
     protected_bodies: FnvHashMap<Symbol, SrcPos>,
+    attribute_specifications: Vec<SpecifiedAttribute>,
     kind: RegionKind,
+    // VHDL-2019 package region including the private declarations which are only visible in the body
+    private: Option<Box<Region<'static>>>,
 }
 
 impl<'a> Region<'a> {
@@ -215,7 +234,9 @@ impl<'a> Region<'a> {
             visibility: Visibility::default(),
             entities: FnvHashMap::default(),
             protected_bodies: FnvHashMap::default(),
+            attribute_specifications: Vec::new(),
             kind: RegionKind::Other,
+            private: None,
         }
     }
 
@@ -236,7 +257,18 @@ impl<'a> Region<'a> {
             visibility: self.visibility,
             entities: self.entities,
             protected_bodies: self.protected_bodies,
+            attribute_specifications: self.attribute_specifications,
             kind: self.kind,
+            private: self.private,
+        }
+    }
+
+    /// Keep the full region including private declarations for the package body
+    /// while only the public declarations are visible from outside
+    pub fn with_private(self, full_region: Region<'_>) -> Region<'a> {
+        Region {
+            private: Some(Box::new(full_region.without_parent())),
+            ..self
         }
     }
 
@@ -255,12 +287,20 @@ impl<'a> Region<'a> {
             "Parent of extended region must be the same as the parent"
         );
 
+        // The package body also sees the private declarations
+        let region: &Region<'_> = match region.private {
+            Some(ref private) => private,
+            None => region,
+        };
+
         Region {
             parent,
             visibility: region.visibility.clone(),
             entities: region.entities.clone(),
             protected_bodies: region.protected_bodies.clone(),
+            attribute_specifications: region.attribute_specifications.clone(),
             kind,
+            private: None,
         }
     }
 
@@ -270,7 +310,7 @@ impl<'a> Region<'a> {
             RegionKind::PackageDeclaration | RegionKind::PackageBody => {
                 for ent in self.entities.values() {
                     if let NamedEntityKind::DeferredConstant(..) = ent.first_kind() {
-                        ent.first().error(diagnostics, format!("Deferred constant '{}' lacks corresponding full constant declaration in package body", ent.designator()));
+                        ent.first().error(diagnostics, format!("Deferred constant '{}' lacks corresponding full constant declaration in package body", ent.designator()), ErrorCode::MissingDeferredConstant);
                     }
                 }
             }
@@ -294,6 +334,7 @@ impl<'a> Region<'a> {
                 ent.first().error(
                     diagnostics,
                     format!("Missing body for protected type '{}'", ent.designator()),
+                    ErrorCode::MissingBody,
                 );
             }
         }
@@ -312,6 +353,35 @@ impl<'a> Region<'a> {
         }
     }
 
+    pub fn add_attribute_specification(&mut self, attribute: &NamedEntity, target: SpecifiedFor) {
+        self.attribute_specifications.push(SpecifiedAttribute {
+            attribute: attribute.id(),
+            target,
+        });
+    }
+
+    /// Find the region where the named entity is declared and return its attribute specifications
+    /// Returns None when the named entity is not declared within this region or any of its parents
+    pub fn lookup_attribute_specifications(
+        &self,
+        ent: &NamedEntity,
+    ) -> Option<&[SpecifiedAttribute]> {
+        let is_declared_here = match self.entities.get(ent.designator()) {
+            Some(NamedEntities::Single(declared)) => declared.id() == ent.id(),
+            Some(NamedEntities::Overloaded(overloaded)) => overloaded
+                .entities()
+                .any(|declared| declared.id() == ent.id()),
+            None => false,
+        };
+
+        if is_declared_here {
+            Some(&self.attribute_specifications)
+        } else {
+            self.parent
+                .and_then(|parent| parent.lookup_attribute_specifications(ent))
+        }
+    }
+
     pub fn add_named_entity(
         &mut self,
         ent: Arc<NamedEntity>,
@@ -321,6 +391,7 @@ impl<'a> Region<'a> {
             ent.error(
                 diagnostics,
                 "Deferred constants are only allowed in package declarations (not body)",
+                ErrorCode::IllegalDeferredConstant,
             );
             return;
         };
@@ -344,6 +415,7 @@ impl<'a> Region<'a> {
                                 ent.error(
                                     diagnostics,
                                     "Full declaration of deferred constant is only allowed in a package body",
+                                    ErrorCode::IllegalDeferredConstant,
                                 );
                             }
                         } else if let Some(pos) = ent.decl_pos() {
@@ -521,10 +593,10 @@ impl<'a> Region<'a> {
 
         match result {
             Some(visible) => Ok(visible),
-            None => Err(Diagnostic::error(
-                pos,
-                format!("No declaration of '{}'", designator),
-            )),
+            None => Err(
+                Diagnostic::error(pos, format!("No declaration of '{}'", designator))
+                    .with_code(ErrorCode::Unresolved),
+            ),
         }
     }
 }
@@ -583,7 +655,8 @@ pub(super) fn duplicate_error(
     pos: &SrcPos,
     prev_pos: Option<&SrcPos>,
 ) -> Diagnostic {
-    let mut diagnostic = Diagnostic::error(pos, format!("Duplicate declaration of '{}'", name));
+    let mut diagnostic = Diagnostic::error(pos, format!("Duplicate declaration of '{}'", name))
+        .with_code(ErrorCode::DuplicateDeclaration);
 
     if let Some(prev_pos) = prev_pos {
         diagnostic.add_related(prev_pos, "Previously defined here");
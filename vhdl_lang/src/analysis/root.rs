@@ -10,9 +10,11 @@ use std::collections::hash_map::Entry;
 use super::analyze::*;
 use super::lock::*;
 use super::region::*;
+use super::schedule::analysis_waves;
 use crate::ast::search::*;
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::Symbols;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -32,9 +34,15 @@ pub(super) type UnitReadGuard<'a> = ReadGuard<'a, AnyDesignUnit, AnalysisData>;
 
 /// Wraps the AST of a [design unit](../../ast/enum.AnyDesignUnit.html) in a thread-safe
 /// r/w-lock for analysis.
+///
+/// Units are shared between copies of a [`DesignRoot`](struct.DesignRoot.html), a shared unit
+/// is replaced by an unanalyzed copy instead of being reset.
 pub(super) struct LockedUnit {
     ident: Ident,
     unit_id: UnitId,
+    standard: VHDLStandard,
+    // Hash of the source text around the design unit
+    text_hash: u64,
     pub unit: AnalysisLock<AnyDesignUnit, AnalysisData>,
 }
 
@@ -45,7 +53,12 @@ impl HasUnitId for LockedUnit {
 }
 
 impl LockedUnit {
-    fn new(library_name: &Symbol, unit: AnyDesignUnit) -> LockedUnit {
+    fn new(
+        library_name: &Symbol,
+        unit: AnyDesignUnit,
+        standard: VHDLStandard,
+        text_hash: u64,
+    ) -> LockedUnit {
         let unit_id = match unit {
             AnyDesignUnit::Primary(ref unit) => {
                 UnitId::primary(library_name, PrimaryKind::kind_of(unit), unit.name())
@@ -61,9 +74,36 @@ impl LockedUnit {
         LockedUnit {
             ident: unit.ident().clone(),
             unit_id,
+            standard,
+            text_hash,
             unit: AnalysisLock::new(unit),
         }
     }
+
+    /// A copy of the design unit without analysis result
+    fn unanalyzed_copy(&self) -> LockedUnit {
+        LockedUnit {
+            ident: self.ident.clone(),
+            unit_id: self.unit_id.clone(),
+            standard: self.standard,
+            text_hash: self.text_hash,
+            unit: AnalysisLock::new(self.unit.read().clone()),
+        }
+    }
+
+    /// True if the design unit was parsed from the same text at the same position
+    /// such that the analysis result of this unit is valid for the other
+    fn has_same_text(&self, other: &LockedUnit) -> bool {
+        self.unit_id == other.unit_id
+            && self.source() == other.source()
+            && self.standard == other.standard
+            && self.text_hash == other.text_hash
+    }
+
+    /// The VHDL standard the design unit was parsed with
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
 }
 
 impl HasIdent for LockedUnit {
@@ -75,13 +115,14 @@ impl HasIdent for LockedUnit {
 /// Represents a VHDL library containing zero or more design units.
 ///
 /// This struct also keeps track of which source file contained which design units.
+#[derive(Clone)]
 struct Library {
     name: Symbol,
 
     /// Named entity corresponding to the library.
     ent: Arc<NamedEntity>,
 
-    units: FnvHashMap<UnitKey, LockedUnit>,
+    units: FnvHashMap<UnitKey, Arc<LockedUnit>>,
     units_by_source: FnvHashMap<Source, FnvHashSet<UnitId>>,
 
     /// Units removed since last analysis.
@@ -91,7 +132,11 @@ struct Library {
 
     /// Design units which were not added since they were duplicates.
     /// They need to be kept for later refresh which might make them not duplicates.
-    duplicates: Vec<(SrcPos, LockedUnit)>,
+    duplicates: Vec<(SrcPos, Arc<LockedUnit>)>,
+
+    /// Units removed since last analysis which are reused with their analysis result
+    /// if the same source adds them again with unchanged text.
+    reusable: FnvHashMap<UnitId, Arc<LockedUnit>>,
 }
 
 impl Library {
@@ -109,6 +154,7 @@ impl Library {
             added: FnvHashSet::default(),
             removed: FnvHashSet::default(),
             duplicates: Vec::new(),
+            reusable: FnvHashMap::default(),
         }
     }
 
@@ -116,7 +162,7 @@ impl Library {
         &self.name
     }
 
-    fn add_design_unit(&mut self, unit: LockedUnit) {
+    fn add_design_unit(&mut self, unit: Arc<LockedUnit>) {
         let unit_id = unit.unit_id().clone();
         match self.units.entry(unit.key().clone()) {
             Entry::Occupied(entry) => {
@@ -124,7 +170,17 @@ impl Library {
                     .push((entry.get().ident().pos.clone(), unit));
             }
             Entry::Vacant(entry) => {
-                self.added.insert(unit_id);
+                let unit = match self.reusable.remove(&unit_id) {
+                    Some(old_unit) if old_unit.has_same_text(&unit) => {
+                        // The unit is unchanged, keep the analysis result
+                        self.removed.remove(&unit_id);
+                        old_unit
+                    }
+                    _ => {
+                        self.added.insert(unit_id);
+                        unit
+                    }
+                };
                 match self.units_by_source.entry(unit.source().clone()) {
                     Entry::Occupied(mut entry) => {
                         entry.get_mut().insert(unit.unit_id().clone());
@@ -140,14 +196,22 @@ impl Library {
         }
     }
 
-    fn add_design_file(&mut self, design_file: DesignFile) {
-        for design_unit in design_file.design_units {
-            self.add_design_unit(LockedUnit::new(self.name(), design_unit));
+    fn add_design_file(&mut self, design_file: DesignFile, standard: VHDLStandard) {
+        let text_hashes = text_hashes(&design_file);
+        for (design_unit, text_hash) in design_file.design_units.into_iter().zip(text_hashes) {
+            self.add_design_unit(Arc::new(LockedUnit::new(
+                self.name(),
+                design_unit,
+                standard,
+                text_hash,
+            )));
         }
     }
 
     /// Refresh library after removing or adding new design units.
     fn refresh(&mut self, diagnostics: &mut dyn DiagnosticHandler) {
+        // Units which were not added again are removed
+        self.reusable.clear();
         self.append_duplicate_diagnostics(diagnostics);
     }
 
@@ -179,7 +243,9 @@ impl Library {
                 },
             };
 
-            let diagnostic = diagnostic.related(prev_pos, "Previously defined here");
+            let diagnostic = diagnostic
+                .related(prev_pos, "Previously defined here")
+                .with_code(ErrorCode::DuplicateDeclaration);
             diagnostics.push(diagnostic);
         }
     }
@@ -187,15 +253,18 @@ impl Library {
     /// Remove all design units defined in source.
     /// This is used for incremental analysis where only a single source file is updated.
     fn remove_source(&mut self, source: &Source) {
-        let removed = &mut self.removed;
-        self.units.retain(|_, value| {
-            if value.source() != source {
-                true
-            } else {
-                removed.insert(value.unit_id().clone());
-                false
+        let keys: Vec<UnitKey> = self
+            .units
+            .iter()
+            .filter(|(_, value)| value.source() == source)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            if let Some(unit) = self.units.remove(&key) {
+                self.removed.insert(unit.unit_id().clone());
+                self.reusable.insert(unit.unit_id().clone(), unit);
             }
-        });
+        }
         self.units_by_source.remove(source);
         self.duplicates
             .retain(|(_, value)| value.source() != source);
@@ -256,6 +325,21 @@ pub struct DesignRoot {
     users_of_library_all: RwLock<FnvHashMap<Symbol, FnvHashSet<UnitId>>>,
 }
 
+/// Cloning shares the design units together with their analysis results,
+/// the copy can be read while the original is analyzed again since units
+/// which need to be analyzed again are replaced instead of reset
+impl Clone for DesignRoot {
+    fn clone(&self) -> DesignRoot {
+        DesignRoot {
+            symbols: self.symbols.clone(),
+            libraries: self.libraries.clone(),
+            users_of: RwLock::new(self.users_of.read().clone()),
+            missing_primary: RwLock::new(self.missing_primary.read().clone()),
+            users_of_library_all: RwLock::new(self.users_of_library_all.read().clone()),
+        }
+    }
+}
+
 impl DesignRoot {
     pub fn new(symbols: Arc<Symbols>) -> DesignRoot {
         DesignRoot {
@@ -285,7 +369,7 @@ impl DesignRoot {
     pub(super) fn get_library_units(
         &self,
         library_name: &Symbol,
-    ) -> Option<&FnvHashMap<UnitKey, LockedUnit>> {
+    ) -> Option<&FnvHashMap<UnitKey, Arc<LockedUnit>>> {
         self.libraries
             .get(library_name)
             .map(|library| &library.units)
@@ -296,11 +380,19 @@ impl DesignRoot {
         self.libraries.get(library_name).map(|library| &library.ent)
     }
 
-    pub fn add_design_file(&mut self, library_name: Symbol, design_file: DesignFile) {
+    pub fn add_design_file(
+        &mut self,
+        library_name: Symbol,
+        design_file: DesignFile,
+        standard: VHDLStandard,
+    ) {
         self.get_or_create_library(library_name)
-            .add_design_file(design_file);
+            .add_design_file(design_file, standard);
     }
 
+    /// Remove the design units of a source
+    /// The units are kept until the next analysis such that units which are added again
+    /// by the same source with unchanged text are reused together with their analysis result
     pub fn remove_source(&mut self, library_name: Symbol, source: &Source) {
         self.get_or_create_library(library_name)
             .remove_source(source);
@@ -334,7 +426,8 @@ impl DesignRoot {
     pub(super) fn get_analysis<'a>(&self, locked_unit: &'a LockedUnit) -> UnitReadGuard<'a> {
         match locked_unit.unit.entry() {
             AnalysisEntry::Vacant(mut unit) => {
-                let context = AnalyzeContext::new(self, locked_unit.unit_id());
+                let context =
+                    AnalyzeContext::new(self, locked_unit.unit_id(), locked_unit.standard());
 
                 let entity_id = super::named_entity::new_id();
                 let mut diagnostics = Vec::new();
@@ -373,6 +466,9 @@ impl DesignRoot {
                             NamedEntityKind::PackageInstance(region)
                         }
                         AnyPrimaryUnit::Context(..) => NamedEntityKind::Context(region),
+                        AnyPrimaryUnit::VerificationUnit(..) => {
+                            NamedEntityKind::VerificationUnit(region)
+                        }
                     };
 
                     Some(Arc::new(NamedEntity::new_with_id(
@@ -399,17 +495,21 @@ impl DesignRoot {
         }
     }
 
-    fn get_unit<'a>(&'a self, unit_id: &UnitId) -> Option<&'a LockedUnit> {
+    fn get_unit_mut<'a>(&'a mut self, unit_id: &UnitId) -> Option<&'a mut Arc<LockedUnit>> {
         self.libraries
-            .get(unit_id.library_name())
-            .and_then(|library| library.units.get(unit_id.key()))
+            .get_mut(unit_id.library_name())
+            .and_then(|library| library.units.get_mut(unit_id.key()))
     }
 
-    fn reset_affected(&self, mut affected: FnvHashSet<UnitId>) {
+    fn reset_affected(&mut self, mut affected: FnvHashSet<UnitId>) {
         // Reset analysis state of all design units
         for unit_id in affected.drain() {
-            if let Some(unit) = self.get_unit(&unit_id) {
-                unit.unit.reset();
+            if let Some(unit) = self.get_unit_mut(&unit_id) {
+                match Arc::get_mut(unit) {
+                    Some(unit) => unit.unit.reset(),
+                    // The unit is shared with a copy of the design root which keeps its result
+                    None => *unit = Arc::new(unit.unanalyzed_copy()),
+                }
             }
         }
     }
@@ -538,10 +638,11 @@ impl DesignRoot {
             }
         }
 
-        self.reset_affected(get_all_affected(&users_of, affected));
+        let all_affected = get_all_affected(&users_of, affected);
         drop(users_of);
         drop(users_of_library_all);
         drop(missing_primary);
+        self.reset_affected(all_affected);
 
         let mut users_of = self.users_of.write();
         let mut users_of_library_all = self.users_of_library_all.write();
@@ -563,26 +664,36 @@ impl DesignRoot {
         }
     }
 
-    pub fn analyze(&mut self, diagnostics: &mut dyn DiagnosticHandler) {
+    pub fn analyze(
+        &mut self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        progress: &mut dyn ProgressHandler,
+    ) {
         self.reset();
 
         for library in self.libraries.values_mut() {
             library.refresh(diagnostics);
         }
 
-        use rayon::prelude::*;
-        // @TODO run in parallel
         let mut units: Vec<_> = Vec::new();
         for library in self.libraries.values() {
             for unit in library.units.values() {
-                units.push(unit);
+                units.push(unit.as_ref());
             }
         }
 
-        // @TODO compute the best order to process the units in parallel
-        units.par_iter().for_each(|unit| {
-            self.get_analysis(*unit);
-        });
+        let total = units.len();
+        let mut done = 0;
+        progress.begin(ProgressPhase::Analyze, total);
+        for wave in analysis_waves(&self.symbols, units) {
+            use rayon::prelude::*;
+            wave.par_iter().for_each(|unit| {
+                self.get_analysis(unit);
+            });
+            done += wave.len();
+            progress.report(ProgressPhase::Analyze, done, total);
+        }
+        progress.end(ProgressPhase::Analyze);
 
         // Emit diagnostics sorted within a file
         for library in self.libraries.values() {
@@ -594,6 +705,47 @@ impl DesignRoot {
     }
 }
 
+/// Hash the source text of each design unit of a file together with its position.
+///
+/// The text of a unit starts where the previous unit ends such that it also covers the
+/// context clause and the comments before the unit. Edits within one unit then only change
+/// the hash of that unit, and of the later units when the edit moves them.
+/// The whole file is hashed for units without a known range.
+fn text_hashes(design_file: &DesignFile) -> Vec<u64> {
+    use std::hash::{Hash, Hasher};
+
+    let source = match design_file.design_units.first() {
+        Some(unit) => unit.ident().pos.source.clone(),
+        None => return Vec::new(),
+    };
+    let contents = source.contents();
+
+    (0..design_file.design_units.len())
+        .map(|idx| {
+            let mut hasher = fnv::FnvHasher::default();
+            match design_file.unit_ranges.get(idx) {
+                Some(range) => {
+                    range.start.hash(&mut hasher);
+                    let mut reader = ContentReader::new(&contents);
+                    reader.seek_pos(range.start);
+                    while reader.pos() < range.end {
+                        match reader.pop_char() {
+                            Some(chr) => chr.hash(&mut hasher),
+                            None => break,
+                        }
+                    }
+                }
+                None => {
+                    for lineno in 0..contents.num_lines() {
+                        contents.get_line(lineno).hash(&mut hasher);
+                    }
+                }
+            }
+            hasher.finish()
+        })
+        .collect()
+}
+
 fn get_all_affected(
     users_of: &FnvHashMap<UnitId, FnvHashSet<UnitId>>,
     mut affected: FnvHashSet<UnitId>,
@@ -641,12 +793,15 @@ impl Search for Library {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::syntax::test::{check_diagnostics, Code};
+    use crate::analysis::tests::add_standard_library;
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics, Code};
+    use crate::syntax::VHDLParser;
+    use std::path::Path;
 
     fn new_library_with_diagnostics(code: &Code, name: &str) -> (Library, Vec<Diagnostic>) {
         let mut diagnostics = Vec::new();
         let mut library = Library::new(code.symbol(name));
-        library.add_design_file(code.design_file());
+        library.add_design_file(code.design_file(), code.standard);
         library.refresh(&mut diagnostics);
         (library, diagnostics)
     }
@@ -791,4 +946,239 @@ end configuration;
         assert_eq!(library.units.len(), 2);
         assert_eq!(library.duplicates.len(), 1);
     }
+
+    #[test]
+    fn unchanged_units_are_reused_when_source_is_updated() {
+        let parser = VHDLParser::default();
+        let mut root = DesignRoot::new(parser.symbols.clone());
+        add_standard_library(parser.symbols.clone(), &mut root);
+        let libname = root.symbol_utf8("libname");
+        let pkg = root.symbol_utf8("pkg");
+        let other_id = UnitId::package(&libname, &root.symbol_utf8("other"));
+        let package_id = UnitId::package(&libname, &pkg);
+        let body_id = UnitId::secondary(&libname, SecondaryKind::PackageBody, &pkg, &pkg);
+        let user_id = UnitId::package(&libname, &root.symbol_utf8("user"));
+
+        let source = Source::inline(
+            Path::new("pkg.vhd"),
+            "
+package other is
+  type t is range 0 to 1;
+end package;
+
+package pkg is
+  type t is range 0 to 1;
+end package;
+
+package body pkg is
+end package body;
+
+use work.pkg.all;
+package user is
+  constant c : t := 0;
+end package;
+",
+        );
+
+        let update = |root: &mut DesignRoot, range: crate::data::Range, text: &str| {
+            source.change(Some(&range), text);
+            root.remove_source(libname.clone(), &source);
+            let parsed =
+                parser.parse_design_source(&source, VHDLStandard::default(), &mut Vec::new());
+            root.add_design_file(libname.clone(), parsed.design_file, VHDLStandard::default());
+        };
+
+        let insert_at_line =
+            |line| crate::data::Range::new(Position::new(line, 0), Position::new(line, 0));
+
+        // The region of the analysis result is created again each time a unit is analyzed
+        let region_of = |root: &DesignRoot, unit_id: &UnitId| {
+            root.libraries
+                .get(&libname)
+                .unwrap()
+                .units
+                .get(unit_id.key())
+                .unwrap()
+                .unit
+                .expect_analyzed()
+                .result()
+                .region
+                .clone()
+        };
+
+        update(&mut root, insert_at_line(0), "");
+        let mut diagnostics = Vec::new();
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
+        check_no_diagnostics(&diagnostics);
+
+        // Add lines within the package body
+        update(
+            &mut root,
+            insert_at_line(10),
+            "  type t2 is range 0 to 1;\n",
+        );
+        let library = root.libraries.get(&libname).unwrap();
+        assert!(!library.added.contains(&package_id));
+        assert!(!library.removed.contains(&package_id));
+        assert!(library.added.contains(&body_id));
+        library
+            .units
+            .get(package_id.key())
+            .unwrap()
+            .unit
+            .expect_analyzed();
+        let mut diagnostics = Vec::new();
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
+        check_no_diagnostics(&diagnostics);
+
+        let other_region = region_of(&root, &other_id);
+        let package_region = region_of(&root, &package_id);
+        let body_region = region_of(&root, &body_id);
+        let user_region = region_of(&root, &user_id);
+
+        // Change the package
+        update(&mut root, insert_at_line(7), "  type t3 is range 0 to 1;\n");
+        let library = root.libraries.get(&libname).unwrap();
+        assert!(!library.added.contains(&other_id));
+        assert!(library.added.contains(&package_id));
+        assert!(library.added.contains(&body_id));
+        let mut diagnostics = Vec::new();
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
+        check_no_diagnostics(&diagnostics);
+
+        // The unchanged unit keeps its result while the package and its users are analyzed again
+        assert!(Arc::ptr_eq(&other_region, &region_of(&root, &other_id)));
+        assert!(!Arc::ptr_eq(
+            &package_region,
+            &region_of(&root, &package_id)
+        ));
+        assert!(!Arc::ptr_eq(&body_region, &region_of(&root, &body_id)));
+        assert!(!Arc::ptr_eq(&user_region, &region_of(&root, &user_id)));
+    }
+
+    #[test]
+    fn independent_unit_after_edited_unit_is_reused() {
+        let parser = VHDLParser::default();
+        let mut root = DesignRoot::new(parser.symbols.clone());
+        add_standard_library(parser.symbols.clone(), &mut root);
+        let libname = root.symbol_utf8("libname");
+        let first_id = UnitId::package(&libname, &root.symbol_utf8("first"));
+        let second_id = UnitId::package(&libname, &root.symbol_utf8("second"));
+
+        let source = Source::inline(
+            Path::new("pkg.vhd"),
+            "
+package first is
+  constant c : natural := 0;
+end package;
+
+-- Comment before the second package
+package second is
+  constant c : natural := 0;
+end package;
+",
+        );
+
+        let update = |root: &mut DesignRoot| {
+            root.remove_source(libname.clone(), &source);
+            let parsed =
+                parser.parse_design_source(&source, VHDLStandard::default(), &mut Vec::new());
+            root.add_design_file(libname.clone(), parsed.design_file, VHDLStandard::default());
+        };
+
+        update(&mut root);
+        let mut diagnostics = Vec::new();
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
+        check_no_diagnostics(&diagnostics);
+
+        // Change the value of the constant in the first package without moving the second one
+        let range = crate::data::Range::new(Position::new(2, 26), Position::new(2, 27));
+        source.change(Some(&range), "1");
+        update(&mut root);
+
+        let library = root.libraries.get(&libname).unwrap();
+        assert!(library.added.contains(&first_id));
+        assert!(!library.added.contains(&second_id));
+        assert!(!library.removed.contains(&second_id));
+        library
+            .units
+            .get(second_id.key())
+            .unwrap()
+            .unit
+            .expect_analyzed();
+    }
+
+    #[test]
+    fn copy_shares_units_and_keeps_results_when_original_is_analyzed_again() {
+        let parser = VHDLParser::default();
+        let mut root = DesignRoot::new(parser.symbols.clone());
+        add_standard_library(parser.symbols.clone(), &mut root);
+        let libname = root.symbol_utf8("libname");
+        let other_id = UnitId::package(&libname, &root.symbol_utf8("other"));
+        let package_id = UnitId::package(&libname, &root.symbol_utf8("pkg"));
+
+        let source = Source::inline(
+            Path::new("pkg.vhd"),
+            "
+package other is
+end package;
+
+package pkg is
+end package;
+",
+        );
+
+        let update = |root: &mut DesignRoot| {
+            root.remove_source(libname.clone(), &source);
+            let parsed =
+                parser.parse_design_source(&source, VHDLStandard::default(), &mut Vec::new());
+            root.add_design_file(libname.clone(), parsed.design_file, VHDLStandard::default());
+        };
+
+        let unit_of = |root: &DesignRoot, unit_id: &UnitId| {
+            root.libraries
+                .get(&libname)
+                .unwrap()
+                .units
+                .get(unit_id.key())
+                .unwrap()
+                .clone()
+        };
+
+        update(&mut root);
+        let mut diagnostics = Vec::new();
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
+        check_no_diagnostics(&diagnostics);
+
+        let copy = root.clone();
+        assert!(Arc::ptr_eq(
+            &unit_of(&root, &package_id),
+            &unit_of(&copy, &package_id)
+        ));
+
+        // Change the package
+        let range = crate::data::Range::new(Position::new(5, 0), Position::new(5, 0));
+        source.change(Some(&range), "  type t is range 0 to 1;\n");
+        update(&mut root);
+        let mut diagnostics = Vec::new();
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
+        check_no_diagnostics(&diagnostics);
+
+        // The unchanged unit is still shared while the copy keeps the old result of the package
+        assert!(Arc::ptr_eq(
+            &unit_of(&root, &other_id),
+            &unit_of(&copy, &other_id)
+        ));
+        assert!(!Arc::ptr_eq(
+            &unit_of(&root, &package_id),
+            &unit_of(&copy, &package_id)
+        ));
+        let region = |unit: Arc<LockedUnit>| unit.unit.expect_analyzed().result().region.clone();
+        assert!(region(unit_of(&root, &package_id))
+            .lookup_immediate(&Designator::Identifier(root.symbol_utf8("t")))
+            .is_some());
+        assert!(region(unit_of(&copy, &package_id))
+            .lookup_immediate(&Designator::Identifier(root.symbol_utf8("t")))
+            .is_none());
+    }
 }
@@ -0,0 +1,243 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Computes the order in which design units are analyzed in parallel.
+//!
+//! Analyzing a design unit blocks on the analysis lock of every design unit it depends on.
+//! When all units are analyzed in parallel without any order most threads spend their time
+//! waiting for the same few packages. The dependencies given by the context clauses are known
+//! before analysis so the units are grouped into waves where each unit only depends on units
+//! of earlier waves. Dependencies which are only found during analysis, such as entity
+//! instantiations, are still handled by the analysis lock.
+
+use super::root::LockedUnit;
+use crate::ast::*;
+use crate::data::*;
+use crate::syntax::Symbols;
+use fnv::{FnvHashMap, FnvHashSet};
+
+pub(super) fn analysis_waves<'a>(
+    symbols: &Symbols,
+    units: Vec<&'a LockedUnit>,
+) -> Vec<Vec<&'a LockedUnit>> {
+    let work = symbols.symtab().insert_utf8("work");
+    // All design units implicitly use std.standard
+    let std_standard = (
+        symbols.symtab().insert_utf8("std"),
+        symbols.symtab().insert_utf8("standard"),
+    );
+
+    let mut primary_units = FnvHashMap::default();
+    for (idx, unit) in units.iter().enumerate() {
+        if let UnitKey::Primary(ref name) = unit.key() {
+            primary_units.insert((unit.unit_id().library_name().clone(), name.clone()), idx);
+        }
+    }
+
+    let mut users: Vec<Vec<usize>> = vec![Vec::new(); units.len()];
+    let mut num_dependencies = vec![0; units.len()];
+
+    for (idx, unit) in units.iter().enumerate() {
+        let library_name = unit.unit_id().library_name();
+        let mut dependencies = unit_dependencies(&unit.unit.read(), library_name, &work);
+        dependencies.push(std_standard.clone());
+
+        let dependencies: FnvHashSet<usize> = dependencies
+            .iter()
+            .filter_map(|key| primary_units.get(key).cloned())
+            .filter(|dependency| *dependency != idx)
+            .collect();
+
+        num_dependencies[idx] = dependencies.len();
+        for dependency in dependencies {
+            users[dependency].push(idx);
+        }
+    }
+
+    let mut waves = Vec::new();
+    let mut is_scheduled = vec![false; units.len()];
+    let mut ready: Vec<usize> = (0..units.len())
+        .filter(|idx| num_dependencies[*idx] == 0)
+        .collect();
+
+    while !ready.is_empty() {
+        let mut next_ready = Vec::new();
+        for idx in ready.iter() {
+            is_scheduled[*idx] = true;
+            for user in users[*idx].iter() {
+                num_dependencies[*user] -= 1;
+                if num_dependencies[*user] == 0 {
+                    next_ready.push(*user);
+                }
+            }
+        }
+        waves.push(ready.iter().map(|idx| units[*idx]).collect());
+        ready = next_ready;
+    }
+
+    // Units with circular dependencies are analyzed last where the analysis reports the error
+    let remaining: Vec<_> = (0..units.len())
+        .filter(|idx| !is_scheduled[*idx])
+        .map(|idx| units[idx])
+        .collect();
+    if !remaining.is_empty() {
+        waves.push(remaining);
+    }
+
+    waves
+}
+
+/// The (library name, primary unit name) of the units which a design unit depends on
+fn unit_dependencies(
+    unit: &AnyDesignUnit,
+    library_name: &Symbol,
+    work: &Symbol,
+) -> Vec<(Symbol, Symbol)> {
+    let mut dependencies = Vec::new();
+
+    for context_item in unit.context_clause().iter() {
+        let name_list = match context_item.item {
+            ContextItem::Use(ref use_clause) => &use_clause.name_list,
+            ContextItem::Context(ref context_reference) => &context_reference.name_list,
+            ContextItem::Library(..) => continue,
+        };
+
+        for name in name_list.iter() {
+            let mut prefix = Vec::new();
+            name_prefix(&name.item, &mut prefix);
+            // A single name refers to something made visible by an earlier use clause
+            if prefix.len() >= 2 {
+                dependencies.push(library_unit(library_name, work, &prefix));
+            }
+        }
+    }
+
+    let unit_name = match unit {
+        AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(ref unit)) => Some(&unit.entity_name),
+        AnyDesignUnit::Primary(AnyPrimaryUnit::PackageInstance(ref unit)) => {
+            Some(&unit.package_name)
+        }
+        _ => None,
+    };
+
+    if let Some(unit_name) = unit_name {
+        let mut prefix = Vec::new();
+        selected_name_prefix(&unit_name.item, &mut prefix);
+        if prefix.len() == 1 {
+            dependencies.push((library_name.clone(), prefix[0].clone()));
+        } else if prefix.len() >= 2 {
+            dependencies.push(library_unit(library_name, work, &prefix));
+        }
+    }
+
+    if let AnyDesignUnit::Secondary(ref unit) = unit {
+        dependencies.push((library_name.clone(), unit.primary_name().clone()));
+    }
+
+    // Verification units depend on the bound entity and the inherited units
+    if let AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(ref unit)) = unit {
+        for name in unit.entity_name.iter().chain(unit.inherits.iter()) {
+            dependencies.push((library_name.clone(), name.item.name().clone()));
+        }
+    }
+
+    dependencies
+}
+
+fn library_unit(library_name: &Symbol, work: &Symbol, prefix: &[Symbol]) -> (Symbol, Symbol) {
+    if &prefix[0] == work {
+        (library_name.clone(), prefix[1].clone())
+    } else {
+        (prefix[0].clone(), prefix[1].clone())
+    }
+}
+
+/// The identifiers of a selected name such as lib.pkg.all
+fn name_prefix(name: &Name, prefix: &mut Vec<Symbol>) {
+    match name {
+        Name::Designator(ref designator) => push_identifier(&designator.item, prefix),
+        Name::Selected(ref name, ref suffix) => {
+            name_prefix(&name.item, prefix);
+            push_identifier(&suffix.item.item, prefix);
+        }
+        Name::SelectedAll(ref name) => name_prefix(&name.item, prefix),
+        _ => {}
+    }
+}
+
+fn selected_name_prefix(name: &SelectedName, prefix: &mut Vec<Symbol>) {
+    match name {
+        SelectedName::Designator(ref designator) => push_identifier(&designator.item, prefix),
+        SelectedName::Selected(ref name, ref suffix) => {
+            selected_name_prefix(&name.item, prefix);
+            push_identifier(&suffix.item.item, prefix);
+        }
+    }
+}
+
+fn push_identifier(designator: &Designator, prefix: &mut Vec<Symbol>) {
+    if let Designator::Identifier(ref symbol) = designator {
+        prefix.push(symbol.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::CodeBuilder;
+
+    #[test]
+    fn dependencies_from_context_clause() {
+        let builder = CodeBuilder::new();
+        let code = builder.code(
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+use work.pkg.const;
+context lib.ctx;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+end architecture;
+
+configuration cfg of ent is
+  for a
+  end for;
+end configuration;
+
+package inst_pkg is new work.gen_pkg;
+",
+        );
+        let lib = builder.symbol("lib");
+        let work = builder.symbol("work");
+        let dependencies: Vec<_> = code
+            .design_file()
+            .design_units
+            .iter()
+            .map(|unit| unit_dependencies(unit, &lib, &work))
+            .collect();
+
+        let dep =
+            |library_name: &str, name: &str| (builder.symbol(library_name), builder.symbol(name));
+
+        assert_eq!(
+            dependencies,
+            vec![
+                vec![
+                    dep("ieee", "std_logic_1164"),
+                    dep("lib", "pkg"),
+                    dep("lib", "ctx")
+                ],
+                vec![dep("lib", "ent")],
+                vec![dep("lib", "ent")],
+                vec![dep("lib", "gen_pkg")],
+            ]
+        );
+    }
+}
@@ -87,6 +87,7 @@ impl<'a> AnalyzeContext<'a> {
                         prefix_pos,
                         "Internal error when referencing full type of incomplete type",
                     )
+                    .with_code(ErrorCode::Internal)
                     .into())
                 }
             }
@@ -118,10 +119,10 @@ impl<'a> AnalyzeContext<'a> {
                     return Ok(visible);
                 };
 
-                Err(AnalysisError::NotFatal(Diagnostic::error(
-                    &prefix.pos,
-                    "Invalid prefix for selected name",
-                )))
+                Err(AnalysisError::NotFatal(
+                    Diagnostic::error(&prefix.pos, "Invalid prefix for selected name")
+                        .with_code(ErrorCode::InvalidName),
+                ))
             }
             SelectedName::Designator(ref mut designator) => {
                 designator.clear_reference();
@@ -223,7 +224,8 @@ impl<'a> AnalyzeContext<'a> {
                     let mut error = Diagnostic::error(
                         pos,
                         format!("Expected {}, got {}", expected, ent.describe()),
-                    );
+                    )
+                    .with_code(ErrorCode::MismatchedKinds);
                     if let Some(decl_pos) = ent.decl_pos() {
                         error.add_related(decl_pos, "Defined here");
                     }
@@ -232,7 +234,8 @@ impl<'a> AnalyzeContext<'a> {
             }
             Err(overloaded) => {
                 let mut error =
-                    Diagnostic::error(pos, format!("Expected {}, got overloaded name", expected));
+                    Diagnostic::error(pos, format!("Expected {}, got overloaded name", expected))
+                        .with_code(ErrorCode::MismatchedKinds);
                 for ent in overloaded.entities() {
                     if let Some(decl_pos) = ent.decl_pos() {
                         error.add_related(decl_pos, "Defined here");
@@ -257,33 +260,6 @@ impl<'a> AnalyzeContext<'a> {
         )
     }
 
-    fn analyze_attribute_name(
-        &self,
-        region: &Region<'_>,
-        attr: &mut AttributeName,
-        diagnostics: &mut dyn DiagnosticHandler,
-    ) -> FatalNullResult {
-        // @TODO more, attr must be checked inside the region of attributes of prefix
-        let AttributeName {
-            name,
-            signature,
-            expr,
-            ..
-        } = attr;
-
-        self.resolve_name(region, &name.pos, &mut name.item, diagnostics)?;
-
-        if let Some(ref mut signature) = signature {
-            if let Err(err) = self.resolve_signature(region, signature) {
-                err.add_to(diagnostics)?;
-            }
-        }
-        if let Some(ref mut expr) = expr {
-            self.analyze_expression(region, expr, diagnostics)?;
-        }
-        Ok(())
-    }
-
     pub fn analyze_range(
         &self,
         region: &Region<'_>,
@@ -296,7 +272,7 @@ impl<'a> AnalyzeContext<'a> {
                 self.analyze_expression(region, &mut constraint.right_expr, diagnostics)?;
             }
             Range::Attribute(ref mut attr) => {
-                self.analyze_attribute_name(region, attr, diagnostics)?
+                self.analyze_attribute_name(region, attr, diagnostics)?;
             }
         }
         Ok(())
@@ -467,7 +443,7 @@ impl<'a> AnalyzeContext<'a> {
         Ok(())
     }
 
-    fn analyze_expression_pos(
+    pub fn analyze_expression_pos(
         &self,
         region: &Region<'_>,
         pos: &SrcPos,
@@ -502,6 +478,16 @@ impl<'a> AnalyzeContext<'a> {
                 }
             },
             Expression::Literal(_) => Ok(()),
+            Expression::Conditional(ref mut conditionals) => {
+                for Conditional { condition, item } in conditionals.conditionals.iter_mut() {
+                    self.analyze_expression(region, item, diagnostics)?;
+                    self.analyze_expression(region, condition, diagnostics)?;
+                }
+                if let Some(ref mut item) = conditionals.else_item {
+                    self.analyze_expression(region, item, diagnostics)?;
+                }
+                Ok(())
+            }
         }
     }
 
@@ -549,6 +535,7 @@ impl<'a> AnalyzeContext<'a> {
                 suffix_pos,
                 format!("{} cannot be indexed", type_mark.describe()),
             )
+            .with_code(ErrorCode::MismatchedKinds)
             .into())
         }
     }
@@ -569,9 +556,12 @@ impl<'a> AnalyzeContext<'a> {
 
         if let NamedEntityKind::ArrayType { .. } = base_type.kind() {
         } else {
-            diagnostics.error(
-                suffix_pos,
-                format!("{} cannot be sliced", type_mark.describe()),
+            diagnostics.push(
+                Diagnostic::error(
+                    suffix_pos,
+                    format!("{} cannot be sliced", type_mark.describe()),
+                )
+                .with_code(ErrorCode::MismatchedKinds),
             );
         }
 
@@ -616,13 +606,16 @@ impl<'a> AnalyzeContext<'a> {
                                 }
                             }
                         } else {
-                            diagnostics.push(Diagnostic::error(
-                                &fcall.name.pos,
-                                format!(
-                                    "{} cannot be the prefix of a function call",
-                                    ent.describe()
-                                ),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    &fcall.name.pos,
+                                    format!(
+                                        "{} cannot be the prefix of a function call",
+                                        ent.describe()
+                                    ),
+                                )
+                                .with_code(ErrorCode::MismatchedKinds),
+                            );
 
                             self.analyze_assoc_elems(region, &mut fcall.parameters, diagnostics)?;
                         }
@@ -755,6 +748,15 @@ impl<'a> AnalyzeContext<'a> {
             Name::FunctionCall(..) => {
                 self.analyze_ambiguous_function_call(region, name_pos, name, diagnostics)?;
             }
+            Name::Attribute(ref mut attr) => {
+                if let Some(attr_type) = self.analyze_attribute_name(region, attr, diagnostics)? {
+                    if let Some(diagnostic) =
+                        attr_type.check_target_type(name_pos, attr, target_type)
+                    {
+                        diagnostics.push(diagnostic);
+                    }
+                }
+            }
 
             Name::Slice(ref mut prefix, ref mut drange) => {
                 if let Some(NamedEntities::Single(ref named_entity)) =
@@ -789,10 +791,13 @@ impl<'a> AnalyzeContext<'a> {
         match expr.item {
             Expression::Literal(Literal::AbstractLiteral(AbstractLiteral::Integer(_))) => {
                 if !matches!(target_base.kind(), NamedEntityKind::IntegerType(..)) {
-                    diagnostics.push(Diagnostic::error(
-                        expr,
-                        format!("integer literal does not match {}", target_type.describe()),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            expr,
+                            format!("integer literal does not match {}", target_type.describe()),
+                        )
+                        .with_code(ErrorCode::TypeMismatch),
+                    );
                 }
                 Ok(())
             }
@@ -803,6 +808,26 @@ impl<'a> AnalyzeContext<'a> {
                 name,
                 diagnostics,
             ),
+            Expression::Conditional(ref mut conditionals) => {
+                for Conditional { condition, item } in conditionals.conditionals.iter_mut() {
+                    self.analyze_expression_with_target_type(
+                        region,
+                        target_type,
+                        item,
+                        diagnostics,
+                    )?;
+                    self.analyze_expression(region, condition, diagnostics)?;
+                }
+                if let Some(ref mut item) = conditionals.else_item {
+                    self.analyze_expression_with_target_type(
+                        region,
+                        target_type,
+                        item,
+                        diagnostics,
+                    )?;
+                }
+                Ok(())
+            }
             _ => self.analyze_expression(region, expr, diagnostics),
         }
     }
@@ -947,7 +972,8 @@ fn ambiguous_overloaded_type(
     name: &Name,
     candidates: &[&Arc<NamedEntity>],
 ) -> Diagnostic {
-    let mut diagnostic = Diagnostic::error(pos, format!("ambiguous use of '{}'", name));
+    let mut diagnostic = Diagnostic::error(pos, format!("ambiguous use of '{}'", name))
+        .with_code(ErrorCode::AmbiguousName);
 
     for ent in candidates {
         if let Some(signature) = ent.signature() {
@@ -968,6 +994,7 @@ fn overloaded_type_mismatch(pos: &SrcPos, name: &Name, expected_type: &NamedEnti
         pos,
         format!("'{}' does not match {}", name, expected_type.describe()),
     )
+    .with_code(ErrorCode::TypeMismatch)
 }
 
 fn type_mismatch(pos: &SrcPos, ent: &NamedEntity, expected_type: &NamedEntity) -> Diagnostic {
@@ -979,6 +1006,7 @@ fn type_mismatch(pos: &SrcPos, ent: &NamedEntity, expected_type: &NamedEntity) -
             expected_type.describe()
         ),
     )
+    .with_code(ErrorCode::TypeMismatch)
 }
 
 pub fn invalid_selected_name_prefix(named_entity: &NamedEntity, prefix: &SrcPos) -> Diagnostic {
@@ -989,6 +1017,7 @@ pub fn invalid_selected_name_prefix(named_entity: &NamedEntity, prefix: &SrcPos)
             named_entity.describe(),
         )),
     )
+    .with_code(ErrorCode::MismatchedKinds)
 }
 
 pub fn no_declaration_within(
@@ -1003,6 +1032,7 @@ pub fn no_declaration_within(
             named_entity.describe(),
         ),
     )
+    .with_code(ErrorCode::Unresolved)
 }
 
 fn plural(singular: &'static str, plural: &'static str, count: usize) -> &'static str {
@@ -1019,7 +1049,8 @@ fn dimension_mismatch(
     got: usize,
     expected: usize,
 ) -> Diagnostic {
-    let mut diag = Diagnostic::error(pos, "Number of indexes does not match array dimension");
+    let mut diag = Diagnostic::error(pos, "Number of indexes does not match array dimension")
+        .with_code(ErrorCode::DimensionMismatch);
 
     if let Some(decl_pos) = base_type.decl_pos() {
         diag.add_related(
@@ -27,10 +27,16 @@ impl<'a> AnalyzeContext<'a> {
 
         match statement.statement {
             SequentialStatement::Return(ref mut ret) => {
-                let ReturnStatement { expression } = ret;
+                let ReturnStatement {
+                    expression,
+                    condition,
+                } = ret;
                 if let Some(ref mut expression) = expression {
                     self.analyze_expression(parent, expression, diagnostics)?;
                 }
+                if let Some(ref mut condition) = condition {
+                    self.analyze_expression(parent, condition, diagnostics)?;
+                }
             }
             SequentialStatement::Wait(ref mut wait_stmt) => {
                 let WaitStatement {
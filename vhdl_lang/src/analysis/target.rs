@@ -51,25 +51,34 @@ impl<'a> AnalyzeContext<'a> {
                 } = resolved_name
                 {
                     if !is_valid_assignment_target(base_object) {
-                        diagnostics.push(Diagnostic::error(
-                            target_pos,
-                            format!(
-                                "{} may not be the target of an assignment",
-                                base_object.describe_class()
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                target_pos,
+                                format!(
+                                    "{} may not be the target of an assignment",
+                                    base_object.describe_class()
+                                ),
+                            )
+                            .with_code(ErrorCode::InvalidTarget),
+                        );
                     } else if !is_valid_assignment_type(base_object, assignment_type) {
-                        diagnostics.push(Diagnostic::error(
-                            target_pos,
-                            format!(
-                                "{} may not be the target of a {} assignment",
-                                base_object.describe_class(),
-                                assignment_type.to_str()
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                target_pos,
+                                format!(
+                                    "{} may not be the target of a {} assignment",
+                                    base_object.describe_class(),
+                                    assignment_type.to_str()
+                                ),
+                            )
+                            .with_code(ErrorCode::InvalidTarget),
+                        );
                     }
                 } else {
-                    diagnostics.push(Diagnostic::error(target_pos, "Invalid assignment target"));
+                    diagnostics.push(
+                        Diagnostic::error(target_pos, "Invalid assignment target")
+                            .with_code(ErrorCode::InvalidTarget),
+                    );
                 }
             }
             Err(err) => {
@@ -0,0 +1,248 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn predefined_attributes_with_valid_prefixes() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+architecture a of ent is
+  type state_t is (idle, busy, done);
+  type arr_t is array (natural range <>) of state_t;
+  subtype small_t is integer range 0 to 7;
+  signal vec : bit_vector(7 downto 0);
+  constant states : arr_t(0 to 2) := (idle, busy, done);
+
+  constant len : natural := vec'length;
+  constant hi : natural := vec'high;
+  constant lo : natural := states'low;
+  constant first : state_t := state_t'left;
+  constant next_state : state_t := state_t'succ(idle);
+  constant prev_state : state_t := state_t'pred(done);
+  constant from_pos : state_t := state_t'val(1);
+  constant to_pos : integer := state_t'pos(busy);
+  constant from_str : state_t := state_t'value(\"busy\");
+  constant img : string := small_t'image(3);
+  constant base_high : integer := small_t'base'high;
+  constant asc : boolean := vec'ascending;
+  constant name : string := vec'path_name;
+  constant inst : string := ent'instance_name;
+begin
+  main : process (clk)
+    variable changed : boolean;
+    variable last : bit;
+    variable since : time;
+  begin
+    for i in vec'range loop
+    end loop;
+    for i in vec'reverse_range loop
+    end loop;
+    changed := clk'event and not clk'stable(1 ns) and clk'delayed(2 ns)'event;
+    last := clk'last_value;
+    since := clk'last_event;
+    changed := clk'quiet and clk'active;
+  end process;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn predefined_attribute_requires_valid_prefix() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c0 : natural := 0;
+  signal sig : bit;
+  constant ev : boolean := c0'event;
+  constant len : natural := integer'length;
+  constant pos : natural := c0'pos(0);
+  constant sub : natural := natural'subtype'high;
+begin
+  lab : sig <= '1' when lab'length = 1 else '0';
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s("c0", 2),
+                "Expected signal as prefix of attribute 'event', got constant 'c0'",
+            ),
+            Diagnostic::error(
+                code.s("integer", 1),
+                "Expected array as prefix of attribute 'length', got integer type 'INTEGER'",
+            ),
+            Diagnostic::error(
+                code.s("c0", 3),
+                "Expected type as prefix of attribute 'pos', got constant 'c0'",
+            ),
+            Diagnostic::error(
+                code.s("natural", 5),
+                "Expected object as prefix of attribute 'subtype', got subtype 'NATURAL'",
+            ),
+            Diagnostic::error(
+                code.s("lab", 2),
+                "Expected array as prefix of attribute 'length', got label 'lab'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn predefined_attribute_result_type_is_checked() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+architecture a of ent is
+  signal vec : bit_vector(7 downto 0);
+  constant c0 : integer := clk'event;
+  constant c1 : boolean := vec'length;
+  constant c2 : integer := integer'image(0);
+  constant c3 : bit := integer'high;
+begin
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("clk'event"),
+                "'clk'event' of type 'BOOLEAN' does not match integer type 'INTEGER'",
+            ),
+            Diagnostic::error(
+                code.s1("vec'length"),
+                "'vec'length' of universal_integer does not match type 'BOOLEAN'",
+            ),
+            Diagnostic::error(
+                code.s1("integer'image(0)"),
+                "'integer'image(0)' of array type 'STRING' does not match integer type 'INTEGER'",
+            ),
+            Diagnostic::error(
+                code.s1("integer'high"),
+                "'integer'high' of integer type 'INTEGER' does not match type 'BIT'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn user_defined_attribute_resolves_through_specification() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  attribute keep : boolean;
+  attribute weight : natural;
+  signal s0, s1 : bit;
+  constant c0 : natural := 0;
+  attribute keep of s0 : signal is true;
+  attribute weight of all : constant is 3;
+
+  constant k0 : boolean := s0'keep;
+  constant w0 : natural := c0'weight;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference(code.source(), code.s("keep", 3).start()),
+        Some(code.s1("keep").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("s0", 2).start()),
+        Some(code.s1("s0").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("weight", 3).start()),
+        Some(code.s1("weight").pos())
+    );
+}
+
+#[test]
+fn user_defined_attribute_errors() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  attribute keep : boolean;
+  signal s0, s1, s2 : bit;
+  constant c0 : natural := 0;
+  attribute keep of s0 : signal is true;
+  attribute keep of c0 : signal is true;
+  attribute keep of s2 : signal is 1;
+
+  constant k0 : boolean := s1'keep;
+  constant k1 : boolean := s0'missing;
+  constant k2 : boolean := s0'c0;
+  constant k3 : integer := s0'keep;
+begin
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s("c0", 2), "Expected signal, got constant 'c0'")
+                .related(code.s1("c0"), "Defined here"),
+            Diagnostic::error(
+                code.s1("is 1").s1("1"),
+                "integer literal does not match type 'BOOLEAN'",
+            ),
+            Diagnostic::error(
+                code.s("keep", 5),
+                "Attribute 'keep' has not been specified for signal 's1'",
+            ),
+            Diagnostic::error(code.s1("missing"), "No declaration of 'missing'"),
+            Diagnostic::error(code.s("c0", 3), "Expected attribute, got constant 'c0'")
+                .related(code.s1("c0"), "Defined here"),
+            Diagnostic::error(
+                code.s1("s0'keep"),
+                "'s0'keep' of type 'BOOLEAN' does not match integer type 'INTEGER'",
+            ),
+        ],
+    );
+}
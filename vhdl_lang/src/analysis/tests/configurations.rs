@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn configuration_resolves_instances_and_bindings() {
+    let mut builder = LibraryBuilder::new();
+    let gates = builder.code(
+        "gates",
+        "
+package gate_pkg is
+  constant delay : time := 1 ns;
+end package;
+
+entity nand2 is
+end entity;
+
+architecture netlist of nand2 is
+begin
+end architecture;
+",
+    );
+    let code = builder.code(
+        "libname",
+        "
+entity top is
+end entity;
+
+architecture rtl of top is
+  component gate is
+  end component;
+begin
+  u1 : gate;
+  u2 : gate;
+end architecture;
+
+library gates;
+configuration cfg of top is
+  for rtl
+    use gates.gate_pkg.all;
+    for u1, u2 : gate
+      use entity gates.nand2(netlist);
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference(code.source(), code.s("u1", 2).start()),
+        Some(code.s1("u1").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("u2", 2).start()),
+        Some(code.s1("u2").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s(": gate", 3).s1("gate").start()),
+        Some(code.s1("gate").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s1("nand2").start()),
+        Some(gates.s1("nand2").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s1("gate_pkg").start()),
+        Some(gates.s1("gate_pkg").pos())
+    );
+}
+
+#[test]
+fn configuration_reports_unknown_instances_and_architectures() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture netlist of leaf is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component leaf is
+  end component;
+begin
+  u1 : leaf;
+  gen : if true generate
+  end generate;
+end architecture;
+
+configuration cfg of top is
+  for rtl
+    for u7 : leaf
+      use entity work.leaf(netlst);
+    end for;
+    for gen7
+    end for;
+  end for;
+end configuration;
+
+configuration cfg2 of top is
+  for rlt
+  end for;
+end configuration;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s1("u7"), "No instance 'u7' within architecture 'rtl'"),
+            Diagnostic::error(
+                code.s1("netlst"),
+                "No architecture 'netlst' for entity 'leaf'",
+            ),
+            Diagnostic::error(
+                code.s1("gen7"),
+                "No block or generate statement 'gen7' within architecture 'rtl'",
+            ),
+            Diagnostic::error(code.s1("rlt"), "No architecture 'rlt' for entity 'top'"),
+        ],
+    );
+}
+
+#[test]
+fn configuration_checks_binding_kinds() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+end package;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component comp is
+  end component;
+  constant c0 : natural := 0;
+begin
+  u1 : comp;
+  u2 : comp;
+end architecture;
+
+configuration cfg of top is
+  for rtl
+    for u1 : c0
+      use entity work.pkg;
+    end for;
+    for u2 : comp
+      use configuration work.missing;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s(": c0", 1).s1("c0"),
+                "Expected component, got constant 'c0'",
+            )
+            .related(code.s1("c0"), "Defined here"),
+            Diagnostic::error(
+                code.s("work.pkg", 1).s1("pkg"),
+                "Expected entity, got package 'pkg'",
+            )
+            .related(code.s1("pkg"), "Defined here"),
+            Diagnostic::error(
+                code.s1("missing"),
+                "No primary unit 'missing' within library 'libname'",
+            ),
+        ],
+    );
+}
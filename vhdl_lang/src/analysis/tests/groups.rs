@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn group_resolves_template_and_constituents() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  group pin2pin is (signal, signal);
+  group path is (label <>);
+  attribute max_delay : time;
+end package;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig_a, sig_b : bit;
+  group g1 : pin2pin (sig_a, sig_b);
+  group g2 : work.pkg.path (lab1, lab2);
+  attribute max_delay of g1 : group is 2 ns;
+begin
+  lab1: sig_b <= sig_a;
+  lab2: sig_a <= sig_b;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference(code.source(), code.s("pin2pin", 2).start()),
+        Some(code.s1("pin2pin").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("path", 2).start()),
+        Some(code.s1("path").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("sig_b", 2).start()),
+        Some(code.s1("sig_b").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("g1", 2).start()),
+        Some(code.s1("g1").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("max_delay", 2).start()),
+        Some(code.s1("max_delay").pos())
+    );
+}
+
+#[test]
+fn group_reports_errors() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  constant c0 : natural := 0;
+  signal clk : bit;
+  group pin2pin is (signal, signal);
+  group g1 : pin2pin (clk, c0);
+  group g2 : pin2pin (clk);
+  group g3 : c0 (clk);
+  group g4 : pin2pin (clk, missing);
+  attribute attr : natural;
+  attribute attr of clk : group is 0;
+end package;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    let count_error = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.pos == code.s1("g2").pos())
+        .unwrap();
+    assert_eq!(count_error.code, Some(ErrorCode::MismatchedKinds));
+
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s("c0", 2), "Expected signal, got constant 'c0'")
+                .related(code.s1("c0"), "Defined here"),
+            Diagnostic::error(code.s1("g2"), "Group 'g2' has 1 constituents, expected 2"),
+            Diagnostic::error(
+                code.s("c0", 3),
+                "Expected group template, got constant 'c0'",
+            )
+            .related(code.s1("c0"), "Defined here"),
+            missing(&code, "missing", 1),
+            Diagnostic::error(code.s("clk", 6), "Expected group, got signal 'clk'")
+                .related(code.s1("clk"), "Defined here"),
+        ],
+    );
+}
@@ -42,6 +42,26 @@ end package;
     check_diagnostics(diagnostics, duplicates(&code, &["a1"]));
 }
 
+#[test]
+fn homographs_have_duplicate_declaration_code() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+constant a1 : natural := 0;
+constant a1 : natural := 0;
+end package;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![duplicate(&code, "a1", 1, 2).with_code(ErrorCode::DuplicateDeclaration)],
+    );
+}
+
 #[test]
 fn forbid_homographs_in_subprogram_bodies() {
     let mut builder = LibraryBuilder::new();
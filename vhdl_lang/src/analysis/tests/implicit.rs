@@ -34,6 +34,29 @@ end package;
     );
 }
 
+#[test]
+fn does_not_add_to_string_before_vhdl2008() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type type_t is range 0 to 1;
+  alias my_to_string is to_string[type_t, return string];
+end package;
+",
+        VHDLStandard::VHDL2002,
+    );
+
+    let diagnostics = builder.analyze();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].pos, code.s("to_string", 2).pos());
+    assert_eq!(
+        diagnostics[0].message,
+        "Could not find declaration with given signature"
+    );
+}
+
 #[test]
 fn adds_to_string_for_array_types() {
     check_code_with_no_diagnostics(
@@ -22,6 +22,7 @@ fn allows_incomplete_type_definition() {
 package pkg is
   type rec_t;
   type rec_t is record
+    elem : natural;
   end record;
 
   type enum_t;
@@ -44,6 +45,7 @@ package pkg is
 type rec_t;
 type rec_t;
 type rec_t is record
+elem : natural;
 end record;
 end package;
 ",
@@ -66,6 +68,7 @@ end package;
 package body pkg is
 -- Must appear in the same immediate declarative region
 type rec_t is record
+elem : natural;
 end record;
 end package body;
 ",
@@ -83,6 +86,7 @@ begin
 blk : block
 -- Must appear in the same immediate declarative region
 type rec_t is record
+elem : natural;
 end record;
 begin
 end block;
@@ -8,7 +8,7 @@ use super::*;
 use crate::analysis::DesignRoot;
 use crate::ast::search::*;
 use crate::ast::Reference;
-use crate::data::SrcPos;
+use crate::data::{NoProgress, SrcPos};
 use fnv::FnvHashSet;
 
 #[test]
@@ -156,7 +156,7 @@ end architecture;
         "libname",
         "
 configuration cfg of ent is
-for rtl
+for a
 end for;
 end configuration;
 ",
@@ -269,17 +269,17 @@ fn check_incremental_analysis(builder: LibraryBuilder, expected_diagnostics: Vec
         add_standard_library(symbols.clone(), &mut root);
 
         for (j, (library_name, code)) in codes.iter().enumerate() {
-            root.add_design_file(library_name.clone(), code.design_file());
+            root.add_design_file(library_name.clone(), code.design_file(), code.standard);
 
             if i != j {
-                fresh_root.add_design_file(library_name.clone(), code.design_file());
+                fresh_root.add_design_file(library_name.clone(), code.design_file(), code.standard);
             } else {
                 fresh_root.ensure_library(library_name.clone());
             }
         }
 
         let mut diagnostics = Vec::new();
-        root.analyze(&mut diagnostics);
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
         check_diagnostics(diagnostics, expected_diagnostics.clone());
 
         let (library_name, code) = &codes[i];
@@ -289,8 +289,8 @@ fn check_incremental_analysis(builder: LibraryBuilder, expected_diagnostics: Vec
         check_analysis_equal(&mut root, &mut fresh_root);
 
         // Add back files again
-        root.add_design_file(library_name.clone(), code.design_file());
-        fresh_root.add_design_file(library_name.clone(), code.design_file());
+        root.add_design_file(library_name.clone(), code.design_file(), code.standard);
+        fresh_root.add_design_file(library_name.clone(), code.design_file(), code.standard);
 
         let diagnostics = check_analysis_equal(&mut root, &mut fresh_root);
 
@@ -301,10 +301,10 @@ fn check_incremental_analysis(builder: LibraryBuilder, expected_diagnostics: Vec
 
 fn check_analysis_equal(got: &mut DesignRoot, expected: &mut DesignRoot) -> Vec<Diagnostic> {
     let mut got_diagnostics = Vec::new();
-    got.analyze(&mut got_diagnostics);
+    got.analyze(&mut got_diagnostics, &mut NoProgress::default());
 
     let mut expected_diagnostics = Vec::new();
-    expected.analyze(&mut expected_diagnostics);
+    expected.analyze(&mut expected_diagnostics, &mut NoProgress::default());
 
     // Check that diagnostics are equal to doing analysis from scratch
     check_diagnostics(got_diagnostics.clone(), expected_diagnostics);
@@ -5,22 +5,28 @@
 // Copyright (c) 2019, Olof Kraigher olof.kraigher@gmail.com
 
 mod assignment_typecheck;
+mod attributes;
 mod circular_dependencies;
+mod configurations;
 mod context_clause;
 mod deferred_constant;
+mod groups;
 mod homographs;
 mod implicit;
 mod incomplete_type;
 mod incremental_analysis;
 mod package_instance;
 mod protected_type;
+mod psl;
 mod resolves_design_units;
 mod resolves_names;
 mod resolves_type_mark;
 mod typecheck_expression;
 mod util;
+mod vhdl2019;
 mod visibility;
 
 pub use self::util::*;
-pub use crate::data::Diagnostic;
+pub use crate::data::{Diagnostic, ErrorCode};
+pub use crate::standard::VHDLStandard;
 pub use crate::syntax::test::*;
@@ -0,0 +1,217 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn psl_resolves_signal_names() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+architecture a of ent is
+  signal req, ack : boolean;
+  default clock is rising_edge(clk);
+  sequence handshake (boolean r, a) is {r; a[*1 to inf]};
+  property eventually_ack is always req -> eventually! ack;
+begin
+  assert always req -> next ack report \"no ack\" severity error;
+  a0: assert eventually_ack;
+  c0: cover {req; ack};
+  assume never (req and ack) @ rising_edge(clk);
+  assert always handshake(req, ack);
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let req_refs = root.find_all_references(&code.s1("req").pos());
+    assert_eq!(
+        req_refs,
+        (1..=6).map(|i| code.s("req", i).pos()).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        root.search_reference(code.source(), code.s("handshake", 2).start()),
+        Some(code.s1("handshake").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("eventually_ack", 2).start()),
+        Some(code.s1("eventually_ack").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s1("a[*1").start()),
+        Some(code.s1("r, a)").s1("a").pos())
+    );
+}
+
+#[test]
+fn psl_reports_missing_names() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal req : boolean;
+  property p (boolean b) is always b -> next missing1;
+begin
+  assert always req -> next missing2;
+  cover {req; missing3[*2]};
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            missing(&code, "missing1", 1),
+            missing(&code, "missing2", 1),
+            missing(&code, "missing3", 1),
+        ],
+    );
+}
+
+#[test]
+fn psl_builtin_functions_analyze_their_arguments() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+architecture a of ent is
+  signal req, ack : boolean;
+  signal state : bit_vector(3 downto 0);
+  default clock is rising_edge(clk);
+begin
+  assert always rose(req) -> next ack;
+  assert always fell(req) -> not Prev(ack);
+  assert always onehot0(state) and prev(state, 2) /= state;
+  assert always rose(missing1) -> next fell(missing2);
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![missing(&code, "missing1", 1), missing(&code, "missing2", 1)],
+    );
+
+    let state_refs = root.find_all_references(&code.s1("state").pos());
+    assert_eq!(
+        state_refs,
+        (1..=4)
+            .map(|i| code.s("state", i).pos())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn vunit_bound_to_architecture_sees_its_declarations() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of ent is
+  signal busy, done : boolean;
+begin
+end architecture;
+
+vunit common {
+  sequence pulse (boolean b) is {b; not b};
+}
+
+vunit checker (ent(rtl)) {
+  inherit common;
+  default clock is rising_edge(clk);
+  assert always busy -> eventually! done;
+  assert always pulse(done);
+}
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference(code.source(), code.s("busy", 2).start()),
+        Some(code.s1("busy").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("clk", 2).start()),
+        Some(code.s1("clk").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("pulse", 2).start()),
+        Some(code.s1("pulse").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("common", 2).start()),
+        Some(code.s1("common").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s1("(ent(").s1("ent").start()),
+        Some(code.s1("entity ent").s1(" ent").s1("ent").pos())
+    );
+}
+
+#[test]
+fn vunit_bound_to_missing_units() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+package pkg is
+end package;
+
+vunit v1 (missing_ent) {
+}
+
+vunit v2 (ent(missing_arch)) {
+}
+
+vunit v3 {
+  inherit pkg;
+}
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("missing_ent"),
+                "No entity 'missing_ent' within library 'libname'",
+            ),
+            Diagnostic::error(
+                code.s1("missing_arch"),
+                "No architecture 'missing_arch' for entity 'ent'",
+            ),
+            Diagnostic::error(code.s("pkg", 2), "package 'pkg' is not a verification unit"),
+        ],
+    );
+}
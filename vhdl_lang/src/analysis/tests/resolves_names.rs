@@ -1471,3 +1471,78 @@ end architecture;
         ],
     );
 }
+
+#[test]
+fn guarded_block_declares_implicit_guard_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal clk, en : bit;
+  signal dout : bit register;
+  disconnect dout : bit after 1 ns;
+begin
+  blk : block (clk = '1' and en = '1')
+  begin
+    dout <= guarded en;
+    postponed dout <= guarded not guard;
+  end block;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference(code.source(), code.s1("not guard").s1("guard").start()),
+        Some(code.s1("clk = '1' and en = '1'").pos())
+    );
+    assert_eq!(
+        root.search_reference(code.source(), code.s("dout", 2).start()),
+        Some(code.s1("dout").pos())
+    );
+}
+
+#[test]
+fn guarded_assignment_requires_guard_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal dout, din : bit;
+  disconnect missing : bit after 1 ns;
+begin
+  dout <= guarded din;
+
+  blk : block (din)
+  begin
+  end block;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            missing(&code, "missing", 1),
+            Diagnostic::error(
+                code.s("dout", 2),
+                "Guarded signal assignment requires a visible GUARD signal",
+            ),
+            Diagnostic::error(
+                code.s("din", 3),
+                "signal 'din' does not match type 'BOOLEAN'",
+            ),
+        ],
+    );
+}
@@ -6,6 +6,7 @@
 
 use crate::analysis::DesignRoot;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::test::*;
 use crate::syntax::Symbols;
 use pretty_assertions::assert_eq;
@@ -43,6 +44,17 @@ impl LibraryBuilder {
         code
     }
 
+    pub fn code_with_standard(
+        &mut self,
+        library_name: &str,
+        code: &str,
+        standard: VHDLStandard,
+    ) -> Code {
+        let code = self.code_builder.code_with_standard(code, standard);
+        self.add_code(library_name, code.clone());
+        code
+    }
+
     pub fn in_declarative_region(&mut self, code: &str) -> Code {
         self.code(
             "libname",
@@ -68,11 +80,11 @@ end architecture;",
 
         for (library_name, codes) in self.libraries.iter() {
             for code in codes {
-                root.add_design_file(library_name.clone(), code.design_file());
+                root.add_design_file(library_name.clone(), code.design_file(), code.standard);
             }
         }
 
-        root.analyze(&mut diagnostics);
+        root.analyze(&mut diagnostics, &mut NoProgress::default());
 
         (root, diagnostics)
     }
@@ -117,9 +129,17 @@ pub fn add_standard_library(symbols: Arc<Symbols>, root: &mut DesignRoot) {
     ));
     let std_sym = symbols.symtab().insert_utf8("std");
 
-    root.add_design_file(std_sym.clone(), std_standard.design_file());
-    root.add_design_file(std_sym.clone(), std_textio.design_file());
-    root.add_design_file(std_sym, std_env.design_file());
+    root.add_design_file(
+        std_sym.clone(),
+        std_standard.design_file(),
+        std_standard.standard,
+    );
+    root.add_design_file(
+        std_sym.clone(),
+        std_textio.design_file(),
+        std_textio.standard,
+    );
+    root.add_design_file(std_sym, std_env.design_file(), std_env.standard);
 }
 
 pub fn missing(code: &Code, name: &str, occ: usize) -> Diagnostic {
@@ -0,0 +1,243 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn mode_view_ports() {
+    let mut builder = LibraryBuilder::new();
+    builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type bus_t is record
+    data : natural;
+    valid, ready : boolean;
+  end record;
+
+  view master of bus_t is
+    data, valid : out;
+    ready : in;
+  end view;
+end package;
+
+use work.pkg.all;
+
+entity ent is
+  port (
+    m : view master;
+    s : view master'converse of bus_t
+  );
+end entity;
+
+architecture a of ent is
+begin
+  m.data <= s.data;
+end architecture;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn converse_of_mode_view_in_alias_and_view_names() {
+    let mut builder = LibraryBuilder::new();
+    builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type sub_t is record
+    req, ack : boolean;
+  end record;
+
+  view sub_master of sub_t is
+    req : out;
+    ack : in;
+  end view;
+
+  type bus_t is record
+    data : natural;
+    sub : sub_t;
+  end record;
+
+  view master_v of bus_t is
+    data : out;
+    sub : view sub_master'converse;
+  end view;
+
+  alias slave_v is master_v'converse;
+end package;
+
+use work.pkg.all;
+
+entity ent is
+  port (
+    s : view slave_v;
+    t : view work.pkg.slave_v'converse
+  );
+end entity;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn converse_alias_requires_mode_view() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type bus_t is record
+    data : natural;
+  end record;
+
+  alias bad is bus_t'converse;
+end package;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(code.s1("bus_t'converse"), "Invalid alias name")
+                .with_code(ErrorCode::InvalidName),
+        ],
+    );
+}
+
+#[test]
+fn mode_view_elements_must_be_record_elements() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  type bus_t is record
+    data : natural;
+  end record;
+
+  view master of bus_t is
+    data : out;
+    missing : in;
+  end view;
+
+  view bad of natural is
+  end view;
+end package;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::error(
+                code.s1("missing"),
+                "No declaration of 'missing' within record type 'bus_t'",
+            ),
+            Diagnostic::error(
+                code.s("natural", 2),
+                "Expected record type, got integer type 'INTEGER'",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn port_view_name_must_be_a_view() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+entity ent is
+  port (
+    m : view natural
+  );
+end entity;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].pos, code.s1("natural").pos());
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected view, got subtype 'NATURAL'"
+    );
+}
+
+#[test]
+fn private_declarations_are_only_visible_in_package_body() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  constant pub : natural := 0;
+private
+  constant priv : natural := pub;
+end package;
+
+package body pkg is
+  constant body_const : natural := priv;
+end package body;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c0 : natural := pub;
+  constant c1 : natural := priv;
+begin
+end architecture;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(diagnostics, vec![missing(&code, "priv", 4)]);
+}
+
+#[test]
+fn conditional_expressions_and_return() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code_with_standard(
+        "libname",
+        "
+package pkg is
+  constant sel : boolean := true;
+  constant c0 : natural := 1 when sel else 2;
+  constant c1 : natural := 1 when missing else 2;
+  function fun(x : natural) return natural;
+end package;
+
+package body pkg is
+  function fun(x : natural) return natural is
+  begin
+    return when x = 0;
+    return x when sel else 0;
+  end function;
+end package body;
+",
+        VHDLStandard::VHDL2019,
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(diagnostics, vec![missing(&code, "missing", 1)]);
+}
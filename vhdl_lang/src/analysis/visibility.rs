@@ -200,7 +200,8 @@ impl<'a> Visible<'a> {
             let mut error = Diagnostic::error(
                 pos,
                 format!("Name '{}' is hidden by conflicting use clause", designator),
-            );
+            )
+            .with_code(ErrorCode::HiddenByUseClause);
 
             fn last_visible_pos(visible_entity: &VisibleEntityRef) -> u32 {
                 if let Some(pos) = visible_entity.visible_pos.iter().rev().flatten().next() {
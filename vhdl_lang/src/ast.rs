@@ -102,7 +102,7 @@ pub enum Binary {
 pub struct AttributeName {
     pub name: WithPos<Name>,
     pub signature: Option<WithPos<Signature>>,
-    pub attr: Ident,
+    pub attr: WithRef<Ident>,
     pub expr: Option<Box<WithPos<Expression>>>,
 }
 
@@ -259,6 +259,9 @@ pub enum Expression {
 
     /// LRM 9.3.7 Allocators
     New(Box<WithPos<Allocator>>),
+
+    /// LRM 9.3.8 Conditional expressions (VHDL-2019)
+    Conditional(Box<ConditionalExpressions>),
 }
 
 /// An identifier together with the lexical source location it occurs in.
@@ -402,7 +405,7 @@ pub struct AttributeDeclaration {
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
 pub struct EntityTag {
-    pub designator: WithPos<Designator>,
+    pub designator: WithPos<WithRef<Designator>>,
     pub signature: Option<WithPos<Signature>>,
 }
 
@@ -441,7 +444,7 @@ pub enum EntityClass {
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
 pub struct AttributeSpecification {
-    pub ident: Ident,
+    pub ident: WithRef<Ident>,
     pub entity_name: EntityName,
     pub entity_class: EntityClass,
     pub expr: WithPos<Expression>,
@@ -454,6 +457,45 @@ pub enum Attribute {
     Declaration(AttributeDeclaration),
 }
 
+/// LRM 6.9 Group template declarations
+#[derive(PartialEq, Debug, Clone)]
+pub struct EntityClassEntry {
+    pub entity_class: EntityClass,
+    /// The entry is followed by <> and may be repeated
+    pub is_repeated: bool,
+}
+
+/// LRM 6.9 Group template declarations
+#[derive(PartialEq, Debug, Clone)]
+pub struct GroupTemplateDeclaration {
+    pub ident: Ident,
+    pub entity_classes: Vec<EntityClassEntry>,
+}
+
+/// LRM 6.10 Group declarations
+#[derive(PartialEq, Debug, Clone)]
+pub struct GroupDeclaration {
+    pub ident: Ident,
+    pub template_name: WithPos<SelectedName>,
+    pub constituents: Vec<WithPos<Name>>,
+}
+
+/// LRM 7.4 Disconnection specification
+#[derive(PartialEq, Debug, Clone)]
+pub enum GuardedSignalList {
+    Names(Vec<WithPos<Name>>),
+    Others,
+    All,
+}
+
+/// LRM 7.4 Disconnection specification
+#[derive(PartialEq, Debug, Clone)]
+pub struct DisconnectionSpecification {
+    pub signal_list: GuardedSignalList,
+    pub type_mark: WithPos<SelectedName>,
+    pub after: WithPos<Expression>,
+}
+
 /// LRM 5.6.2 Protected type declarations
 #[derive(PartialEq, Debug, Clone)]
 pub struct ProtectedTypeDeclaration {
@@ -527,6 +569,13 @@ pub enum ObjectClass {
     SharedVariable,
 }
 
+/// LRM 6.4.2.3 Signal declarations
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SignalKind {
+    Register,
+    Bus,
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum InterfaceListType {
     Port,
@@ -539,6 +588,7 @@ pub struct ObjectDeclaration {
     pub class: ObjectClass,
     pub ident: Ident,
     pub subtype_indication: SubtypeIndication,
+    pub signal_kind: Option<SignalKind>,
     pub expression: Option<WithPos<Expression>>,
 }
 
@@ -607,6 +657,7 @@ pub struct InterfaceObjectDeclaration {
     pub ident: Ident,
     pub mode: Mode,
     pub subtype_indication: SubtypeIndication,
+    pub signal_kind: Option<SignalKind>,
     pub expression: Option<WithPos<Expression>>,
 }
 
@@ -640,6 +691,38 @@ pub enum InterfaceDeclaration {
     Subprogram(SubprogramDeclaration, Option<SubprogramDefault>),
     /// LRM 6.5.5 Interface package declaration
     Package(InterfacePackageDeclaration),
+    /// LRM 6.5.2 Interface object declaration with a mode view (VHDL-2019)
+    View(InterfaceViewDeclaration),
+}
+
+/// LRM 6.5.2 Mode view indication of a port (VHDL-2019)
+#[derive(PartialEq, Debug, Clone)]
+pub struct InterfaceViewDeclaration {
+    pub ident: Ident,
+    pub view: ModeViewIndication,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ModeViewKind {
+    /// view name
+    Record,
+    /// view (name)
+    Array,
+}
+
+/// LRM 6.5.2 mode_view_name with an optional 'converse attribute
+#[derive(PartialEq, Debug, Clone)]
+pub struct ModeViewName {
+    pub name: WithPos<SelectedName>,
+    pub converse: bool,
+}
+
+/// LRM 6.5.2 mode_view_indication
+#[derive(PartialEq, Debug, Clone)]
+pub struct ModeViewIndication {
+    pub kind: ModeViewKind,
+    pub name: ModeViewName,
+    pub subtype_indication: Option<SubtypeIndication>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -664,6 +747,28 @@ pub struct ComponentDeclaration {
     pub port_list: Vec<InterfaceDeclaration>,
 }
 
+/// LRM 6.5.2 element_mode_indication (VHDL-2019)
+#[derive(PartialEq, Debug, Clone)]
+pub enum ElementMode {
+    Simple(Mode),
+    View(ModeViewKind, ModeViewName),
+}
+
+/// LRM 6.5.2 mode_view_element_definition (VHDL-2019)
+#[derive(PartialEq, Debug, Clone)]
+pub struct ModeViewElement {
+    pub names: Vec<WithRef<Ident>>,
+    pub mode: ElementMode,
+}
+
+/// LRM 6.5.2 Mode view declarations (VHDL-2019)
+#[derive(PartialEq, Debug, Clone)]
+pub struct ModeViewDeclaration {
+    pub ident: Ident,
+    pub subtype_indication: SubtypeIndication,
+    pub elements: Vec<ModeViewElement>,
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Declaration {
     Object(ObjectDeclaration),
@@ -677,6 +782,13 @@ pub enum Declaration {
     Use(WithPos<UseClause>),
     Package(PackageInstantiation),
     Configuration(ConfigurationSpecification),
+    View(ModeViewDeclaration),
+    GroupTemplate(GroupTemplateDeclaration),
+    Group(GroupDeclaration),
+    Disconnection(DisconnectionSpecification),
+    Psl(PslDeclaration),
+    /// PSL default clock declaration
+    DefaultClock(WithPos<Expression>),
 }
 
 /// LRM 10.2 Wait statement
@@ -843,6 +955,8 @@ pub struct ExitStatement {
 #[derive(PartialEq, Debug, Clone)]
 pub struct ReturnStatement {
     pub expression: Option<WithPos<Expression>>,
+    /// VHDL-2019 return when condition;
+    pub condition: Option<WithPos<Expression>>,
 }
 
 /// LRM 10. Sequential statements
@@ -977,6 +1091,170 @@ pub enum ConcurrentStatement {
     ForGenerate(ForGenerateStatement),
     IfGenerate(IfGenerateStatement),
     CaseGenerate(CaseGenerateStatement),
+    Psl(PslDirective),
+}
+
+/// PSL FL occurrence and invariance operators
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PslUnary {
+    Always,
+    Never,
+    Eventually,
+    Next,
+    NextA,
+    NextE,
+    NextEvent,
+    NextEventA,
+    NextEventE,
+}
+
+/// PSL property and sequence operators
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PslBinary {
+    // ->
+    Implication,
+    // <->
+    Equivalence,
+    // |->
+    OverlappingSuffixImplication,
+    // |=>
+    SuffixImplication,
+    Until,
+    // until_
+    UntilInclusive,
+    Before,
+    // before_
+    BeforeInclusive,
+    Abort,
+    AsyncAbort,
+    SyncAbort,
+    And,
+    Or,
+    // ;
+    Concatenation,
+    // :
+    Fusion,
+    // |
+    SequenceOr,
+    // &&
+    LengthMatchingAnd,
+    // &
+    NonLengthMatchingAnd,
+    Within,
+}
+
+/// PSL SERE repetition operators
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PslRepetitionKind {
+    // [*]
+    Consecutive,
+    // [+]
+    Plus,
+    // [=]
+    NonConsecutive,
+    // [->]
+    Goto,
+}
+
+/// PSL count of a repetition or an occurrence operator
+#[derive(PartialEq, Debug, Clone)]
+pub enum PslCount {
+    Number(WithPos<Expression>),
+    /// The upper bound is None for inf
+    Range(WithPos<Expression>, Option<WithPos<Expression>>),
+}
+
+/// PSL FL occurrence and invariance properties
+#[derive(PartialEq, Debug, Clone)]
+pub struct PslUnaryExpression {
+    pub op: WithPos<PslUnary>,
+    pub strong: bool,
+    /// The boolean of next_event
+    pub event: Option<WithPos<Expression>>,
+    pub count: Option<PslCount>,
+    pub operand: WithPos<PslExpression>,
+}
+
+/// PSL binary property and sequence expressions
+#[derive(PartialEq, Debug, Clone)]
+pub struct PslBinaryExpression {
+    pub op: WithPos<PslBinary>,
+    pub strong: bool,
+    pub left: WithPos<PslExpression>,
+    pub right: WithPos<PslExpression>,
+}
+
+/// PSL SERE repetition
+#[derive(PartialEq, Debug, Clone)]
+pub struct PslRepetition {
+    pub kind: PslRepetitionKind,
+    pub count: Option<PslCount>,
+    /// A repetition without operand repeats any boolean such as [*]
+    pub operand: Option<WithPos<PslExpression>>,
+}
+
+/// PSL sequences and properties in the VHDL flavor
+#[derive(PartialEq, Debug, Clone)]
+pub enum PslExpression {
+    /// A VHDL boolean expression or an instance of a named property or sequence
+    Boolean(WithPos<Expression>),
+    /// A braced SERE
+    Sequence(Box<WithPos<PslExpression>>),
+    Unary(Box<PslUnaryExpression>),
+    Binary(Box<PslBinaryExpression>),
+    Repetition(Box<PslRepetition>),
+    /// A property or sequence clocked by @
+    Clocked(Box<WithPos<PslExpression>>, WithPos<Expression>),
+}
+
+/// PSL verification directives
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PslDirectiveKind {
+    Assert,
+    Assume,
+    Restrict,
+    Cover,
+}
+
+/// PSL verification directives
+#[derive(PartialEq, Debug, Clone)]
+pub struct PslDirective {
+    pub kind: PslDirectiveKind,
+    pub expression: WithPos<PslExpression>,
+    pub report: Option<WithPos<Expression>>,
+    pub severity: Option<WithPos<Expression>>,
+}
+
+/// PSL named properties and sequences
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PslDeclarationKind {
+    Property,
+    Sequence,
+}
+
+/// PSL formal parameters of named properties and sequences
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PslParameterKind {
+    Const,
+    Boolean,
+    Property,
+    Sequence,
+}
+
+/// PSL formal parameters of named properties and sequences
+#[derive(PartialEq, Debug, Clone)]
+pub struct PslParameter {
+    pub kind: PslParameterKind,
+    pub ident: Ident,
+}
+
+/// PSL named properties and sequences
+#[derive(PartialEq, Debug, Clone)]
+pub struct PslDeclaration {
+    pub kind: PslDeclarationKind,
+    pub ident: Ident,
+    pub parameters: Vec<PslParameter>,
+    pub expression: WithPos<PslExpression>,
 }
 
 /// LRM 11. Concurrent statements
@@ -1029,9 +1307,9 @@ pub struct PackageInstantiation {
 }
 
 /// LRM 7.3 Configuration specification
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum InstantiationList {
-    Labels(Vec<Ident>),
+    Labels(Vec<WithRef<Ident>>),
     Others,
     All,
 }
@@ -1077,8 +1355,8 @@ pub struct ConfigurationSpecification {
 #[derive(PartialEq, Debug, Clone)]
 pub enum ConfigurationDeclarativeItem {
     Use(WithPos<UseClause>),
-    // @TODO attribute
-    // @TODO group
+    Attribute(AttributeSpecification),
+    Group(GroupDeclaration),
 }
 /// LRM 3.4 Configuration declarations
 #[derive(PartialEq, Debug, Clone)]
@@ -1100,7 +1378,7 @@ pub enum ConfigurationItem {
 #[derive(PartialEq, Debug, Clone)]
 pub struct BlockConfiguration {
     pub block_spec: WithPos<Name>,
-    pub use_clauses: Vec<UseClause>,
+    pub use_clauses: Vec<WithPos<UseClause>>,
     pub items: Vec<ConfigurationItem>,
 }
 
@@ -1143,6 +1421,8 @@ pub struct PackageDeclaration {
     pub ident: Ident,
     pub generic_clause: Option<Vec<InterfaceDeclaration>>,
     pub decl: Vec<Declaration>,
+    /// VHDL-2019 private declarations only visible in the package body
+    pub private_decl: Option<Vec<Declaration>>,
 }
 
 /// LRM 4.8 Package bodies
@@ -1153,6 +1433,28 @@ pub struct PackageBody {
     pub decl: Vec<Declaration>,
 }
 
+/// PSL verification unit kinds
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum VerificationUnitKind {
+    Vunit,
+    Vmode,
+    Vprop,
+}
+
+/// PSL verification units
+#[derive(PartialEq, Debug, Clone)]
+pub struct VerificationUnit {
+    pub context_clause: ContextClause,
+    pub kind: VerificationUnitKind,
+    pub ident: Ident,
+    /// The entity and optional architecture the verification unit is bound to
+    pub entity_name: Option<WithRef<Ident>>,
+    pub architecture_name: Option<WithRef<Ident>>,
+    pub inherits: Vec<WithRef<Ident>>,
+    pub decl: Vec<Declaration>,
+    pub statements: Vec<LabeledConcurrentStatement>,
+}
+
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone)]
 pub enum AnyPrimaryUnit {
@@ -1170,6 +1472,9 @@ pub enum AnyPrimaryUnit {
 
     /// LRM 13.4 Context clauses
     Context(ContextDeclaration),
+
+    /// PSL verification units
+    VerificationUnit(VerificationUnit),
 }
 
 /// LRM 13.1 Design units
@@ -1194,4 +1499,7 @@ pub enum AnyDesignUnit {
 #[derive(PartialEq, Debug, Clone, Default)]
 pub struct DesignFile {
     pub design_units: Vec<AnyDesignUnit>,
+    /// The source text of each design unit, from the end of the previous unit
+    /// until the end of the unit itself
+    pub unit_ranges: Vec<crate::data::Range>,
 }
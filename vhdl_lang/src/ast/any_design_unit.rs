@@ -13,6 +13,7 @@ pub enum PrimaryKind {
     Package,
     PackageInstance,
     Context,
+    VerificationUnit,
 }
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
@@ -132,6 +133,7 @@ macro_rules! delegate_primary {
             AnyPrimaryUnit::PackageInstance($unit) => $block,
             AnyPrimaryUnit::Context($unit) => $block,
             AnyPrimaryUnit::Configuration($unit) => $block,
+            AnyPrimaryUnit::VerificationUnit($unit) => $block,
         }
     };
 }
@@ -178,6 +180,7 @@ impl PrimaryKind {
             AnyPrimaryUnit::Package(..) => PrimaryKind::Package,
             AnyPrimaryUnit::PackageInstance(..) => PrimaryKind::PackageInstance,
             AnyPrimaryUnit::Context(..) => PrimaryKind::Context,
+            AnyPrimaryUnit::VerificationUnit(..) => PrimaryKind::VerificationUnit,
         }
     }
 
@@ -188,6 +191,7 @@ impl PrimaryKind {
             PrimaryKind::Package => "package",
             PrimaryKind::PackageInstance => "package instance",
             PrimaryKind::Context => "context",
+            PrimaryKind::VerificationUnit => "verification unit",
         }
     }
 }
@@ -216,6 +220,28 @@ impl AnyDesignUnit {
             None
         }
     }
+
+    /// The context clause of the design unit or the items of a context declaration
+    pub fn context_clause(&self) -> &ContextClause {
+        match self {
+            AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(ref unit)) => &unit.context_clause,
+            AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(ref unit)) => &unit.context_clause,
+            AnyDesignUnit::Primary(AnyPrimaryUnit::Package(ref unit)) => &unit.context_clause,
+            AnyDesignUnit::Primary(AnyPrimaryUnit::PackageInstance(ref unit)) => {
+                &unit.context_clause
+            }
+            AnyDesignUnit::Primary(AnyPrimaryUnit::Context(ref unit)) => &unit.items,
+            AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(ref unit)) => {
+                &unit.context_clause
+            }
+            AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(ref unit)) => {
+                &unit.context_clause
+            }
+            AnyDesignUnit::Secondary(AnySecondaryUnit::PackageBody(ref unit)) => {
+                &unit.context_clause
+            }
+        }
+    }
 }
 
 /// Upper case first letter
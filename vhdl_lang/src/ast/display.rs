@@ -95,7 +95,7 @@ impl Display for AttributeName {
         if let Some(ref signature) = self.signature {
             write!(f, "{}", signature)?;
         }
-        write!(f, "'{}", self.attr)?;
+        write!(f, "'{}", self.attr.item)?;
         if let Some(ref expr) = self.expr {
             write!(f, "({})", expr)
         } else {
@@ -428,6 +428,19 @@ impl Display for Expression {
             Expression::Name(ref name) => write!(f, "{}", name),
             Expression::Literal(ref literal) => write!(f, "{}", literal),
             Expression::New(ref alloc) => write!(f, "new {}", alloc),
+            Expression::Conditional(ref conditionals) => {
+                for conditional in conditionals.conditionals.iter() {
+                    write!(
+                        f,
+                        "{} when {} else ",
+                        conditional.item, conditional.condition
+                    )?;
+                }
+                match conditionals.else_item {
+                    Some(ref item) => write!(f, "{}", item),
+                    None => Ok(()),
+                }
+            }
         }
     }
 }
@@ -728,6 +741,15 @@ impl Display for ObjectClass {
     }
 }
 
+impl Display for SignalKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SignalKind::Register => write!(f, "register"),
+            SignalKind::Bus => write!(f, "bus"),
+        }
+    }
+}
+
 impl Display for ObjectDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
@@ -735,6 +757,9 @@ impl Display for ObjectDeclaration {
             "{} {} : {}",
             self.class, self.ident, self.subtype_indication,
         )?;
+        if let Some(signal_kind) = self.signal_kind {
+            write!(f, " {}", signal_kind)?;
+        }
         match self.expression {
             Some(ref expr) => write!(f, " := {};", expr),
             None => write!(f, ";"),
@@ -879,6 +904,9 @@ impl Display for InterfaceObjectDeclaration {
                 )?;
             }
         }
+        if let Some(signal_kind) = self.signal_kind {
+            write!(f, " {}", signal_kind)?;
+        }
         match self.expression {
             Some(ref expr) => write!(f, " := {}", expr),
             None => Ok(()),
@@ -935,10 +963,69 @@ impl Display for InterfaceDeclaration {
                 }
             }
             InterfaceDeclaration::Package(ref decl) => write!(f, "{}", decl),
+            InterfaceDeclaration::View(ref decl) => write!(f, "{}", decl),
+        }
+    }
+}
+
+impl Display for InterfaceViewDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} : {}", self.ident, self.view)
+    }
+}
+
+impl Display for ModeViewIndication {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.kind {
+            ModeViewKind::Record => write!(f, "view {}", self.name)?,
+            ModeViewKind::Array => write!(f, "view ({})", self.name)?,
+        }
+        match self.subtype_indication {
+            Some(ref subtype_indication) => write!(f, " of {}", subtype_indication),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for ModeViewName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.name)?;
+        if self.converse {
+            write!(f, "'converse")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ElementMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ElementMode::Simple(ref mode) => write!(f, "{}", mode),
+            ElementMode::View(ModeViewKind::Record, ref name) => write!(f, "view {}", name),
+            ElementMode::View(ModeViewKind::Array, ref name) => write!(f, "view ({})", name),
         }
     }
 }
 
+impl Display for ModeViewDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "view {} of {} is", self.ident, self.subtype_indication)?;
+        for element in self.elements.iter() {
+            let mut first = true;
+            for name in element.names.iter() {
+                if first {
+                    write!(f, "\n  {}", name)?;
+                } else {
+                    write!(f, ", {}", name)?;
+                }
+                first = false;
+            }
+            write!(f, " : {};", element.mode)?;
+        }
+        write!(f, "\nend view;")
+    }
+}
+
 impl Display for Mode {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
@@ -1097,6 +1184,237 @@ impl Display for PackageDeclaration {
     }
 }
 
+impl Display for VerificationUnitKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            VerificationUnitKind::Vunit => write!(f, "vunit"),
+            VerificationUnitKind::Vmode => write!(f, "vmode"),
+            VerificationUnitKind::Vprop => write!(f, "vprop"),
+        }
+    }
+}
+
+impl Display for VerificationUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        // Not used: context_clause, inherits, decl, statements
+        write!(f, "{} {}", self.kind, self.ident)?;
+        if let Some(ref entity_name) = self.entity_name {
+            write!(f, " ({}", entity_name)?;
+            if let Some(ref architecture_name) = self.architecture_name {
+                write!(f, "({})", architecture_name)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for PslUnary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PslUnary::Always => write!(f, "always"),
+            PslUnary::Never => write!(f, "never"),
+            PslUnary::Eventually => write!(f, "eventually"),
+            PslUnary::Next => write!(f, "next"),
+            PslUnary::NextA => write!(f, "next_a"),
+            PslUnary::NextE => write!(f, "next_e"),
+            PslUnary::NextEvent => write!(f, "next_event"),
+            PslUnary::NextEventA => write!(f, "next_event_a"),
+            PslUnary::NextEventE => write!(f, "next_event_e"),
+        }
+    }
+}
+
+impl Display for PslBinary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PslBinary::Implication => write!(f, " -> "),
+            PslBinary::Equivalence => write!(f, " <-> "),
+            PslBinary::OverlappingSuffixImplication => write!(f, " |-> "),
+            PslBinary::SuffixImplication => write!(f, " |=> "),
+            PslBinary::Until => write!(f, " until"),
+            PslBinary::UntilInclusive => write!(f, " until_"),
+            PslBinary::Before => write!(f, " before"),
+            PslBinary::BeforeInclusive => write!(f, " before_"),
+            PslBinary::Abort => write!(f, " abort "),
+            PslBinary::AsyncAbort => write!(f, " async_abort "),
+            PslBinary::SyncAbort => write!(f, " sync_abort "),
+            PslBinary::And => write!(f, " and "),
+            PslBinary::Or => write!(f, " or "),
+            PslBinary::Concatenation => write!(f, "; "),
+            PslBinary::Fusion => write!(f, " : "),
+            PslBinary::SequenceOr => write!(f, " | "),
+            PslBinary::LengthMatchingAnd => write!(f, " && "),
+            PslBinary::NonLengthMatchingAnd => write!(f, " & "),
+            PslBinary::Within => write!(f, " within "),
+        }
+    }
+}
+
+impl Display for PslCount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PslCount::Number(ref count) => write!(f, "{}", count),
+            PslCount::Range(ref low, Some(ref high)) => write!(f, "{} to {}", low, high),
+            PslCount::Range(ref low, None) => write!(f, "{} to inf", low),
+        }
+    }
+}
+
+impl Display for PslExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PslExpression::Boolean(ref expr) => write!(f, "{}", expr),
+            PslExpression::Sequence(ref sere) => write!(f, "{{{}}}", sere),
+            PslExpression::Unary(ref unary) => {
+                write!(f, "{}", unary.op)?;
+                if unary.strong {
+                    write!(f, "!")?;
+                }
+                if let Some(ref event) = unary.event {
+                    write!(f, "({})", event)?;
+                }
+                if let Some(ref count) = unary.count {
+                    write!(f, "[{}]", count)?;
+                }
+                write!(f, " {}", unary.operand)
+            }
+            PslExpression::Binary(ref binary) => {
+                write!(f, "{}{}", binary.left, binary.op)?;
+                if binary.strong {
+                    write!(f, "!")?;
+                }
+                match binary.op.item {
+                    PslBinary::Until
+                    | PslBinary::UntilInclusive
+                    | PslBinary::Before
+                    | PslBinary::BeforeInclusive => write!(f, " {}", binary.right),
+                    _ => write!(f, "{}", binary.right),
+                }
+            }
+            PslExpression::Repetition(ref repetition) => {
+                if let Some(ref operand) = repetition.operand {
+                    write!(f, "{}", operand)?;
+                }
+                match repetition.kind {
+                    PslRepetitionKind::Consecutive => write!(f, "[*")?,
+                    PslRepetitionKind::Plus => write!(f, "[+")?,
+                    PslRepetitionKind::NonConsecutive => write!(f, "[=")?,
+                    PslRepetitionKind::Goto => write!(f, "[->")?,
+                }
+                if let Some(ref count) = repetition.count {
+                    write!(f, "{}", count)?;
+                }
+                write!(f, "]")
+            }
+            PslExpression::Clocked(ref expr, ref clock) => write!(f, "{} @ {}", expr, clock),
+        }
+    }
+}
+
+impl Display for PslParameterKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PslParameterKind::Const => write!(f, "const"),
+            PslParameterKind::Boolean => write!(f, "boolean"),
+            PslParameterKind::Property => write!(f, "property"),
+            PslParameterKind::Sequence => write!(f, "sequence"),
+        }
+    }
+}
+
+impl Display for PslDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.kind {
+            PslDeclarationKind::Property => write!(f, "property {}", self.ident)?,
+            PslDeclarationKind::Sequence => write!(f, "sequence {}", self.ident)?,
+        }
+
+        let mut first = true;
+        for parameter in &self.parameters {
+            if first {
+                write!(f, " ({} {}", parameter.kind, parameter.ident)?;
+            } else {
+                write!(f, "; {} {}", parameter.kind, parameter.ident)?;
+            }
+            first = false;
+        }
+        if !first {
+            write!(f, ")")?;
+        }
+
+        write!(f, " is {};", self.expression)
+    }
+}
+
+impl Display for EntityClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            EntityClass::Entity => write!(f, "entity"),
+            EntityClass::Architecture => write!(f, "architecture"),
+            EntityClass::Configuration => write!(f, "configuration"),
+            EntityClass::Procedure => write!(f, "procedure"),
+            EntityClass::Function => write!(f, "function"),
+            EntityClass::Package => write!(f, "package"),
+            EntityClass::Type => write!(f, "type"),
+            EntityClass::Subtype => write!(f, "subtype"),
+            EntityClass::Constant => write!(f, "constant"),
+            EntityClass::Signal => write!(f, "signal"),
+            EntityClass::Variable => write!(f, "variable"),
+            EntityClass::Component => write!(f, "component"),
+            EntityClass::Label => write!(f, "label"),
+            EntityClass::Literal => write!(f, "literal"),
+            EntityClass::Units => write!(f, "units"),
+            EntityClass::Group => write!(f, "group"),
+            EntityClass::File => write!(f, "file"),
+            EntityClass::Property => write!(f, "property"),
+            EntityClass::Sequence => write!(f, "sequence"),
+        }
+    }
+}
+
+impl Display for EntityClassEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.is_repeated {
+            write!(f, "{} <>", self.entity_class)
+        } else {
+            write!(f, "{}", self.entity_class)
+        }
+    }
+}
+
+impl Display for GroupTemplateDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "group {} is (", self.ident)?;
+        let mut first = true;
+        for entry in &self.entity_classes {
+            if first {
+                write!(f, "{}", entry)?;
+            } else {
+                write!(f, ", {}", entry)?;
+            }
+            first = false;
+        }
+        write!(f, ");")
+    }
+}
+
+impl Display for GroupDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "group {} : {} (", self.ident, self.template_name)?;
+        let mut first = true;
+        for constituent in &self.constituents {
+            if first {
+                write!(f, "{}", constituent)?;
+            } else {
+                write!(f, ", {}", constituent)?;
+            }
+            first = false;
+        }
+        write!(f, ");")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1714,6 +2032,11 @@ end units;",
         assert_format("signal foo : natural;", Code::object_decl);
     }
 
+    #[test]
+    fn test_object_declaration_signal_kind() {
+        assert_format("signal foo : natural register := 0;", Code::object_decl);
+    }
+
     #[test]
     fn test_object_declaration_variable() {
         assert_format("variable foo : natural;", Code::object_decl);
@@ -2136,4 +2459,47 @@ end package;",
             },
         );
     }
+
+    #[test]
+    fn test_psl_property_declaration() {
+        assert_format(
+            "property handshake (boolean req; boolean ack) is always req -> next_event(ack)[2] eventually! ack;",
+            |code| match code.declarative_part().remove(0) {
+                Declaration::Psl(decl) => decl,
+                decl => panic!("Expected PSL declaration, got {:?}", decl),
+            },
+        );
+    }
+
+    #[test]
+    fn test_psl_sequence_declaration() {
+        assert_format(
+            "sequence s is {req; busy[*2 to inf]; ack[->]} |=> done until! idle @ rising_edge(clk);",
+            |code| match code.declarative_part().remove(0) {
+                Declaration::Psl(decl) => decl,
+                decl => panic!("Expected PSL declaration, got {:?}", decl),
+            },
+        );
+    }
+
+    #[test]
+    fn test_group_template_declaration() {
+        assert_format("group path is (signal, label <>);", |code| {
+            match code.declarative_part().remove(0) {
+                Declaration::GroupTemplate(decl) => decl,
+                decl => panic!("Expected group template declaration, got {:?}", decl),
+            }
+        });
+    }
+
+    #[test]
+    fn test_group_declaration() {
+        assert_format(
+            "group g1 : work.pkg.path (sig, lab1, lab2);",
+            |code| match code.declarative_part().remove(0) {
+                Declaration::Group(decl) => decl,
+                decl => panic!("Expected group declaration, got {:?}", decl),
+            },
+        );
+    }
 }
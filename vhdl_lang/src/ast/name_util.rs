@@ -159,6 +159,12 @@ impl HasIdent for ContextDeclaration {
     }
 }
 
+impl HasIdent for VerificationUnit {
+    fn ident(&self) -> &Ident {
+        &self.ident
+    }
+}
+
 impl HasIdent for ConfigurationDeclaration {
     fn ident(&self) -> &Ident {
         &self.ident
@@ -173,6 +179,7 @@ impl HasIdent for AnyPrimaryUnit {
             AnyPrimaryUnit::Package(ref unit) => unit.ident(),
             AnyPrimaryUnit::PackageInstance(ref unit) => unit.ident(),
             AnyPrimaryUnit::Context(ref unit) => unit.ident(),
+            AnyPrimaryUnit::VerificationUnit(ref unit) => unit.ident(),
         }
     }
 }
@@ -44,9 +44,11 @@ pub enum FoundDeclaration<'a> {
     ElementDeclaration(&'a ElementDeclaration),
     EnumerationLiteral(&'a Ident, &'a EnumerationLiteral),
     InterfaceObject(&'a InterfaceObjectDeclaration),
+    InterfaceView(&'a InterfaceViewDeclaration),
     File(&'a FileDeclaration),
     Type(&'a TypeDeclaration),
     Component(&'a ComponentDeclaration),
+    View(&'a ModeViewDeclaration),
     //Attribute(&'a AttributeDeclaration),
     Alias(&'a AliasDeclaration),
     Function(&'a FunctionSpecification),
@@ -57,6 +59,10 @@ pub enum FoundDeclaration<'a> {
     Configuration(&'a ConfigurationDeclaration),
     Entity(&'a EntityDeclaration),
     Context(&'a ContextDeclaration),
+    VerificationUnit(&'a VerificationUnit),
+    Psl(&'a PslDeclaration),
+    GroupTemplate(&'a GroupTemplateDeclaration),
+    Group(&'a GroupDeclaration),
     ForIndex(&'a Ident, &'a DiscreteRange),
     ForGenerateIndex(&'a Option<Ident>, &'a ForGenerateStatement),
     // Not used
@@ -263,8 +269,12 @@ impl Search for LabeledSequentialStatement {
             return_if_found!(searcher.search_decl_pos(label.pos()).or_not_found());
         }
         match self.statement {
-            SequentialStatement::Return(ReturnStatement { ref expression }) => {
+            SequentialStatement::Return(ReturnStatement {
+                ref expression,
+                ref condition,
+            }) => {
                 return_if_found!(expression.search(searcher));
+                return_if_found!(condition.search(searcher));
             }
             SequentialStatement::ProcedureCall(ref pcall) => {
                 return_if_found!(pcall.search(searcher));
@@ -413,7 +423,7 @@ impl Search for LabeledConcurrentStatement {
         }
         match self.statement {
             ConcurrentStatement::Block(ref block) => {
-                // @TODO guard condition
+                return_if_found!(block.guard_condition.search(searcher));
                 return_if_found!(block.decl.search(searcher));
                 block.statements.search(searcher)
             }
@@ -474,10 +484,84 @@ impl Search for LabeledConcurrentStatement {
                 return_if_found!(severity.search(searcher));
                 NotFound
             }
+            ConcurrentStatement::Psl(ref directive) => directive.search(searcher),
+        }
+    }
+}
+
+impl Search for WithPos<PslExpression> {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_finished!(searcher.search_with_pos(&self.pos));
+        match self.item {
+            PslExpression::Boolean(ref expr) => expr.search(searcher),
+            PslExpression::Sequence(ref sere) => sere.search(searcher),
+            PslExpression::Unary(ref unary) => {
+                let PslUnaryExpression {
+                    event,
+                    count,
+                    operand,
+                    ..
+                } = unary.as_ref();
+                return_if_found!(event.search(searcher));
+                return_if_found!(count.search(searcher));
+                operand.search(searcher)
+            }
+            PslExpression::Binary(ref binary) => {
+                return_if_found!(binary.left.search(searcher));
+                binary.right.search(searcher)
+            }
+            PslExpression::Repetition(ref repetition) => {
+                return_if_found!(repetition.operand.search(searcher));
+                repetition.count.search(searcher)
+            }
+            PslExpression::Clocked(ref expr, ref clock) => {
+                return_if_found!(expr.search(searcher));
+                clock.search(searcher)
+            }
         }
     }
 }
 
+impl Search for PslCount {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            PslCount::Number(ref expr) => expr.search(searcher),
+            PslCount::Range(ref low, ref high) => {
+                return_if_found!(low.search(searcher));
+                high.search(searcher)
+            }
+        }
+    }
+}
+
+impl Search for PslDirective {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        let PslDirective {
+            kind: _,
+            expression,
+            report,
+            severity,
+        } = self;
+        return_if_found!(expression.search(searcher));
+        return_if_found!(report.search(searcher));
+        severity.search(searcher)
+    }
+}
+
+impl Search for PslDeclaration {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_found!(searcher
+            .search_decl(self.ident.pos(), FoundDeclaration::Psl(self))
+            .or_not_found());
+        for param in self.parameters.iter() {
+            return_if_found!(searcher
+                .search_decl(param.ident.pos(), FoundDeclaration::Psl(self))
+                .or_not_found());
+        }
+        self.expression.search(searcher)
+    }
+}
+
 impl Search for WithPos<WithRef<Designator>> {
     fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
         return_if_finished!(searcher.search_with_pos(&self.pos));
@@ -528,15 +612,7 @@ fn search_pos_name(pos: &SrcPos, name: &Name, searcher: &mut impl Searcher) -> S
             NotFound
         }
         Name::FunctionCall(ref fcall) => fcall.search(searcher),
-        Name::Attribute(ref attr) => {
-            // @TODO more
-            let AttributeName { name, expr, .. } = attr.as_ref();
-            return_if_found!(name.search(searcher));
-            if let Some(expr) = expr {
-                return_if_found!(expr.search(searcher));
-            }
-            NotFound
-        }
+        Name::Attribute(ref attr) => attr.search(searcher),
         Name::External(ref ename) => {
             let ExternalName { subtype, .. } = ename.as_ref();
             return_if_found!(subtype.search(searcher));
@@ -617,9 +693,21 @@ impl Search for RangeConstraint {
 
 impl Search for AttributeName {
     fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
-        // @TODO more
-        let AttributeName { name, .. } = self;
-        name.search(searcher)
+        let AttributeName {
+            name,
+            signature,
+            attr,
+            expr,
+        } = self;
+        return_if_found!(name.search(searcher));
+        if let Some(signature) = signature {
+            return_if_found!(signature.item.search(searcher));
+        }
+        return_if_found!(searcher.search_ident_ref(attr).or_not_found());
+        if let Some(expr) = expr {
+            return_if_found!(expr.search(searcher));
+        }
+        NotFound
     }
 }
 
@@ -772,6 +860,9 @@ fn search_pos_expr(pos: &SrcPos, expr: &Expression, searcher: &mut impl Searcher
             }
         }
         Expression::Literal(_) => NotFound,
+        Expression::Conditional(ref conditionals) => {
+            search_conditionals(conditionals, true, searcher)
+        }
     }
 }
 
@@ -906,9 +997,7 @@ impl Search for Declaration {
                     return_if_found!(signature.item.search(searcher));
                 }
             }
-            Declaration::Use(use_clause) => return_if_found!(searcher
-                .search_with_pos(&use_clause.pos)
-                .or_else(|| use_clause.item.name_list.search(searcher))),
+            Declaration::Use(use_clause) => return_if_found!(use_clause.search(searcher)),
             Declaration::Component(component) => {
                 let ComponentDeclaration {
                     ident,
@@ -937,6 +1026,57 @@ impl Search for Declaration {
                 return_if_found!(file_name.search(searcher));
             }
 
+            Declaration::View(view) => {
+                let ModeViewDeclaration {
+                    ident,
+                    subtype_indication,
+                    elements,
+                } = view;
+                return_if_found!(searcher
+                    .search_decl(ident.pos(), FoundDeclaration::View(view))
+                    .or_not_found());
+                return_if_found!(subtype_indication.search(searcher));
+                for ModeViewElement { names, mode } in elements.iter() {
+                    for name in names.iter() {
+                        return_if_found!(searcher.search_ident_ref(name).or_not_found());
+                    }
+                    if let ElementMode::View(_, ref view_name) = mode {
+                        return_if_found!(view_name.name.search(searcher));
+                    }
+                }
+            }
+
+            Declaration::Attribute(Attribute::Specification(spec)) => {
+                return_if_found!(spec.search(searcher));
+            }
+
+            Declaration::GroupTemplate(template) => {
+                return_if_found!(searcher
+                    .search_decl(
+                        template.ident.pos(),
+                        FoundDeclaration::GroupTemplate(template)
+                    )
+                    .or_not_found());
+            }
+
+            Declaration::Group(group) => return_if_found!(group.search(searcher)),
+
+            Declaration::Psl(decl) => return_if_found!(decl.search(searcher)),
+            Declaration::DefaultClock(clock) => return_if_found!(clock.search(searcher)),
+
+            Declaration::Disconnection(disconnection) => {
+                let DisconnectionSpecification {
+                    signal_list,
+                    type_mark,
+                    after,
+                } = disconnection;
+                if let GuardedSignalList::Names(names) = signal_list {
+                    return_if_found!(names.search(searcher));
+                }
+                return_if_found!(type_mark.search(searcher));
+                return_if_found!(after.search(searcher));
+            }
+
             // @TODO more
             _ => {}
         }
@@ -944,6 +1084,47 @@ impl Search for Declaration {
     }
 }
 
+impl Search for WithPos<UseClause> {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        searcher
+            .search_with_pos(&self.pos)
+            .or_else(|| self.item.name_list.search(searcher))
+    }
+}
+
+impl Search for AttributeSpecification {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        let AttributeSpecification {
+            ident,
+            entity_name,
+            entity_class: _,
+            expr,
+        } = self;
+        return_if_found!(searcher.search_ident_ref(ident).or_not_found());
+        if let EntityName::Name(EntityTag { designator, .. }) = entity_name {
+            return_if_found!(searcher
+                .search_designator_ref(&designator.pos, &designator.item)
+                .or_not_found());
+        }
+        expr.search(searcher)
+    }
+}
+
+impl Search for GroupDeclaration {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        let GroupDeclaration {
+            ident,
+            template_name,
+            constituents,
+        } = self;
+        return_if_found!(searcher
+            .search_decl(ident.pos(), FoundDeclaration::Group(self))
+            .or_not_found());
+        return_if_found!(template_name.search(searcher));
+        constituents.search(searcher)
+    }
+}
+
 impl Search for InterfaceDeclaration {
     fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
         match self {
@@ -957,6 +1138,13 @@ impl Search for InterfaceDeclaration {
             InterfaceDeclaration::Subprogram(ref decl, _) => {
                 return_if_found!(decl.search(searcher));
             }
+            InterfaceDeclaration::View(ref decl) => {
+                return_if_found!(searcher
+                    .search_decl(decl.ident.pos(), FoundDeclaration::InterfaceView(decl))
+                    .or_not_found());
+                return_if_found!(decl.view.name.name.search(searcher));
+                return_if_found!(decl.view.subtype_indication.search(searcher));
+            }
             _ => {}
         };
         NotFound
@@ -1071,7 +1259,8 @@ impl Search for PackageDeclaration {
             .search_decl(self.ident().pos(), FoundDeclaration::Package(self))
             .or_not_found());
         return_if_found!(self.generic_clause.search(searcher));
-        self.decl.search(searcher)
+        return_if_found!(self.decl.search(searcher));
+        self.private_decl.search(searcher)
     }
 }
 
@@ -1102,7 +1291,69 @@ impl Search for ConfigurationDeclaration {
         return_if_found!(searcher
             .search_decl(self.ident().pos(), FoundDeclaration::Configuration(self))
             .or_not_found());
-        self.entity_name.search(searcher)
+        return_if_found!(self.entity_name.search(searcher));
+        return_if_found!(self.decl.search(searcher));
+        self.block_config.search(searcher)
+    }
+}
+
+impl Search for ConfigurationDeclarativeItem {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            ConfigurationDeclarativeItem::Use(use_clause) => use_clause.search(searcher),
+            ConfigurationDeclarativeItem::Attribute(spec) => spec.search(searcher),
+            ConfigurationDeclarativeItem::Group(group) => group.search(searcher),
+        }
+    }
+}
+
+impl Search for BlockConfiguration {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        let BlockConfiguration {
+            block_spec: _,
+            use_clauses,
+            items,
+        } = self;
+        return_if_found!(use_clauses.search(searcher));
+        items.search(searcher)
+    }
+}
+
+impl Search for ConfigurationItem {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        match self {
+            ConfigurationItem::Block(block_config) => block_config.search(searcher),
+            ConfigurationItem::Component(component_config) => {
+                let ComponentConfiguration {
+                    spec,
+                    bind_ind,
+                    vunit_bind_inds: _,
+                    block_config,
+                } = component_config;
+                let ComponentSpecification {
+                    instantiation_list,
+                    component_name,
+                } = spec;
+                if let InstantiationList::Labels(labels) = instantiation_list {
+                    for label in labels.iter() {
+                        return_if_found!(searcher.search_ident_ref(label).or_not_found());
+                    }
+                }
+                return_if_found!(component_name.search(searcher));
+                if let Some(bind_ind) = bind_ind {
+                    match bind_ind.entity_aspect {
+                        Some(EntityAspect::Entity(ref entity_name, _)) => {
+                            return_if_found!(entity_name.search(searcher))
+                        }
+                        Some(EntityAspect::Configuration(ref config_name)) => {
+                            return_if_found!(config_name.search(searcher))
+                        }
+                        Some(EntityAspect::Open) | None => {}
+                    }
+                }
+                block_config.search(searcher)
+            }
+        }
     }
 }
 
@@ -1116,6 +1367,27 @@ impl Search for ContextDeclaration {
     }
 }
 
+impl Search for VerificationUnit {
+    fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_finished!(searcher.search_source(self.source()));
+        return_if_found!(self.context_clause.search(searcher));
+        return_if_found!(searcher
+            .search_decl(self.ident().pos(), FoundDeclaration::VerificationUnit(self))
+            .or_not_found());
+        if let Some(ref entity_name) = self.entity_name {
+            return_if_found!(searcher.search_ident_ref(entity_name).or_not_found());
+        }
+        if let Some(ref architecture_name) = self.architecture_name {
+            return_if_found!(searcher.search_ident_ref(architecture_name).or_not_found());
+        }
+        for inherit in self.inherits.iter() {
+            return_if_found!(searcher.search_ident_ref(inherit).or_not_found());
+        }
+        return_if_found!(self.decl.search(searcher));
+        self.statements.search(searcher)
+    }
+}
+
 impl Search for CaseStatement {
     fn search(&self, searcher: &mut impl Searcher) -> SearchResult {
         let CaseStatement {
@@ -1232,6 +1504,9 @@ impl Searcher for FormatDeclaration {
                     InterfaceListType::Generic => format!("```vhdl\ngeneric {};\n```", value),
                     InterfaceListType::Parameter => format!("```vhdl\n{};\n```", value),
                 },
+                FoundDeclaration::InterfaceView(ref value) => {
+                    format!("```vhdl\nport {};\n```", value)
+                }
                 FoundDeclaration::ForIndex(ref ident, ref drange) => {
                     format!("```vhdl\nfor {} in {} loop\n```", ident, drange)
                 }
@@ -1266,6 +1541,9 @@ impl Searcher for FormatDeclaration {
                 FoundDeclaration::Component(ref value) => {
                     format!("```vhdl\n{}\n```", value)
                 }
+                FoundDeclaration::View(ref value) => {
+                    format!("```vhdl\n{}\n```", value)
+                }
                 FoundDeclaration::Alias(ref value) => {
                     format!("```vhdl\n{}\n```", value)
                 }
@@ -1284,6 +1562,18 @@ impl Searcher for FormatDeclaration {
                 FoundDeclaration::Context(ref value) => {
                     format!("```vhdl\n{}\n```", value)
                 }
+                FoundDeclaration::VerificationUnit(ref value) => {
+                    format!("```vhdl\n{}\n```", value)
+                }
+                FoundDeclaration::GroupTemplate(ref value) => {
+                    format!("```vhdl\n{}\n```", value)
+                }
+                FoundDeclaration::Group(ref value) => {
+                    format!("```vhdl\n{}\n```", value)
+                }
+                FoundDeclaration::Psl(ref value) => {
+                    format!("```vhdl\n{}\n```", value)
+                }
             });
             Finished(Found)
         } else {
@@ -7,24 +7,56 @@
 //! Configuration of the design hierarchy and other settings
 
 use crate::data::*;
-use fnv::FnvHashMap;
+use crate::standard::VHDLStandard;
+use fnv::{FnvHashMap, FnvHashSet};
 use std::env;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use toml::Value;
 
+mod import;
+
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct Config {
     // A map from library name to file name
     libraries: FnvHashMap<String, LibraryConfig>,
+    // The library of files which are not matched by any library
+    default_library: Option<String>,
+    lint: LintConfig,
+    // The values of conditional analysis identifiers by upper case name
+    conditional_analysis: FnvHashMap<String, String>,
+    // Included configuration files which are loaded by read_file_path
+    includes: Vec<PathBuf>,
+}
+
+/// The configured severity of diagnostics per rule code
+/// A severity of None means that the rule is turned off
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct LintConfig {
+    severities: FnvHashMap<ErrorCode, Option<Severity>>,
 }
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct LibraryConfig {
     name: String,
-    patterns: Vec<String>,
+    patterns: Vec<FilePattern>,
+    exclude: Vec<String>,
+    // Patterns which could not be resolved, such as those using an unset environment variable
+    unresolved: Vec<String>,
+    standard: Option<VHDLStandard>,
+    is_third_party: bool,
+    encoding: Option<Encoding>,
+    // The configuration file which defined the library
+    defined_in: Option<PathBuf>,
+}
+
+/// A file name or glob pattern with an optional standard overriding the library standard
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct FilePattern {
+    pattern: String,
+    standard: Option<VHDLStandard>,
 }
 
 impl LibraryConfig {
@@ -32,26 +64,32 @@ impl LibraryConfig {
     /// Only include files that exists
     /// Files that do not exist produce a warning message
     pub fn file_names(&self, messages: &mut dyn MessageHandler) -> Vec<PathBuf> {
-        fn as_abspath(file_path: &Path) -> Result<PathBuf, Message> {
-            match dunce::canonicalize(file_path) {
-                Ok(file_path) => Ok(file_path),
-                Err(err) => Err(Message::error(format!(
-                    "Could not create absolute path {}: {:?}",
-                    file_path.to_string_lossy(),
-                    err
-                ))),
-            }
+        self.files(messages)
+            .into_iter()
+            .map(|(file_name, _)| file_name)
+            .collect()
+    }
+
+    /// Return a vector of file names together with the VHDL standard to use for each file
+    /// The standard of a file pattern takes precedence over the standard of the library
+    /// Files matching an exclude pattern are not included
+    /// Patterns which could not be resolved produce a warning message
+    pub fn files(&self, messages: &mut dyn MessageHandler) -> Vec<(PathBuf, VHDLStandard)> {
+        for err in self.unresolved.iter() {
+            messages.push(Message::warning(err.clone()));
         }
 
         let mut result = Vec::new();
-        for pattern in self.patterns.iter() {
+        for FilePattern { pattern, standard } in self.patterns.iter() {
+            let standard = standard.or(self.standard).unwrap_or_default();
+
             if is_literal(pattern, cfg!(windows)) {
                 let file_path = Path::new(pattern);
 
                 if file_path.exists() {
                     match as_abspath(file_path) {
                         Ok(abs_path) => {
-                            result.push(abs_path);
+                            result.push((abs_path, standard));
                         }
                         Err(msg) => {
                             messages.push(msg);
@@ -73,7 +111,7 @@ impl LibraryConfig {
                                 Ok(file_path) => {
                                     match as_abspath(&file_path) {
                                         Ok(abs_path) => {
-                                            result.push(abs_path);
+                                            result.push((abs_path, standard));
                                         }
                                         Err(msg) => {
                                             messages.push(msg);
@@ -102,38 +140,354 @@ impl LibraryConfig {
                 }
             }
         }
+
+        let excluded = self.excluded_paths(messages);
+        if !excluded.is_empty() {
+            // A file is also excluded when one of its parent directories is
+            result.retain(|(file_name, _)| {
+                !file_name.ancestors().any(|path| excluded.contains(path))
+            });
+        }
+
         Self::remove_duplicates(result)
     }
 
+    /// Return the set of existing files and directories matching any of the exclude patterns
+    fn excluded_paths(&self, messages: &mut dyn MessageHandler) -> FnvHashSet<PathBuf> {
+        let mut excluded = FnvHashSet::default();
+
+        for pattern in self.exclude.iter() {
+            match glob::glob(trim_trailing_separator(pattern)) {
+                Ok(paths) => {
+                    for file_path in paths.flatten() {
+                        match as_abspath(&file_path) {
+                            Ok(abs_path) => {
+                                excluded.insert(abs_path);
+                            }
+                            Err(msg) => {
+                                messages.push(msg);
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    messages.push(Message::error(format!(
+                        "Invalid exclude pattern '{}' {}",
+                        pattern, err
+                    )));
+                }
+            }
+        }
+
+        excluded
+    }
+
     /// Remove duplicate file names from the result
-    fn remove_duplicates(file_names: Vec<PathBuf>) -> Vec<PathBuf> {
-        let mut result = Vec::with_capacity(file_names.len());
+    fn remove_duplicates(files: Vec<(PathBuf, VHDLStandard)>) -> Vec<(PathBuf, VHDLStandard)> {
+        let mut result = Vec::with_capacity(files.len());
         let mut fileset = std::collections::HashSet::new();
 
-        for file_name in file_names.into_iter() {
+        for (file_name, standard) in files.into_iter() {
             if fileset.insert(file_name.clone()) {
-                result.push(file_name);
+                result.push((file_name, standard));
             }
         }
         result
     }
 
+    /// Return the VHDL standard of the file if it is matched by any file pattern of the library
+    /// The file does not need to exist which allows mapping new files to the library
+    pub fn match_file(&self, file_name: &Path) -> Option<VHDLStandard> {
+        if self.exclude.iter().any(|pattern| {
+            let pattern = trim_trailing_separator(pattern);
+            file_name
+                .ancestors()
+                .any(|path| pattern_matches(pattern, path))
+        }) {
+            return None;
+        }
+
+        self.patterns
+            .iter()
+            .find(|file_pattern| pattern_matches(&file_pattern.pattern, file_name))
+            .map(|file_pattern| file_pattern.standard.or(self.standard).unwrap_or_default())
+    }
+
+    /// Returns true if the other library has the same files and settings
+    /// regardless of where it was defined
+    /// The patterns are compared with their folders resolved such that the same files
+    /// written relative to different configuration files are the same definition
+    fn has_same_definition(&self, other: &LibraryConfig) -> bool {
+        let patterns = |library: &LibraryConfig| -> Vec<(String, Option<VHDLStandard>)> {
+            library
+                .patterns
+                .iter()
+                .map(|file_pattern| {
+                    (
+                        canonical_pattern(&file_pattern.pattern),
+                        file_pattern.standard,
+                    )
+                })
+                .collect()
+        };
+        let exclude = |library: &LibraryConfig| -> Vec<String> {
+            library
+                .exclude
+                .iter()
+                .map(|pattern| canonical_pattern(pattern))
+                .collect()
+        };
+
+        patterns(self) == patterns(other)
+            && exclude(self) == exclude(other)
+            && self.unresolved == other.unresolved
+            && self.standard == other.standard
+            && self.is_third_party == other.is_third_party
+            && self.encoding == other.encoding
+    }
+
     /// Returns the name of the library
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
+
+    /// Returns the VHDL standard of the library if it was configured
+    pub fn standard(&self) -> Option<VHDLStandard> {
+        self.standard
+    }
+
+    /// Returns true if the library is third-party code whose diagnostics shall not be reported
+    pub fn is_third_party(&self) -> bool {
+        self.is_third_party
+    }
+
+    /// Returns the encoding of the library files if it was configured,
+    /// otherwise the encoding is detected for each file
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.encoding
+    }
+}
+
+impl LintConfig {
+    /// Set the severity of a rule, None turns the rule off
+    pub fn set_severity(&mut self, code: ErrorCode, severity: Option<Severity>) {
+        self.severities.insert(code, severity);
+    }
+
+    /// Apply the configured severity to a diagnostic
+    /// Returns None if the rule of the diagnostic is turned off
+    pub fn apply(&self, diagnostic: Diagnostic) -> Option<Diagnostic> {
+        let code = if let Some(code) = diagnostic.code {
+            code
+        } else {
+            return Some(diagnostic);
+        };
+
+        match self.severities.get(&code) {
+            Some(Some(severity)) => Some(Diagnostic {
+                severity: *severity,
+                ..diagnostic
+            }),
+            Some(None) => None,
+            None => Some(diagnostic),
+        }
+    }
+
+    fn append(&mut self, lint: &LintConfig) {
+        for (code, severity) in lint.severities.iter() {
+            self.severities.insert(*code, *severity);
+        }
+    }
+}
+
+fn parse_lint(value: &Value) -> Result<LintConfig, String> {
+    let mut lint = LintConfig::default();
+
+    let rules = value.as_table().ok_or("lint must be a table")?;
+    for (name, severity) in rules.iter() {
+        let code = name.parse()?;
+        let severity = match severity.as_str() {
+            Some("error") => Some(Severity::Error),
+            Some("warning") => Some(Severity::Warning),
+            Some("info") => Some(Severity::Info),
+            Some("hint") => Some(Severity::Hint),
+            Some("off") => None,
+            _ => {
+                return Err(format!(
+                    "Invalid severity {} for lint rule '{}', expected 'error', 'warning', 'info', 'hint' or 'off'",
+                    severity, name
+                ))
+            }
+        };
+        lint.set_severity(code, severity);
+    }
+
+    Ok(lint)
+}
+
+/// Parse the values of conditional analysis identifiers such as TOOL_TYPE = 'SIMULATION'
+fn parse_conditional_analysis(value: &Value) -> Result<FnvHashMap<String, String>, String> {
+    let table = value
+        .as_table()
+        .ok_or("conditional_analysis must be a table")?;
+
+    let mut identifiers = FnvHashMap::default();
+    for (name, value) in table.iter() {
+        let value = value.as_str().ok_or_else(|| {
+            format!(
+                "Conditional analysis identifier {} must be a string, got {}",
+                name, value
+            )
+        })?;
+        identifiers.insert(name.to_uppercase(), value.to_owned());
+    }
+    Ok(identifiers)
+}
+
+fn as_abspath(file_path: &Path) -> Result<PathBuf, Message> {
+    match dunce::canonicalize(file_path) {
+        Ok(file_path) => Ok(file_path),
+        Err(err) => Err(Message::error(format!(
+            "Could not create absolute path {}: {:?}",
+            file_path.to_string_lossy(),
+            err
+        ))),
+    }
+}
+
+/// Expand environment variables written as $NAME or ${NAME}
+fn expand_env_vars(path: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(idx) = rest.find('$') {
+        result.push_str(&rest[..idx]);
+        rest = &rest[idx + 1..];
+
+        let (name, remaining) = if rest.starts_with('{') {
+            let end = rest.find('}').ok_or_else(|| {
+                format!("Missing closing '}}' of environment variable in '{}'", path)
+            })?;
+            (&rest[1..end], &rest[end + 1..])
+        } else {
+            let end = rest
+                .find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_'))
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+
+        if name.is_empty() {
+            // A lone $ is kept as is
+            result.push('$');
+            continue;
+        }
+
+        let value = env::var(name).map_err(|_| {
+            format!(
+                "Environment variable '{}' used in '{}' is not set",
+                name, path
+            )
+        })?;
+        result.push_str(&value);
+        rest = remaining;
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Expand environment variables and make the path relative to the parent folder
+fn resolve_path(parent: &Path, path: &str) -> Result<String, String> {
+    let path = parent.join(expand_env_vars(path)?);
+    Ok(path
+        .to_str()
+        .ok_or_else(|| format!("Could not convert {:?} to string", path))?
+        .to_owned())
+}
+
+/// Parse an optional field which is either a file name or an array of file names
+fn parse_paths(config: &Value, field: &str, parent: &Path) -> Result<Vec<String>, String> {
+    let value = match config.get(field) {
+        Some(value) => value,
+        None => return Ok(Vec::new()),
+    };
+
+    let file_names = if let Some(file_name) = value.as_str() {
+        vec![file_name]
+    } else {
+        let mut file_names = Vec::new();
+        for file_name in value
+            .as_array()
+            .ok_or_else(|| format!("{} must be a string or an array, got {}", field, value))?
+            .iter()
+        {
+            file_names.push(
+                file_name
+                    .as_str()
+                    .ok_or_else(|| format!("not a string {}", file_name))?,
+            );
+        }
+        file_names
+    };
+
+    file_names
+        .into_iter()
+        .map(|file_name| resolve_path(parent, file_name))
+        .collect()
+}
+
+/// Parse the optional is_third_party field of a library table
+fn parse_is_third_party(value: &Value) -> Result<bool, String> {
+    if let Some(is_third_party) = value.get("is_third_party") {
+        is_third_party
+            .as_bool()
+            .ok_or_else(|| format!("is_third_party must be a boolean, got {}", is_third_party))
+    } else {
+        Ok(false)
+    }
+}
+
+/// Parse the optional encoding field of a library table
+fn parse_encoding(value: &Value) -> Result<Option<Encoding>, String> {
+    if let Some(encoding) = value.get("encoding") {
+        let encoding = encoding
+            .as_str()
+            .ok_or_else(|| format!("encoding must be a string, got {}", encoding))?;
+        Ok(Some(encoding.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse the optional standard field of a library or file table
+fn parse_standard(value: &Value) -> Result<Option<VHDLStandard>, String> {
+    if let Some(standard) = value.get("standard") {
+        let standard = standard
+            .as_str()
+            .ok_or_else(|| format!("standard must be a string, got {}", standard))?;
+        Ok(Some(standard.parse()?))
+    } else {
+        Ok(None)
+    }
 }
 
 impl Config {
     pub fn from_str(string: &str, parent: &Path) -> Result<Config, String> {
         let config = string.parse::<Value>().map_err(|err| err.to_string())?;
+        Config::from_toml(&config, parent)
+    }
+
+    /// Create configuration from a TOML value such as settings sent by a language client
+    /// File names are relative to the parent folder
+    pub fn from_toml(config: &Value, parent: &Path) -> Result<Config, String> {
         let mut libraries = FnvHashMap::default();
 
-        let libs = config
-            .get("libraries")
-            .ok_or("missing field libraries")?
-            .as_table()
-            .ok_or("libraries must be a table")?;
+        // A configuration might only contain other settings such as lint severities
+        let no_libraries = toml::value::Table::new();
+        let libs = match config.get("libraries") {
+            Some(libs) => libs.as_table().ok_or("libraries must be a table")?,
+            None => &no_libraries,
+        };
 
         for (name, lib) in libs.iter() {
             let file_arr = lib
@@ -142,18 +496,49 @@ impl Config {
                 .as_array()
                 .ok_or_else(|| format!("files for library {} is not array", name))?;
 
+            // A pattern which cannot be resolved only skips that pattern
+            let mut unresolved = Vec::new();
+            let mut skip_pattern = |err: String| {
+                unresolved.push(format!("{}, skipping pattern of library {}", err, name));
+            };
+
             let mut patterns = Vec::new();
             for file in file_arr.iter() {
-                let file = file
-                    .as_str()
-                    .ok_or_else(|| format!("not a string {}", file))?;
+                // A file is either a string or a table with a path and an optional standard
+                let (path, standard) = if file.is_table() {
+                    let path = file
+                        .get("path")
+                        .ok_or_else(|| format!("missing field path in {}", file))?;
+                    (path, parse_standard(file)?)
+                } else {
+                    (file, None)
+                };
 
-                let path = parent.join(file);
                 let path = path
-                    .to_str()
-                    .ok_or_else(|| format!("Could not convert {:?} to string", path))?
-                    .to_owned();
-                patterns.push(path);
+                    .as_str()
+                    .ok_or_else(|| format!("not a string {}", path))?;
+
+                match resolve_path(parent, path) {
+                    Ok(pattern) => patterns.push(FilePattern { pattern, standard }),
+                    Err(err) => skip_pattern(err),
+                }
+            }
+
+            let mut exclude = Vec::new();
+            if let Some(exclude_arr) = lib.get("exclude") {
+                let exclude_arr = exclude_arr
+                    .as_array()
+                    .ok_or_else(|| format!("exclude for library {} is not array", name))?;
+
+                for path in exclude_arr.iter() {
+                    let path = path
+                        .as_str()
+                        .ok_or_else(|| format!("not a string {}", path))?;
+                    match resolve_path(parent, path) {
+                        Ok(pattern) => exclude.push(pattern),
+                        Err(err) => skip_pattern(err),
+                    }
+                }
             }
 
             libraries.insert(
@@ -161,21 +546,177 @@ impl Config {
                 LibraryConfig {
                     name: name.to_owned(),
                     patterns,
+                    exclude,
+                    unresolved,
+                    standard: parse_standard(lib)
+                        .map_err(|err| format!("{} for library {}", err, name))?,
+                    is_third_party: parse_is_third_party(lib)
+                        .map_err(|err| format!("{} for library {}", err, name))?,
+                    encoding: parse_encoding(lib)
+                        .map_err(|err| format!("{} for library {}", err, name))?,
+                    defined_in: None,
                 },
             );
         }
 
-        Ok(Config { libraries })
+        let default_library = if let Some(default_library) = config.get("default_library") {
+            let default_library = default_library.as_str().ok_or_else(|| {
+                format!("default_library must be a string, got {}", default_library)
+            })?;
+            Some(default_library.to_owned())
+        } else {
+            None
+        };
+
+        let lint = if let Some(lint) = config.get("lint") {
+            parse_lint(lint)?
+        } else {
+            LintConfig::default()
+        };
+
+        let conditional_analysis = if let Some(value) = config.get("conditional_analysis") {
+            parse_conditional_analysis(value)?
+        } else {
+            FnvHashMap::default()
+        };
+
+        // Libraries defined in the file take precedence over imported libraries
+        for import_file in parse_paths(config, "import", parent)?.iter() {
+            let imported = import::import_file(Path::new(import_file))?;
+            for (name, library) in imported.libraries.into_iter() {
+                libraries.entry(name).or_insert(library);
+            }
+        }
+
+        let includes = parse_paths(config, "include", parent)?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+
+        Ok(Config {
+            libraries,
+            default_library,
+            lint,
+            conditional_analysis,
+            includes,
+        })
+    }
+
+    /// Read a configuration file and the files it includes
+    /// The including file takes precedence over the included files
+    pub fn read_file_path(
+        file_name: &Path,
+        messages: &mut dyn MessageHandler,
+    ) -> io::Result<Config> {
+        Self::read_file_path_included(file_name, &mut Vec::new(), messages)
     }
 
-    pub fn read_file_path(file_name: &Path) -> io::Result<Config> {
+    fn read_file_path_included(
+        file_name: &Path,
+        include_stack: &mut Vec<PathBuf>,
+        messages: &mut dyn MessageHandler,
+    ) -> io::Result<Config> {
         let mut file = File::open(file_name)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
         let parent = file_name.parent().unwrap();
 
-        Config::from_str(&contents, parent).map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))
+        let mut config = Config::from_str(&contents, parent)
+            .map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))?;
+        for library in config.libraries.values_mut() {
+            library.defined_in = Some(file_name.to_owned());
+        }
+
+        if config.includes.is_empty() {
+            return Ok(config);
+        }
+
+        include_stack.push(dunce::canonicalize(file_name)?);
+        let result = Self::append_includes(
+            config,
+            &file_name.to_string_lossy(),
+            include_stack,
+            messages,
+        );
+        include_stack.pop();
+        Ok(result)
+    }
+
+    /// Read the configuration files included by a configuration from client settings
+    /// The including configuration takes precedence over the included files
+    pub fn read_includes(self, messages: &mut dyn MessageHandler) -> Config {
+        Self::append_includes(self, "client settings", &mut Vec::new(), messages)
+    }
+
+    fn append_includes(
+        mut config: Config,
+        included_from: &str,
+        include_stack: &mut Vec<PathBuf>,
+        messages: &mut dyn MessageHandler,
+    ) -> Config {
+        let includes = std::mem::take(&mut config.includes);
+
+        let mut result = Config::default();
+        for include in includes.iter() {
+            if let Ok(abs_include) = dunce::canonicalize(include) {
+                if include_stack.contains(&abs_include) {
+                    messages.push(Message::error(format!(
+                        "Circular include of configuration file {} from {}",
+                        include.to_string_lossy(),
+                        included_from
+                    )));
+                    continue;
+                }
+            }
+
+            match Self::read_file_path_included(include, include_stack, messages) {
+                Ok(included_config) => {
+                    result.append(&included_config, messages);
+                }
+                Err(ref err) => {
+                    messages.push(Message::error(format!(
+                        "Error while loading configuration file {} included from {}: {}",
+                        include.to_string_lossy(),
+                        included_from,
+                        err
+                    )));
+                }
+            }
+        }
+
+        result.append(&config, messages);
+        result
+    }
+
+    /// Find the vhdl_ls.toml files of sub projects below the root folder
+    /// Hidden folders are ignored and the files are sorted from the outermost to the innermost
+    pub fn find_nested_config_files(root: &Path) -> Vec<PathBuf> {
+        let pattern = root.join("**").join("vhdl_ls.toml");
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: true,
+        };
+
+        let root_config = root.join("vhdl_ls.toml");
+        let mut file_names: Vec<PathBuf> =
+            match glob::glob_with(&pattern.to_string_lossy(), options) {
+                Ok(paths) => paths
+                    .flatten()
+                    .filter(|file_name| *file_name != root_config)
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+
+        file_names.sort_by_key(|file_name| (file_name.components().count(), file_name.clone()));
+        file_names
+    }
+
+    /// Import the libraries of a VUnit --export-json file, a FuseSoC .core file,
+    /// a Vivado .xpr project or a Quartus .qsf file
+    pub fn import_file(file_name: &Path) -> io::Result<Config> {
+        import::import_file(file_name).map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))
     }
 
     pub fn get_library<'a>(&'a self, name: &str) -> Option<&'a LibraryConfig> {
@@ -186,28 +727,69 @@ impl Config {
         self.libraries.values()
     }
 
+    pub fn lint(&self) -> &LintConfig {
+        &self.lint
+    }
+
+    /// The configured values of conditional analysis identifiers by upper case name
+    pub fn conditional_analysis(&self) -> &FnvHashMap<String, String> {
+        &self.conditional_analysis
+    }
+
+    /// The library of files which are not matched by the file patterns of any library
+    pub fn default_library(&self) -> Option<&str> {
+        self.default_library.as_deref()
+    }
+
+    /// Return the names of all libraries whose file patterns match the file
+    /// together with the VHDL standard of the file, sorted by library name
+    pub fn map_file(&self, file_name: &Path) -> Vec<(&str, VHDLStandard)> {
+        let mut result: Vec<_> = self
+            .iter_libraries()
+            .filter_map(|library| {
+                library
+                    .match_file(file_name)
+                    .map(|standard| (library.name(), standard))
+            })
+            .collect();
+        result.sort_unstable_by_key(|(name, _)| *name);
+        result
+    }
+
     /// Append another config to self
     ///
     /// In case of conflict the appended config takes precedence
     pub fn append(&mut self, config: &Config, messages: &mut dyn MessageHandler) {
         for library in config.iter_libraries() {
             if let Some(parent_library) = self.libraries.get_mut(&library.name) {
-                *parent_library = library.clone();
+                // The same definition might be loaded twice such as when a file is both
+                // included and found as a nested configuration file
+                if !parent_library.has_same_definition(library) {
+                    let message = match (&parent_library.defined_in, &library.defined_in) {
+                        (Some(parent_file), Some(file)) if parent_file != file => format!(
+                            "Re-defined library {} in {} conflicts with the definition in {}",
+                            &library.name,
+                            file.to_string_lossy(),
+                            parent_file.to_string_lossy()
+                        ),
+                        _ => format!("Re-defined library {}", &library.name),
+                    };
+                    messages.push(Message::warning(message));
+                }
 
-                messages.push(Message::warning(format!(
-                    "Re-defined library {}",
-                    &library.name
-                )));
+                *parent_library = library.clone();
             } else {
-                self.libraries.insert(
-                    library.name.clone(),
-                    LibraryConfig {
-                        name: library.name.clone(),
-                        patterns: library.patterns.clone(),
-                    },
-                );
+                self.libraries.insert(library.name.clone(), library.clone());
             }
         }
+        if let Some(ref default_library) = config.default_library {
+            self.default_library = Some(default_library.clone());
+        }
+        self.lint.append(&config.lint);
+        for (name, value) in config.conditional_analysis.iter() {
+            self.conditional_analysis
+                .insert(name.clone(), value.clone());
+        }
     }
 
     /// Load configuration file from installation folder
@@ -258,7 +840,7 @@ impl Config {
 
     /// Load and append configuration file
     fn load_config(&mut self, file_name: &Path, desc: &str, messages: &mut dyn MessageHandler) {
-        match Config::read_file_path(Path::new(&file_name)) {
+        match Config::read_file_path(Path::new(&file_name), messages) {
             Ok(env_config) => {
                 messages.push(Message::log(format!(
                     "Loaded {} configuration file: {}",
@@ -285,7 +867,65 @@ impl Config {
     }
 }
 
+/// Returns true if the file name or glob pattern matches the file
+/// Remove a trailing path separator such that 'build/' matches the directory 'build'
+fn trim_trailing_separator(pattern: &str) -> &str {
+    let trimmed = pattern.trim_end_matches(std::path::is_separator);
+    if trimmed.is_empty() {
+        pattern
+    } else {
+        trimmed
+    }
+}
+
+fn pattern_matches(pattern: &str, file_name: &Path) -> bool {
+    if is_literal(pattern, cfg!(windows)) {
+        let pattern = Path::new(pattern);
+        if pattern == file_name {
+            return true;
+        }
+        match (dunce::canonicalize(pattern), dunce::canonicalize(file_name)) {
+            (Ok(pattern), Ok(file_name)) => pattern == file_name,
+            _ => false,
+        }
+    } else if let Ok(pattern) = glob::Pattern::new(pattern) {
+        // A single * shall not match across folders the same way as when globbing files
+        let options = glob::MatchOptions {
+            case_sensitive: !cfg!(windows),
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        pattern.matches_path_with(file_name, options)
+            || dunce::canonicalize(file_name)
+                .map(|file_name| pattern.matches_path_with(&file_name, options))
+                .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
 /// Returns true if the pattern is a plain file name and not a glob pattern
+/// Resolve '.', '..' and symbolic links of the longest existing path preceding any wildcard
+/// The remainder of the pattern is kept as it is
+fn canonical_pattern(pattern: &str) -> String {
+    let components: Vec<_> = Path::new(pattern).components().collect();
+    let num_literal = components
+        .iter()
+        .take_while(|component| is_literal(&component.as_os_str().to_string_lossy(), false))
+        .count();
+
+    for num_existing in (1..=num_literal).rev() {
+        let prefix: PathBuf = components[..num_existing].iter().collect();
+        if let Ok(mut result) = dunce::canonicalize(&prefix) {
+            for component in components[num_existing..].iter() {
+                result.push(component);
+            }
+            return result.to_string_lossy().into_owned();
+        }
+    }
+    pattern.to_owned()
+}
+
 fn is_literal(pattern: &str, is_windows: bool) -> bool {
     let mut chars = pattern.chars();
 
@@ -389,74 +1029,349 @@ lib1.files = [
     }
 
     #[test]
-    fn test_append_config() {
-        let parent0 = Path::new("parent_folder0");
-        let config0 = Config::from_str(
+    fn config_with_standard() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+
+        let config = Config::from_str(
             "
 [libraries]
-lib1.files = [
-  'pkg1.vhd',
-]
+lib1.files = ['pkg1.vhd']
+lib2.standard = '93'
 lib2.files = [
-  'pkg2.vhd'
+  'pkg2.vhd',
+  { path = 'pkg3.vhd', standard = '2019' },
 ]
 ",
-            parent0,
+            parent,
         )
         .unwrap();
 
-        let parent1 = Path::new("parent_folder1");
-        let config1 = Config::from_str(
+        let pkg1_path = touch(parent, "pkg1.vhd");
+        let pkg2_path = touch(parent, "pkg2.vhd");
+        let pkg3_path = touch(parent, "pkg3.vhd");
+
+        let lib1 = config.get_library("lib1").unwrap();
+        let lib2 = config.get_library("lib2").unwrap();
+        assert_eq!(lib1.standard(), None);
+        assert_eq!(lib2.standard(), Some(VHDLStandard::VHDL1993));
+
+        let mut messages = vec![];
+        assert_eq!(
+            lib1.files(&mut messages),
+            vec![(abspath(&pkg1_path), VHDLStandard::VHDL2008)]
+        );
+        assert_eq!(
+            lib2.files(&mut messages),
+            vec![
+                (abspath(&pkg2_path), VHDLStandard::VHDL1993),
+                (abspath(&pkg3_path), VHDLStandard::VHDL2019)
+            ]
+        );
+        assert_eq!(messages, vec![]);
+    }
+
+    #[test]
+    fn config_with_invalid_standard() {
+        let parent = Path::new("parent_folder");
+        let result = Config::from_str(
             "
 [libraries]
-lib2.files = [
-  'ent.vhd'
-]
-lib3.files = [
-  'pkg3.vhd',
-]
+lib.standard = '87'
+lib.files = ['pkg.vhd']
 ",
-            parent1,
+            parent,
+        );
+        assert_eq!(
+            result,
+            Err(
+                "Invalid VHDL standard '87', expected '93', '2002', '2008' or '2019' for library lib"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn config_with_third_party_library() {
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = []
+vendor.files = []
+vendor.is_third_party = true
+",
+            Path::new(""),
         )
         .unwrap();
 
-        let expected_parent = Path::new("");
-        let expected_config = Config::from_str(
-            &format!(
+        assert!(!config.get_library("lib").unwrap().is_third_party());
+        assert!(config.get_library("vendor").unwrap().is_third_party());
+
+        assert_eq!(
+            Config::from_str(
                 "
 [libraries]
-lib1.files = [
-  '{pkg1}',
-]
-lib2.files = [
-  '{ent}'
-]
-lib3.files = [
-  '{pkg3}',
-]
+vendor.files = []
+vendor.is_third_party = 'yes'
 ",
-                pkg1 = parent0.join("pkg1.vhd").to_str().unwrap(),
-                ent = parent1.join("ent.vhd").to_str().unwrap(),
-                pkg3 = parent1.join("pkg3.vhd").to_str().unwrap()
+                Path::new(""),
             ),
-            expected_parent,
-        )
-        .unwrap();
-
-        let mut merged_config = config0;
-        merged_config.append(&config1, &mut Vec::new());
-        assert_eq!(merged_config, expected_config);
+            Err("is_third_party must be a boolean, got \"yes\" for library vendor".to_owned())
+        );
     }
 
     #[test]
-    fn test_warning_on_missing_file() {
-        let parent = Path::new("parent_folder");
+    fn config_with_encoding() {
         let config = Config::from_str(
             "
 [libraries]
-lib.files = [
-  'missing.vhd'
-]
+lib.files = []
+legacy.files = []
+legacy.encoding = 'latin-1'
+modern.files = []
+modern.encoding = 'UTF-8'
+",
+            Path::new(""),
+        )
+        .unwrap();
+
+        assert_eq!(config.get_library("lib").unwrap().encoding(), None);
+        assert_eq!(
+            config.get_library("legacy").unwrap().encoding(),
+            Some(Encoding::Latin1)
+        );
+        assert_eq!(
+            config.get_library("modern").unwrap().encoding(),
+            Some(Encoding::Utf8)
+        );
+
+        assert_eq!(
+            Config::from_str(
+                "
+[libraries]
+lib.files = []
+lib.encoding = 'utf-16'
+",
+                Path::new(""),
+            ),
+            Err(
+                "Invalid encoding 'utf-16', expected 'utf-8' or 'latin-1' for library lib"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn config_with_lint() {
+        let config = Config::from_str(
+            "
+[libraries]
+
+[lint]
+unresolved = 'warning'
+unnecessary-library-clause = 'off'
+",
+            Path::new(""),
+        )
+        .unwrap();
+
+        let mut expected = LintConfig::default();
+        expected.set_severity(ErrorCode::Unresolved, Some(Severity::Warning));
+        expected.set_severity(ErrorCode::UnnecessaryLibraryClause, None);
+        assert_eq!(config.lint(), &expected);
+    }
+
+    #[test]
+    fn config_with_invalid_lint() {
+        assert_eq!(
+            Config::from_str(
+                "
+[libraries]
+
+[lint]
+not-a-rule = 'error'
+",
+                Path::new(""),
+            ),
+            Err("Unknown lint rule 'not-a-rule'".to_owned())
+        );
+
+        assert_eq!(
+            Config::from_str(
+                "
+[libraries]
+
+[lint]
+unresolved = 'fatal'
+",
+                Path::new(""),
+            ),
+            Err("Invalid severity \"fatal\" for lint rule 'unresolved', expected 'error', 'warning', 'info', 'hint' or 'off'".to_owned())
+        );
+    }
+
+    #[test]
+    fn lint_config_applies_severity() {
+        let code = crate::syntax::test::Code::new("foo");
+        let mut lint = LintConfig::default();
+        lint.set_severity(ErrorCode::Unresolved, Some(Severity::Hint));
+        lint.set_severity(ErrorCode::InvalidName, None);
+
+        let diagnostic = Diagnostic::error(code.pos(), "msg").with_code(ErrorCode::Unresolved);
+        assert_eq!(
+            lint.apply(diagnostic.clone()),
+            Some(Diagnostic::hint(code.pos(), "msg").with_code(ErrorCode::Unresolved))
+        );
+
+        let diagnostic = Diagnostic::error(code.pos(), "msg").with_code(ErrorCode::InvalidName);
+        assert_eq!(lint.apply(diagnostic), None);
+
+        let diagnostic = Diagnostic::error(code.pos(), "msg");
+        assert_eq!(lint.apply(diagnostic.clone()), Some(diagnostic));
+    }
+
+    #[test]
+    fn test_append_lint_config() {
+        let mut config0 = Config::from_str(
+            "
+[libraries]
+
+[lint]
+unresolved = 'warning'
+invalid-name = 'off'
+",
+            Path::new(""),
+        )
+        .unwrap();
+
+        let config1 = Config::from_str(
+            "
+[libraries]
+
+[lint]
+unresolved = 'hint'
+",
+            Path::new(""),
+        )
+        .unwrap();
+
+        config0.append(&config1, &mut Vec::new());
+
+        let mut expected = LintConfig::default();
+        expected.set_severity(ErrorCode::Unresolved, Some(Severity::Hint));
+        expected.set_severity(ErrorCode::InvalidName, None);
+        assert_eq!(config0.lint(), &expected);
+    }
+
+    #[test]
+    fn config_with_conditional_analysis() {
+        let mut config0 = Config::from_str(
+            "
+[conditional_analysis]
+tool_type = 'SIMULATION'
+DEBUG = '1'
+",
+            Path::new(""),
+        )
+        .unwrap();
+
+        let config1 = Config::from_str(
+            "
+[conditional_analysis]
+debug = '0'
+",
+            Path::new(""),
+        )
+        .unwrap();
+
+        config0.append(&config1, &mut Vec::new());
+
+        let mut expected = FnvHashMap::default();
+        expected.insert("TOOL_TYPE".to_owned(), "SIMULATION".to_owned());
+        expected.insert("DEBUG".to_owned(), "0".to_owned());
+        assert_eq!(config0.conditional_analysis(), &expected);
+
+        assert_eq!(
+            Config::from_str(
+                "
+[conditional_analysis]
+DEBUG = 1
+",
+                Path::new(""),
+            ),
+            Err("Conditional analysis identifier DEBUG must be a string, got 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_append_config() {
+        let parent0 = Path::new("parent_folder0");
+        let config0 = Config::from_str(
+            "
+[libraries]
+lib1.files = [
+  'pkg1.vhd',
+]
+lib2.files = [
+  'pkg2.vhd'
+]
+",
+            parent0,
+        )
+        .unwrap();
+
+        let parent1 = Path::new("parent_folder1");
+        let config1 = Config::from_str(
+            "
+[libraries]
+lib2.files = [
+  'ent.vhd'
+]
+lib3.files = [
+  'pkg3.vhd',
+]
+",
+            parent1,
+        )
+        .unwrap();
+
+        let expected_parent = Path::new("");
+        let expected_config = Config::from_str(
+            &format!(
+                "
+[libraries]
+lib1.files = [
+  '{pkg1}',
+]
+lib2.files = [
+  '{ent}'
+]
+lib3.files = [
+  '{pkg3}',
+]
+",
+                pkg1 = parent0.join("pkg1.vhd").to_str().unwrap(),
+                ent = parent1.join("ent.vhd").to_str().unwrap(),
+                pkg3 = parent1.join("pkg3.vhd").to_str().unwrap()
+            ),
+            expected_parent,
+        )
+        .unwrap();
+
+        let mut merged_config = config0;
+        merged_config.append(&config1, &mut Vec::new());
+        assert_eq!(merged_config, expected_config);
+    }
+
+    #[test]
+    fn test_warning_on_missing_file() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  'missing.vhd'
+]
 ",
             parent,
         )
@@ -547,4 +1462,448 @@ lib.files = [
             ))]
         );
     }
+
+    #[test]
+    fn test_recursive_wildcard_pattern_with_exclude() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  'src/**/*.vhd'
+]
+lib.exclude = [
+  'src/**/test/*.vhd',
+  'src/old.vhd'
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(parent.join("src").join("sub").join("test")).unwrap();
+        let file1 = touch(parent, "src/file1.vhd");
+        let file2 = touch(parent, "src/sub/file2.vhd");
+        touch(parent, "src/old.vhd");
+        touch(parent, "src/sub/test/tb.vhd");
+
+        let mut messages = vec![];
+        let mut file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        file_names.sort();
+        assert_files_eq(&file_names, &[file1, file2]);
+        assert_eq!(messages, vec![]);
+    }
+
+    #[test]
+    fn test_same_library_through_relative_and_absolute_path() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::create_dir_all(root.join("ieee")).unwrap();
+        std::fs::create_dir_all(root.join("project")).unwrap();
+
+        let relative = Config::from_str(
+            "
+[libraries]
+ieee.files = ['../ieee/*.vhd', './../ieee/pkg.vhd']
+ieee.exclude = ['../ieee/old/']
+",
+            &root.join("project"),
+        )
+        .unwrap();
+        let absolute = Config::from_str(
+            &format!(
+                "
+[libraries]
+ieee.files = ['{0}/*.vhd', '{0}/pkg.vhd']
+ieee.exclude = ['{0}/old/']
+",
+                root.join("ieee").to_string_lossy()
+            ),
+            Path::new(""),
+        )
+        .unwrap();
+
+        let mut messages = vec![];
+        let mut config = Config::default();
+        config.append(&relative, &mut messages);
+        config.append(&absolute, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let other = Config::from_str("[libraries]\nieee.files = ['*.vhd']", &root).unwrap();
+        config.append(&other, &mut messages);
+        assert_eq!(
+            messages,
+            vec![Message::warning("Re-defined library ieee".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_exclude_directory() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  'src/**/*.vhd'
+]
+lib.exclude = [
+  'src/vendor/**',
+  'src/build/',
+  'src/sim'
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(parent.join("src").join("vendor").join("sub")).unwrap();
+        std::fs::create_dir_all(parent.join("src").join("build")).unwrap();
+        std::fs::create_dir_all(parent.join("src").join("sim")).unwrap();
+        let file1 = touch(parent, "src/file1.vhd");
+        touch(parent, "src/vendor/sub/vendor.vhd");
+        touch(parent, "src/build/generated.vhd");
+        touch(parent, "src/sim/tb.vhd");
+
+        let mut messages = vec![];
+        let file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        assert_files_eq(&file_names, &[file1]);
+        assert_eq!(messages, vec![]);
+
+        for excluded in [
+            "src/vendor/sub/new.vhd",
+            "src/build/new.vhd",
+            "src/sim/new.vhd",
+        ] {
+            assert_eq!(config.map_file(&parent.join(excluded)), vec![]);
+        }
+        assert_eq!(
+            config.map_file(&parent.join("src").join("new.vhd")),
+            vec![("lib", VHDLStandard::VHDL2008)]
+        );
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        env::set_var("VHDL_LS_CONFIG_TEST_ROOT", "root");
+        assert_eq!(
+            expand_env_vars("$VHDL_LS_CONFIG_TEST_ROOT/file.vhd"),
+            Ok("root/file.vhd".to_owned())
+        );
+        assert_eq!(
+            expand_env_vars("${VHDL_LS_CONFIG_TEST_ROOT}_dir/file.vhd"),
+            Ok("root_dir/file.vhd".to_owned())
+        );
+        assert_eq!(expand_env_vars("file$.vhd"), Ok("file$.vhd".to_owned()));
+        assert_eq!(
+            expand_env_vars("$VHDL_LS_CONFIG_TEST_MISSING/file.vhd"),
+            Err("Environment variable 'VHDL_LS_CONFIG_TEST_MISSING' used in '$VHDL_LS_CONFIG_TEST_MISSING/file.vhd' is not set".to_owned())
+        );
+        assert!(expand_env_vars("${VHDL_LS_CONFIG_TEST_ROOT/file.vhd").is_err());
+    }
+
+    #[test]
+    fn test_env_var_in_file_pattern() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        env::set_var("VHDL_LS_CONFIG_TEST_DIR", parent.to_str().unwrap());
+
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  '${VHDL_LS_CONFIG_TEST_DIR}/*.vhd'
+]
+lib.exclude = [
+  '$VHDL_LS_CONFIG_TEST_DIR/file2.vhd'
+]
+",
+            Path::new("parent_folder"),
+        )
+        .unwrap();
+
+        let file1 = touch(parent, "file1.vhd");
+        touch(parent, "file2.vhd");
+
+        let mut messages = vec![];
+        let file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        assert_files_eq(&file_names, &[file1]);
+        assert_eq!(messages, vec![]);
+    }
+
+    #[test]
+    fn test_unset_env_var_only_skips_pattern() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = [
+  '$VHDL_LS_CONFIG_TEST_UNSET/*.vhd',
+  '*.vhd'
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        let file1 = touch(parent, "file1.vhd");
+
+        let mut messages = vec![];
+        let file_names = config.get_library("lib").unwrap().file_names(&mut messages);
+        assert_files_eq(&file_names, &[file1]);
+        assert_eq!(
+            messages,
+            vec![Message::warning(
+                "Environment variable 'VHDL_LS_CONFIG_TEST_UNSET' used in '$VHDL_LS_CONFIG_TEST_UNSET/*.vhd' is not set, skipping pattern of library lib"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_map_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let config = Config::from_str(
+            "
+default_library = 'default'
+
+[libraries]
+lib1.files = ['src/**/*.vhd']
+lib1.exclude = ['src/tb/*.vhd']
+lib2.standard = '93'
+lib2.files = ['src/*.vhd', 'pkg.vhd']
+tb.files = [{ path = 'src/tb/*.vhd', standard = '2019' }]
+",
+            parent,
+        )
+        .unwrap();
+
+        assert_eq!(config.default_library(), Some("default"));
+        assert_eq!(
+            config.map_file(&parent.join("src").join("file.vhd")),
+            vec![
+                ("lib1", VHDLStandard::VHDL2008),
+                ("lib2", VHDLStandard::VHDL1993)
+            ]
+        );
+        assert_eq!(
+            config.map_file(&parent.join("src").join("sub").join("file.vhd")),
+            vec![("lib1", VHDLStandard::VHDL2008)]
+        );
+        assert_eq!(
+            config.map_file(&parent.join("src").join("tb").join("tb_file.vhd")),
+            vec![("tb", VHDLStandard::VHDL2019)]
+        );
+        assert_eq!(
+            config.map_file(&parent.join("pkg.vhd")),
+            vec![("lib2", VHDLStandard::VHDL1993)]
+        );
+        assert_eq!(config.map_file(&parent.join("other.vhd")), vec![]);
+    }
+
+    #[test]
+    fn config_without_libraries() {
+        let config = Config::from_str(
+            "
+[lint]
+unresolved = 'off'
+",
+            Path::new("parent_folder"),
+        )
+        .unwrap();
+        assert_eq!(config.iter_libraries().count(), 0);
+        assert_eq!(
+            config.lint().severities.get(&ErrorCode::Unresolved),
+            Some(&None)
+        );
+    }
+
+    #[test]
+    fn test_append_default_library() {
+        let parent = Path::new("parent_folder");
+        let mut config = Config::from_str(
+            "
+default_library = 'lib1'
+[libraries]
+",
+            parent,
+        )
+        .unwrap();
+
+        let mut messages = vec![];
+        config.append(
+            &Config::from_str("[libraries]", parent).unwrap(),
+            &mut messages,
+        );
+        assert_eq!(config.default_library(), Some("lib1"));
+
+        config.append(
+            &Config::from_str("default_library = 'lib2'\n[libraries]", parent).unwrap(),
+            &mut messages,
+        );
+        assert_eq!(config.default_library(), Some("lib2"));
+        assert_eq!(messages, vec![]);
+
+        assert_eq!(
+            Config::from_str("default_library = 1\n[libraries]", parent),
+            Err("default_library must be a string, got 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_import_from_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::write(
+            parent.join("vunit.json"),
+            r#"{"files": [
+                {"file_name": "pkg.vhd", "library_name": "lib1"},
+                {"file_name": "ent.vhd", "library_name": "lib2"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+import = 'vunit.json'
+
+[libraries]
+lib2.files = ['other.vhd']
+",
+            parent,
+        )
+        .unwrap();
+
+        let pkg_path = touch(parent, "pkg.vhd");
+        let other_path = touch(parent, "other.vhd");
+
+        let mut messages = vec![];
+        let lib1 = config.get_library("lib1").unwrap();
+        let lib2 = config.get_library("lib2").unwrap();
+        assert_files_eq(&lib1.file_names(&mut messages), &[pkg_path]);
+        assert_files_eq(&lib2.file_names(&mut messages), &[other_path]);
+        assert_eq!(messages, vec![]);
+
+        assert!(Config::from_str("import = 'missing.json'\n[libraries]", parent).is_err());
+        assert!(Config::from_str("import = 'project.txt'\n[libraries]", parent).is_err());
+    }
+
+    #[test]
+    fn test_include_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = abspath(tempdir.path());
+        std::fs::create_dir(root.join("common")).unwrap();
+        std::fs::create_dir(root.join("project")).unwrap();
+
+        let common_file = root.join("common").join("vhdl_ls.toml");
+        std::fs::write(
+            &common_file,
+            "
+[libraries]
+common.files = ['pkg.vhd']
+lib.files = ['old.vhd']
+
+[lint]
+unresolved = 'warning'
+",
+        )
+        .unwrap();
+
+        let project_file = root.join("project").join("vhdl_ls.toml");
+        std::fs::write(
+            &project_file,
+            "
+include = ['../common/vhdl_ls.toml']
+
+[libraries]
+lib.files = ['new.vhd']
+",
+        )
+        .unwrap();
+
+        let pkg_path = touch(&root.join("common"), "pkg.vhd");
+        let new_path = touch(&root.join("project"), "new.vhd");
+
+        let mut messages = vec![];
+        let config = Config::read_file_path(&project_file, &mut messages).unwrap();
+        assert_eq!(
+            messages,
+            vec![Message::warning(format!(
+                "Re-defined library lib in {} conflicts with the definition in {}",
+                project_file.to_string_lossy(),
+                root.join("project")
+                    .join("../common/vhdl_ls.toml")
+                    .to_string_lossy()
+            ))]
+        );
+
+        let mut messages = vec![];
+        let common = config.get_library("common").unwrap();
+        let lib = config.get_library("lib").unwrap();
+        assert_files_eq(&common.file_names(&mut messages), &[pkg_path]);
+        assert_files_eq(&lib.file_names(&mut messages), &[new_path]);
+        assert_eq!(messages, vec![]);
+        assert_eq!(
+            config.lint().severities.get(&ErrorCode::Unresolved),
+            Some(&Some(Severity::Warning))
+        );
+    }
+
+    #[test]
+    fn test_circular_include_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = abspath(tempdir.path());
+
+        let file1 = root.join("file1.toml");
+        std::fs::write(&file1, "include = 'file2.toml'\n[libraries]").unwrap();
+        let file2 = root.join("file2.toml");
+        std::fs::write(&file2, "include = 'file1.toml'\n[libraries]").unwrap();
+
+        let mut messages = vec![];
+        Config::read_file_path(&file1, &mut messages).unwrap();
+        assert_eq!(
+            messages,
+            vec![Message::error(format!(
+                "Circular include of configuration file {} from {}",
+                root.join("file1.toml").to_string_lossy(),
+                file2.to_string_lossy()
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_append_same_library_definition_does_not_warn() {
+        let parent = Path::new("parent_folder");
+        let mut config = Config::from_str("[libraries]\nlib.files = ['pkg.vhd']", parent).unwrap();
+
+        let mut messages = vec![];
+        config.append(&config.clone(), &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let other = Config::from_str("[libraries]\nlib.files = ['other.vhd']", parent).unwrap();
+        config.append(&other, &mut messages);
+        assert_eq!(messages, vec![Message::warning("Re-defined library lib")]);
+    }
+
+    #[test]
+    fn test_find_nested_config_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = abspath(tempdir.path());
+        for folder in &["a/b", "c", ".hidden"] {
+            std::fs::create_dir_all(root.join(folder)).unwrap();
+        }
+
+        touch(&root, "vhdl_ls.toml");
+        let ab_config = touch(&root.join("a/b"), "vhdl_ls.toml");
+        let c_config = touch(&root.join("c"), "vhdl_ls.toml");
+        touch(&root.join(".hidden"), "vhdl_ls.toml");
+
+        assert_eq!(
+            Config::find_nested_config_files(&root),
+            vec![c_config, ab_config]
+        );
+    }
 }
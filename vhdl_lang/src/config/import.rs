@@ -0,0 +1,582 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Import of libraries and files from the project files of other tools
+//!
+//! Supported formats are
+//! - VUnit `--export-json` files (.json)
+//! - FuseSoC CAPI2 core files (.core)
+//! - Vivado projects (.xpr)
+//! - Quartus settings files (.qsf)
+
+use super::{Config, FilePattern, LibraryConfig};
+use crate::standard::VHDLStandard;
+use fnv::FnvHashMap;
+use std::path::{Path, PathBuf};
+
+/// A VHDL source file of an imported project
+#[derive(PartialEq, Debug)]
+struct ImportedFile {
+    library_name: String,
+    file_name: PathBuf,
+    standard: Option<VHDLStandard>,
+}
+
+/// Import the libraries of a project file of another tool
+/// The format is selected by the file extension
+pub(super) fn import_file(file_name: &Path) -> Result<Config, String> {
+    let contents = std::fs::read(file_name)
+        .map_err(|err| format!("Could not read {}: {}", file_name.to_string_lossy(), err))?;
+    let contents = String::from_utf8_lossy(&contents);
+    let parent = file_name.parent().unwrap_or_else(|| Path::new(""));
+
+    let extension = file_name
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+
+    let files = match extension.as_deref() {
+        Some("json") => import_vunit(&contents, parent)?,
+        Some("core") => import_fusesoc(&contents, parent)?,
+        Some("xpr") => import_vivado(&contents, file_name)?,
+        Some("qsf") => import_quartus(&contents, parent)?,
+        _ => {
+            return Err(format!(
+                "Unknown project file format of {}, expected a VUnit .json, FuseSoC .core, Vivado .xpr or Quartus .qsf file",
+                file_name.to_string_lossy()
+            ));
+        }
+    };
+
+    Ok(to_config(files))
+}
+
+fn to_config(files: Vec<ImportedFile>) -> Config {
+    let mut libraries: FnvHashMap<String, LibraryConfig> = FnvHashMap::default();
+
+    for file in files.into_iter() {
+        let library = libraries
+            .entry(file.library_name.clone())
+            .or_insert_with(|| LibraryConfig {
+                name: file.library_name.clone(),
+                ..LibraryConfig::default()
+            });
+
+        library.patterns.push(FilePattern {
+            pattern: file.file_name.to_string_lossy().into_owned(),
+            standard: file.standard,
+        });
+    }
+
+    Config {
+        libraries,
+        ..Config::default()
+    }
+}
+
+fn is_vhdl_file(file_name: &str) -> bool {
+    let file_name = file_name.to_ascii_lowercase();
+    file_name.ends_with(".vhd") || file_name.ends_with(".vhdl")
+}
+
+/// Parse the standard from the trailing year of a tool specific name such as
+/// vhdlSource-2008, VHDL_1993 or VHDL2008
+fn standard_from_suffix(name: &str) -> Option<VHDLStandard> {
+    let year_start = name
+        .rfind(|chr: char| !chr.is_ascii_digit())
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    match &name[year_start..] {
+        // VHDL-87 is analyzed as VHDL-93 which is the oldest supported standard
+        "87" | "1987" => Some(VHDLStandard::VHDL1993),
+        year => year.parse().ok(),
+    }
+}
+
+/// Import a VUnit --export-json file
+///
+/// {"files": [{"file_name": "/path/to/file.vhd", "library_name": "lib"}, ...], ...}
+fn import_vunit(contents: &str, parent: &Path) -> Result<Vec<ImportedFile>, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(contents).map_err(|err| format!("Invalid VUnit export: {}", err))?;
+    let files = json
+        .get("files")
+        .and_then(serde_json::Value::as_array)
+        .ok_or("Missing files array in VUnit export")?;
+
+    let mut result = Vec::new();
+    for file in files.iter() {
+        let file_name = file
+            .get("file_name")
+            .and_then(serde_json::Value::as_str)
+            .ok_or("Missing file_name in VUnit export")?;
+        let library_name = file
+            .get("library_name")
+            .and_then(serde_json::Value::as_str)
+            .ok_or("Missing library_name in VUnit export")?;
+
+        if is_vhdl_file(file_name) {
+            result.push(ImportedFile {
+                library_name: library_name.to_owned(),
+                file_name: parent.join(file_name),
+                standard: None,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Import the files of all filesets of a FuseSoC CAPI2 core file
+///
+/// filesets:
+///   rtl:
+///     file_type: vhdlSource-2008
+///     logical_name: lib
+///     files:
+///       - file.vhd
+///       - other.vhd: {logical_name: other_lib}
+fn import_fusesoc(contents: &str, parent: &Path) -> Result<Vec<ImportedFile>, String> {
+    let core: serde_yaml::Value = serde_yaml::from_str(contents)
+        .map_err(|err| format!("Invalid FuseSoC core file: {}", err))?;
+    let filesets = core
+        .get("filesets")
+        .and_then(serde_yaml::Value::as_mapping)
+        .ok_or("Missing filesets in FuseSoC core file")?;
+
+    let mut result = Vec::new();
+    for (_, fileset) in filesets.iter() {
+        let fileset_file_type = fileset.get("file_type").and_then(serde_yaml::Value::as_str);
+        let fileset_library = fileset
+            .get("logical_name")
+            .and_then(serde_yaml::Value::as_str);
+
+        let files = match fileset
+            .get("files")
+            .and_then(serde_yaml::Value::as_sequence)
+        {
+            Some(files) => files,
+            None => continue,
+        };
+
+        for file in files.iter() {
+            // A file is either a plain file name or a file name with a map of attributes
+            let (file_name, attributes) = match file {
+                serde_yaml::Value::String(file_name) => (file_name.as_str(), None),
+                serde_yaml::Value::Mapping(entries) if entries.len() == 1 => {
+                    match entries.iter().next() {
+                        Some((serde_yaml::Value::String(file_name), attributes)) => {
+                            (file_name.as_str(), Some(attributes))
+                        }
+                        _ => {
+                            return Err(format!("Invalid file in FuseSoC core file: {:?}", file));
+                        }
+                    }
+                }
+                _ => {
+                    return Err(format!("Invalid file in FuseSoC core file: {:?}", file));
+                }
+            };
+
+            let attribute =
+                |name: &str| attributes.and_then(|attributes| attributes.get(name)?.as_str());
+
+            let is_vhdl = match attribute("file_type").or(fileset_file_type) {
+                Some(file_type) => file_type.starts_with("vhdlSource"),
+                None => is_vhdl_file(file_name),
+            };
+
+            if is_vhdl {
+                result.push(ImportedFile {
+                    library_name: attribute("logical_name")
+                        .or(fileset_library)
+                        .unwrap_or("work")
+                        .to_owned(),
+                    file_name: parent.join(file_name),
+                    standard: attribute("file_type")
+                        .or(fileset_file_type)
+                        .and_then(standard_from_suffix),
+                });
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Import the design and simulation sources of a Vivado project
+///
+/// <FileSet Name="sources_1" Type="DesignSrcs">
+///   <File Path="$PSRCDIR/sources_1/new/file.vhd">
+///     <FileInfo SFType="VHDL2008">
+///       <Attr Name="Library" Val="lib"/>
+///     </FileInfo>
+///   </File>
+/// </FileSet>
+fn import_vivado(contents: &str, file_name: &Path) -> Result<Vec<ImportedFile>, String> {
+    let parent = file_name.parent().unwrap_or_else(|| Path::new(""));
+    let stem = file_name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // Project relative path variables used by Vivado
+    let variables = [
+        ("$PPRDIR", parent.to_path_buf()),
+        ("$PSRCDIR", parent.join(format!("{}.srcs", stem))),
+        ("$PGENDIR", parent.join(format!("{}.gen", stem))),
+        (
+            "$PIPUSERFILESDIR",
+            parent.join(format!("{}.ip_user_files", stem)),
+        ),
+    ];
+
+    let expand = |path: &str| -> PathBuf {
+        for (variable, value) in variables.iter() {
+            if let Some(rest) = path.strip_prefix(variable) {
+                return value.join(rest.trim_start_matches('/'));
+            }
+        }
+        parent.join(path)
+    };
+
+    let document = roxmltree::Document::parse(contents)
+        .map_err(|err| format!("Invalid Vivado project file: {}", err))?;
+
+    let source_filesets = document.descendants().filter(|node| {
+        node.has_tag_name("FileSet")
+            && matches!(
+                node.attribute("Type"),
+                Some("DesignSrcs") | Some("SimulationSrcs")
+            )
+    });
+
+    let mut result = Vec::new();
+    for fileset in source_filesets {
+        for file_node in fileset.children().filter(|node| node.has_tag_name("File")) {
+            let path = match file_node.attribute("Path") {
+                Some(path) if is_vhdl_file(path) => path,
+                _ => continue,
+            };
+
+            let mut file = ImportedFile {
+                library_name: "xil_defaultlib".to_owned(),
+                file_name: expand(path),
+                standard: None,
+            };
+
+            for node in file_node.descendants() {
+                if node.has_tag_name("FileInfo") {
+                    if let Some(file_type) = node.attribute("SFType") {
+                        file.standard = standard_from_suffix(file_type);
+                    }
+                } else if node.has_tag_name("Attr") {
+                    match (node.attribute("Name"), node.attribute("Val")) {
+                        (Some("Library"), Some(library_name)) => {
+                            file.library_name = library_name.to_owned();
+                        }
+                        (Some("FileType"), Some(file_type)) => {
+                            file.standard = standard_from_suffix(file_type);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            result.push(file);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Import the VHDL files of a Quartus settings file
+///
+/// set_global_assignment -name VHDL_INPUT_VERSION VHDL_2008
+/// set_global_assignment -name VHDL_FILE file.vhd -library lib -hdl_version VHDL_1993
+fn import_quartus(contents: &str, parent: &Path) -> Result<Vec<ImportedFile>, String> {
+    let mut result = Vec::new();
+    let mut default_standard = None;
+
+    for line in contents.lines() {
+        let words = tcl_words(line)?;
+        if words.first().map(String::as_str) != Some("set_global_assignment") {
+            continue;
+        }
+
+        let mut options = FnvHashMap::default();
+        let mut values = Vec::new();
+        let mut words = words.into_iter().skip(1);
+        while let Some(word) = words.next() {
+            if word.starts_with('-') {
+                options.insert(word, words.next().unwrap_or_default());
+            } else {
+                values.push(word);
+            }
+        }
+
+        let name = options.get("-name").map(String::as_str);
+        let value = values.first();
+
+        match (name, value) {
+            (Some("VHDL_FILE"), Some(file_name)) => {
+                result.push(ImportedFile {
+                    library_name: options
+                        .get("-library")
+                        .cloned()
+                        .unwrap_or_else(|| "work".to_owned()),
+                    file_name: parent.join(file_name),
+                    standard: options
+                        .get("-hdl_version")
+                        .and_then(|version| standard_from_suffix(version)),
+                });
+            }
+            (Some("VHDL_INPUT_VERSION"), Some(version)) => {
+                default_standard = standard_from_suffix(version);
+            }
+            _ => {}
+        }
+    }
+
+    // The input version applies to all files regardless of where it is set
+    for file in result.iter_mut() {
+        file.standard = file.standard.or(default_standard);
+    }
+
+    Ok(result)
+}
+
+/// Split a line of Tcl into words, handling quotes, braces and comments
+fn tcl_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.peek().map(|chr| chr.is_whitespace()) == Some(true) {
+            chars.next();
+        }
+
+        let word = match chars.peek() {
+            None | Some('#') => break,
+            Some('"') | Some('{') => {
+                let close = if chars.next() == Some('"') { '"' } else { '}' };
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(chr) if chr == close => break,
+                        Some(chr) => word.push(chr),
+                        None => return Err(format!("Missing closing {} in '{}'", close, line)),
+                    }
+                }
+                word
+            }
+            Some(_) => {
+                let mut word = String::new();
+                while let Some(chr) = chars.peek() {
+                    if chr.is_whitespace() {
+                        break;
+                    }
+                    word.push(*chr);
+                    chars.next();
+                }
+                word
+            }
+        };
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn file(
+        library_name: &str,
+        file_name: PathBuf,
+        standard: Option<VHDLStandard>,
+    ) -> ImportedFile {
+        ImportedFile {
+            library_name: library_name.to_owned(),
+            file_name,
+            standard,
+        }
+    }
+
+    #[test]
+    fn test_standard_from_suffix() {
+        assert_eq!(
+            standard_from_suffix("vhdlSource-2008"),
+            Some(VHDLStandard::VHDL2008)
+        );
+        assert_eq!(
+            standard_from_suffix("vhdlSource-87"),
+            Some(VHDLStandard::VHDL1993)
+        );
+        assert_eq!(
+            standard_from_suffix("VHDL_1993"),
+            Some(VHDLStandard::VHDL1993)
+        );
+        assert_eq!(
+            standard_from_suffix("VHDL2019"),
+            Some(VHDLStandard::VHDL2019)
+        );
+        assert_eq!(standard_from_suffix("vhdlSource"), None);
+    }
+
+    #[test]
+    fn test_import_vunit() {
+        let parent = Path::new("parent");
+        let json = r#"
+{
+  "export_format_version": {"major": 1, "minor": 0, "patch": 0},
+  "files": [
+    {"file_name": "/abs/pkg.vhd", "library_name": "lib1"},
+    {"file_name": "rel/ent.vhdl", "library_name": "lib2"},
+    {"file_name": "rel/ent.v", "library_name": "lib2"}
+  ],
+  "tests": []
+}
+"#;
+        assert_eq!(
+            import_vunit(json, parent),
+            Ok(vec![
+                file("lib1", PathBuf::from("/abs/pkg.vhd"), None),
+                file("lib2", parent.join("rel/ent.vhdl"), None),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_import_fusesoc() {
+        let parent = Path::new("parent");
+        let core = "
+CAPI=2:
+name: ::my_core:1.0.0
+
+filesets:
+  rtl:
+    files:
+      - rtl/pkg.vhd
+      - rtl/ent.vhd: {logical_name: other_lib}
+      - rtl/legacy.vhd:
+          file_type: vhdlSource-93
+      - rtl/top.v: {file_type: verilogSource}
+    file_type: vhdlSource-2008
+    logical_name: my_lib
+
+  tb:
+    files: [tb/tb.vhd, tb/data.txt]
+
+targets:
+  default:
+    filesets: [rtl]
+";
+        assert_eq!(
+            import_fusesoc(core, parent),
+            Ok(vec![
+                file(
+                    "my_lib",
+                    parent.join("rtl/pkg.vhd"),
+                    Some(VHDLStandard::VHDL2008)
+                ),
+                file(
+                    "other_lib",
+                    parent.join("rtl/ent.vhd"),
+                    Some(VHDLStandard::VHDL2008)
+                ),
+                file(
+                    "my_lib",
+                    parent.join("rtl/legacy.vhd"),
+                    Some(VHDLStandard::VHDL1993)
+                ),
+                file("work", parent.join("tb/tb.vhd"), None),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_import_vivado() {
+        let parent = Path::new("parent");
+        let xpr = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Product Version: Vivado v2020.2 -->
+<Project Version="7" Path="/path/to/proj.xpr">
+  <FileSets Version="1" Minor="31">
+    <FileSet Name="sources_1" Type="DesignSrcs" RelSrcDir="$PSRCDIR/sources_1">
+      <File Path="$PSRCDIR/sources_1/new/top.vhd">
+        <FileInfo SFType="VHDL2008">
+          <Attr Name="Library" Val="my_lib"/>
+          <Attr Name="UsedIn" Val="synthesis"/>
+        </FileInfo>
+      </File>
+      <File Path="$PPRDIR/../src/pkg.vhd">
+        <FileInfo>
+          <Attr Name="UsedIn" Val="synthesis"/>
+        </FileInfo>
+      </File>
+      <File Path="$PPRDIR/../src/ip.v"/>
+    </FileSet>
+    <FileSet Name="constrs_1" Type="Constrs" RelSrcDir="$PSRCDIR/constrs_1">
+      <File Path="$PPRDIR/constraints.vhd"/>
+    </FileSet>
+    <FileSet Name="sim_1" Type="SimulationSrcs" RelSrcDir="$PSRCDIR/sim_1">
+      <File Path="$PSRCDIR/sim_1/new/tb.vhd"/>
+    </FileSet>
+  </FileSets>
+</Project>
+"#;
+        assert_eq!(
+            import_vivado(xpr, &parent.join("proj.xpr")),
+            Ok(vec![
+                file(
+                    "my_lib",
+                    parent.join("proj.srcs").join("sources_1/new/top.vhd"),
+                    Some(VHDLStandard::VHDL2008)
+                ),
+                file("xil_defaultlib", parent.join("../src/pkg.vhd"), None),
+                file(
+                    "xil_defaultlib",
+                    parent.join("proj.srcs").join("sim_1/new/tb.vhd"),
+                    None
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_import_quartus() {
+        let parent = Path::new("parent");
+        let qsf = r#"
+# Quartus settings
+set_global_assignment -name FAMILY "Cyclone V"
+set_global_assignment -name VHDL_FILE src/pkg.vhd -library my_lib
+set_global_assignment -name VHDL_FILE "src/my top.vhd"
+set_global_assignment -name VHDL_FILE {src/legacy.vhd} -hdl_version VHDL_1993
+set_global_assignment -name VERILOG_FILE src/ip.v
+set_global_assignment -name VHDL_INPUT_VERSION VHDL_2008
+"#;
+        assert_eq!(
+            import_quartus(qsf, parent),
+            Ok(vec![
+                file(
+                    "my_lib",
+                    parent.join("src/pkg.vhd"),
+                    Some(VHDLStandard::VHDL2008)
+                ),
+                file(
+                    "work",
+                    parent.join("src/my top.vhd"),
+                    Some(VHDLStandard::VHDL2008)
+                ),
+                file(
+                    "work",
+                    parent.join("src/legacy.vhd"),
+                    Some(VHDLStandard::VHDL1993)
+                ),
+            ])
+        );
+    }
+}
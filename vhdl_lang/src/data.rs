@@ -7,14 +7,18 @@
 
 mod contents;
 mod diagnostic;
+mod error_codes;
 mod latin_1;
 mod message;
+mod progress;
 mod source;
 mod symbol_table;
 
 pub use contents::*;
 pub use diagnostic::*;
+pub use error_codes::*;
 pub use latin_1::*;
 pub use message::*;
+pub use progress::*;
 pub use source::*;
 pub use symbol_table::*;
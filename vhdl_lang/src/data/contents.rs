@@ -10,19 +10,75 @@ use std::fs::File;
 use std::io;
 use std::io::prelude::Read;
 use std::path::Path;
+use std::str::FromStr;
 
+/// The character encoding of a source file
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Encoding {
+    Utf8,
+    Latin1,
+}
+
+impl Encoding {
+    /// Detect the encoding from a byte order mark or from the bytes being valid UTF-8,
+    /// otherwise the file is assumed to be Latin-1
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        if bytes.starts_with(UTF8_BOM) || std::str::from_utf8(bytes).is_ok() {
+            Encoding::Utf8
+        } else {
+            Encoding::Latin1
+        }
+    }
+
+    pub fn decode(self, bytes: Vec<u8>) -> String {
+        match self {
+            Encoding::Utf8 => {
+                let bytes = if bytes.starts_with(UTF8_BOM) {
+                    &bytes[UTF8_BOM.len()..]
+                } else {
+                    &bytes[..]
+                };
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+            Encoding::Latin1 => Latin1String::from_vec(bytes).to_string(),
+        }
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Encoding, String> {
+        match string.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(Encoding::Latin1),
+            _ => Err(format!(
+                "Invalid encoding '{}', expected 'utf-8' or 'latin-1'",
+                string
+            )),
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+#[derive(Clone)]
 pub struct Contents {
     lines: Vec<String>,
 }
 
 impl Contents {
     pub fn from_latin1_file(file_name: &Path) -> io::Result<Contents> {
+        Self::from_file(file_name, Some(Encoding::Latin1))
+    }
+
+    /// Read a file with the given encoding, the encoding is detected when it is None
+    pub fn from_file(file_name: &Path, encoding: Option<Encoding>) -> io::Result<Contents> {
         let mut file = File::open(file_name)?;
         let mut bytes = Vec::new();
         file.read_to_end(&mut bytes)?;
-        Ok(Contents::from_str(
-            &Latin1String::from_vec(bytes).to_string(),
-        ))
+        let encoding = encoding.unwrap_or_else(|| Encoding::detect(&bytes));
+        Ok(Contents::from_str(&encoding.decode(bytes)))
     }
 
     pub fn from_str(code: &str) -> Contents {
@@ -307,7 +363,6 @@ impl<'a> ContentReader<'a> {
         self.state.pos()
     }
 
-    #[cfg(test)]
     pub fn seek_pos(&mut self, pos: Position) {
         self.state = ReaderState {
             pos: Position {
@@ -347,6 +402,44 @@ mod tests {
         ContentReader::new(contents)
     }
 
+    #[test]
+    fn detect_encoding() {
+        assert_eq!(Encoding::detect("abc".as_bytes()), Encoding::Utf8);
+        assert_eq!(Encoding::detect("-- µs €".as_bytes()), Encoding::Utf8);
+        assert_eq!(Encoding::detect(&[0xEF, 0xBB, 0xBF, b'a']), Encoding::Utf8);
+        assert_eq!(
+            Encoding::detect(&[b'-', b'-', 0xB5, b's']),
+            Encoding::Latin1
+        );
+    }
+
+    #[test]
+    fn decode_encoding() {
+        assert_eq!(Encoding::Utf8.decode("µs".as_bytes().to_vec()), "µs");
+        assert_eq!(
+            Encoding::Utf8.decode(vec![0xEF, 0xBB, 0xBF, b'a', b'b']),
+            "ab"
+        );
+        assert_eq!(Encoding::Latin1.decode(vec![0xB5, b's']), "µs");
+        assert_eq!(Encoding::Latin1.decode("µs".as_bytes().to_vec()), "Âµs");
+    }
+
+    #[test]
+    fn from_file_detects_encoding() {
+        let mut utf8_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut utf8_file, "-- Åsa €\n".as_bytes()).unwrap();
+        let contents = Contents::from_file(utf8_file.path(), None).unwrap();
+        assert_eq!(contents.get_line(0), Some("-- Åsa €\n"));
+
+        let mut latin1_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut latin1_file, &[b'-', b'-', b' ', 0xC5, b'\n']).unwrap();
+        let contents = Contents::from_file(latin1_file.path(), None).unwrap();
+        assert_eq!(contents.get_line(0), Some("-- Å\n"));
+
+        let contents = Contents::from_file(utf8_file.path(), Some(Encoding::Latin1)).unwrap();
+        assert_eq!(contents.get_line(0), Some("-- Ã\u{85}sa â\u{82}¬\n"));
+    }
+
     #[test]
     fn pop_latin1_ok() {
         let contents = new("hi");
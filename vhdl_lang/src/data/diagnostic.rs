@@ -4,7 +4,7 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::SrcPos;
+use super::{ErrorCode, SrcPos};
 use std::convert::{AsRef, Into};
 
 #[derive(PartialEq, Debug, Clone, Copy, Eq, Hash)]
@@ -22,6 +22,7 @@ pub struct Diagnostic {
     pub message: String,
     pub severity: Severity,
     pub related: Vec<(SrcPos, String)>,
+    pub code: Option<ErrorCode>,
 }
 
 impl Diagnostic {
@@ -31,6 +32,7 @@ impl Diagnostic {
             message: msg.into(),
             severity,
             related: vec![],
+            code: None,
         }
     }
 
@@ -56,6 +58,23 @@ impl Diagnostic {
             pos: self.pos,
             severity: self.severity,
             related: vec![],
+            code: self.code,
+        }
+    }
+
+    /// Set the rule code of the diagnostic
+    pub fn with_code(self, code: ErrorCode) -> Diagnostic {
+        Diagnostic {
+            code: Some(code),
+            ..self
+        }
+    }
+
+    /// Set the rule code of the diagnostic unless it already has one
+    pub fn or_code(self, code: ErrorCode) -> Diagnostic {
+        Diagnostic {
+            code: self.code.or(Some(code)),
+            ..self
         }
     }
 
@@ -95,7 +114,12 @@ impl Diagnostic {
             Severity::Info => &"info",
             Severity::Hint => &"hint",
         };
-        result.push_str(&self.pos.show(&format!("{}: {}", severity, self.message)));
+        let header = if let Some(code) = self.code {
+            format!("{}[{}]: {}", severity, code, self.message)
+        } else {
+            format!("{}: {}", severity, self.message)
+        };
+        result.push_str(&self.pos.show(&header));
         result
     }
 }
@@ -187,6 +211,25 @@ error: Greetings
         );
     }
 
+    #[test]
+    fn show_error_with_code() {
+        let code = Code::new_with_file_name(Path::new("{unknown file}"), "hello\nworld\nline\n");
+        assert_eq!(
+            Diagnostic::error(code.s1("world"), "Greetings")
+                .with_code(ErrorCode::Unresolved)
+                .show(),
+            "\
+error[unresolved]: Greetings
+  --> {unknown file}:2
+   |
+1  |  hello
+2 --> world
+   |  ~~~~~
+3  |  line
+"
+        );
+    }
+
     #[test]
     fn show_related() {
         let code = Code::new_with_file_name(Path::new("{unknown file}"), "hello\nworld\nline\n");
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A stable code identifying the rule which produced a diagnostic
+///
+/// The codes are used to configure the severity of diagnostics in the `[lint]` table
+/// of the configuration file and are shown in the command line output and the language server.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash)]
+pub enum ErrorCode {
+    Syntax,
+    CircularDependency,
+    Unresolved,
+    DuplicateDeclaration,
+    MissingBody,
+    MissingFullTypeDeclaration,
+    MissingDeferredConstant,
+    IllegalDeferredConstant,
+    DeclarationOrder,
+    MismatchedKinds,
+    TypeMismatch,
+    AmbiguousName,
+    HiddenByUseClause,
+    UnnecessaryLibraryClause,
+    InvalidName,
+    InvalidSignature,
+    InvalidTarget,
+    InvalidConfiguration,
+    DimensionMismatch,
+    UnusedSuppression,
+    InvalidSuppression,
+    WarningDirective,
+    ErrorDirective,
+    Internal,
+}
+
+impl ErrorCode {
+    /// All error codes in the order they are documented
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::Syntax,
+        ErrorCode::CircularDependency,
+        ErrorCode::Unresolved,
+        ErrorCode::DuplicateDeclaration,
+        ErrorCode::MissingBody,
+        ErrorCode::MissingFullTypeDeclaration,
+        ErrorCode::MissingDeferredConstant,
+        ErrorCode::IllegalDeferredConstant,
+        ErrorCode::DeclarationOrder,
+        ErrorCode::MismatchedKinds,
+        ErrorCode::TypeMismatch,
+        ErrorCode::AmbiguousName,
+        ErrorCode::HiddenByUseClause,
+        ErrorCode::UnnecessaryLibraryClause,
+        ErrorCode::InvalidName,
+        ErrorCode::InvalidSignature,
+        ErrorCode::InvalidTarget,
+        ErrorCode::InvalidConfiguration,
+        ErrorCode::DimensionMismatch,
+        ErrorCode::UnusedSuppression,
+        ErrorCode::InvalidSuppression,
+        ErrorCode::WarningDirective,
+        ErrorCode::ErrorDirective,
+        ErrorCode::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Syntax => "syntax",
+            ErrorCode::CircularDependency => "circular-dependency",
+            ErrorCode::Unresolved => "unresolved",
+            ErrorCode::DuplicateDeclaration => "duplicate-declaration",
+            ErrorCode::MissingBody => "missing-body",
+            ErrorCode::MissingFullTypeDeclaration => "missing-full-type-declaration",
+            ErrorCode::MissingDeferredConstant => "missing-deferred-constant",
+            ErrorCode::IllegalDeferredConstant => "illegal-deferred-constant",
+            ErrorCode::DeclarationOrder => "declaration-order",
+            ErrorCode::MismatchedKinds => "mismatched-kinds",
+            ErrorCode::TypeMismatch => "type-mismatch",
+            ErrorCode::AmbiguousName => "ambiguous-name",
+            ErrorCode::HiddenByUseClause => "hidden-by-use-clause",
+            ErrorCode::UnnecessaryLibraryClause => "unnecessary-library-clause",
+            ErrorCode::InvalidName => "invalid-name",
+            ErrorCode::InvalidSignature => "invalid-signature",
+            ErrorCode::InvalidTarget => "invalid-target",
+            ErrorCode::InvalidConfiguration => "invalid-configuration",
+            ErrorCode::DimensionMismatch => "dimension-mismatch",
+            ErrorCode::UnusedSuppression => "unused-suppression",
+            ErrorCode::InvalidSuppression => "invalid-suppression",
+            ErrorCode::WarningDirective => "warning-directive",
+            ErrorCode::ErrorDirective => "error-directive",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<ErrorCode, String> {
+        ErrorCode::ALL
+            .iter()
+            .find(|code| code.as_str() == string)
+            .cloned()
+            .ok_or_else(|| format!("Unknown lint rule '{}'", string))
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_code_from_str_is_inverse_of_as_str() {
+        for code in ErrorCode::ALL.iter() {
+            assert_eq!(code.as_str().parse(), Ok(*code));
+        }
+        assert!("not-a-rule".parse::<ErrorCode>().is_err());
+    }
+}
@@ -0,0 +1,136 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+/// The phases of loading and analyzing a project
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ProgressPhase {
+    Read,
+    Parse,
+    Analyze,
+}
+
+impl AsRef<str> for ProgressPhase {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Read => "read",
+            Self::Parse => "parse",
+            Self::Analyze => "analyze",
+        }
+    }
+}
+
+/// Receives the progress of each phase
+/// The total is the number of files when reading and parsing and
+/// the number of design units when analyzing
+pub trait ProgressHandler {
+    fn begin(&mut self, phase: ProgressPhase, total: usize);
+    fn report(&mut self, phase: ProgressPhase, done: usize, total: usize);
+    fn end(&mut self, phase: ProgressPhase);
+}
+
+#[derive(Default)]
+pub struct NoProgress {}
+
+impl ProgressHandler for NoProgress {
+    fn begin(&mut self, _phase: ProgressPhase, _total: usize) {}
+    fn report(&mut self, _phase: ProgressPhase, _done: usize, _total: usize) {}
+    fn end(&mut self, _phase: ProgressPhase) {}
+}
+
+// The number of progress reports within each phase
+const NUM_REPORTS: usize = 20;
+
+/// Map the items in parallel and report the progress in between chunks of items
+pub(crate) fn par_map_with_progress<T, R, F>(
+    items: Vec<T>,
+    phase: ProgressPhase,
+    progress: &mut dyn ProgressHandler,
+    fun: F,
+) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    use rayon::prelude::*;
+
+    let total = items.len();
+    // Keep the chunks large enough to not limit the parallelism
+    let chunk_size = std::cmp::max(
+        total.div_ceil(NUM_REPORTS),
+        4 * rayon::current_num_threads(),
+    );
+    let mut result = Vec::with_capacity(total);
+    let mut items = items.into_iter();
+
+    progress.begin(phase, total);
+    loop {
+        let chunk: Vec<T> = items.by_ref().take(chunk_size).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        result.par_extend(chunk.into_par_iter().map(&fun));
+        progress.report(phase, result.len(), total);
+    }
+    progress.end(phase);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct ProgressRecorder {
+        events: Vec<String>,
+    }
+
+    impl ProgressHandler for ProgressRecorder {
+        fn begin(&mut self, phase: ProgressPhase, total: usize) {
+            self.events
+                .push(format!("begin {} {}", phase.as_ref(), total));
+        }
+        fn report(&mut self, phase: ProgressPhase, done: usize, total: usize) {
+            self.events
+                .push(format!("report {} {}/{}", phase.as_ref(), done, total));
+        }
+        fn end(&mut self, phase: ProgressPhase) {
+            self.events.push(format!("end {}", phase.as_ref()));
+        }
+    }
+
+    #[test]
+    fn reports_progress_between_chunks() {
+        let mut progress = ProgressRecorder::default();
+        let total = 100 * rayon::current_num_threads();
+        let items: Vec<usize> = (0..total).collect();
+        let result = par_map_with_progress(items, ProgressPhase::Parse, &mut progress, |i| 2 * i);
+
+        assert_eq!(result, (0..total).map(|i| 2 * i).collect::<Vec<_>>());
+        assert_eq!(progress.events.len(), 22);
+        assert_eq!(progress.events[0], format!("begin parse {}", total));
+        assert_eq!(
+            progress.events[1],
+            format!("report parse {}/{}", total / 20, total)
+        );
+        assert_eq!(
+            progress.events[20],
+            format!("report parse {}/{}", total, total)
+        );
+        assert_eq!(progress.events.last().unwrap(), "end parse");
+    }
+
+    #[test]
+    fn reports_begin_and_end_without_items() {
+        let mut progress = ProgressRecorder::default();
+        let result: Vec<usize> =
+            par_map_with_progress(Vec::new(), ProgressPhase::Analyze, &mut progress, |i| i);
+
+        assert!(result.is_empty());
+        assert_eq!(progress.events, vec!["begin analyze 0", "end analyze"]);
+    }
+}
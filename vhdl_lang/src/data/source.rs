@@ -4,7 +4,7 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::contents::Contents;
+use super::contents::{Contents, Encoding};
 use super::diagnostic::{Diagnostic, DiagnosticResult};
 use parking_lot::{RwLock, RwLockReadGuard};
 use std::cmp::{max, min};
@@ -71,16 +71,15 @@ impl UniqueSource {
         }
     }
 
-    fn from_latin1_file(file_name: &Path) -> io::Result<Self> {
-        let contents = Contents::from_latin1_file(file_name)?;
+    fn from_file(file_name: &Path, encoding: Option<Encoding>) -> io::Result<Self> {
+        let contents = Contents::from_file(file_name, encoding)?;
         Ok(Self {
             file_id: FileId::new(file_name),
             contents: RwLock::new(contents),
         })
     }
 
-    #[cfg(test)]
-    pub fn from_contents(file_name: &Path, contents: Contents) -> UniqueSource {
+    fn from_contents(file_name: &Path, contents: Contents) -> UniqueSource {
         Self {
             file_id: FileId::new(file_name),
             contents: RwLock::new(contents),
@@ -141,8 +140,13 @@ impl Source {
     }
 
     pub fn from_latin1_file(file_name: &Path) -> io::Result<Source> {
+        Self::from_file(file_name, Some(Encoding::Latin1))
+    }
+
+    /// Read a source file with the given encoding, the encoding is detected when it is None
+    pub fn from_file(file_name: &Path, encoding: Option<Encoding>) -> io::Result<Source> {
         Ok(Source {
-            source: Arc::new(UniqueSource::from_latin1_file(file_name)?),
+            source: Arc::new(UniqueSource::from_file(file_name, encoding)?),
         })
     }
 
@@ -168,6 +172,17 @@ impl Source {
         }
     }
 
+    /// Create a new source of the same file with a copy of the current contents
+    /// Changing the copy does not affect design units parsed from this source
+    pub fn snapshot(&self) -> Source {
+        Source {
+            source: Arc::new(UniqueSource::from_contents(
+                self.file_name(),
+                self.contents().clone(),
+            )),
+        }
+    }
+
     pub fn change(&self, range: Option<&Range>, content: &str) {
         let mut contents = self.source.contents.write();
         if let Some(range) = range {
@@ -600,6 +615,23 @@ mod tests {
         fun(CodeBuilder::new().code_from_source(Source::from_latin1_file(&file_name).unwrap()))
     }
 
+    #[test]
+    fn change_of_snapshot_does_not_affect_source() {
+        let code = Code::new("hello world");
+        let snapshot = code.source().snapshot();
+        snapshot.change(Some(&code.s1("world").pos().range()), "there");
+
+        assert_eq!(snapshot, *code.source());
+        assert_eq!(
+            code.s1("world").pos().code_context(),
+            "\
+1 --> hello world
+   |        ~~~~~
+"
+        );
+        assert_eq!(snapshot.contents().get_line(0), Some("hello there"));
+    }
+
     #[test]
     fn code_context_pos_from_filename() {
         with_code_from_file("hello\nworld\n", |code: Code| {
@@ -15,13 +15,16 @@ mod analysis;
 mod config;
 mod data;
 mod project;
+mod standard;
 mod syntax;
 
-pub use crate::config::Config;
+pub use crate::config::{Config, LintConfig};
 pub use crate::data::{
-    Diagnostic, Latin1String, Message, MessageHandler, MessagePrinter, MessageType, Position,
-    Range, Severity, Source, SrcPos,
+    Diagnostic, Encoding, ErrorCode, Latin1String, Message, MessageHandler, MessagePrinter,
+    MessageType, NoProgress, Position, ProgressHandler, ProgressPhase, Range, Severity, Source,
+    SrcPos,
 };
 
 pub use crate::project::{Project, SourceFile};
-pub use crate::syntax::{ParserResult, VHDLParser};
+pub use crate::standard::VHDLStandard;
+pub use crate::syntax::{ParsedSource, ParserResult, VHDLParser};
@@ -13,8 +13,8 @@
 extern crate clap;
 
 use std::path::Path;
-use std::time::SystemTime;
-use vhdl_lang::{Config, Diagnostic, MessagePrinter, Project};
+use std::time::{Duration, Instant, SystemTime};
+use vhdl_lang::{Config, Diagnostic, MessagePrinter, ProgressHandler, ProgressPhase, Project};
 
 fn main() {
     use clap::{App, Arg};
@@ -32,7 +32,7 @@ fn main() {
         .arg(
             Arg::with_name("perf")
                 .long("--perf")
-                .help("Prints the number of files processed and the execution time")
+                .help("Prints the number of files processed and the execution time of each phase")
                 .takes_value(false),
         )
         .arg(
@@ -40,7 +40,16 @@ fn main() {
                 .help("Config file in TOML format containing libraries and settings")
                 .short("-c")
                 .long("--config")
-                .required(true)
+                .required_unless("import")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("import")
+                .help("Project file to import libraries from: a VUnit --export-json file, a FuseSoC .core file, a Vivado .xpr project or a Quartus .qsf file")
+                .short("-i")
+                .long("--import")
+                .multiple(true)
+                .number_of_values(1)
                 .takes_value(true),
         )
         .get_matches();
@@ -55,18 +64,31 @@ fn main() {
 
     let show_perf = matches.is_present("perf");
 
-    let file_name = value_t_or_exit!(matches.value_of("config"), String);
     let mut config = Config::default();
     let mut msg_printer = MessagePrinter::default();
     config.load_external_config(&mut msg_printer);
-    config.append(
-        &Config::read_file_path(Path::new(&file_name)).expect("Failed to read config file"),
-        &mut msg_printer,
-    );
+
+    if let Some(file_name) = matches.value_of("config") {
+        config.append(
+            &Config::read_file_path(Path::new(file_name), &mut msg_printer)
+                .expect("Failed to read config file"),
+            &mut msg_printer,
+        );
+    }
+
+    if let Some(file_names) = matches.values_of("import") {
+        for file_name in file_names {
+            config.append(
+                &Config::import_file(Path::new(file_name)).expect("Failed to import project file"),
+                &mut msg_printer,
+            );
+        }
+    }
 
     let start = SystemTime::now();
-    let mut project = Project::from_config(&config, &mut msg_printer);
-    let diagnostics = project.analyse();
+    let mut timer = PhaseTimer::default();
+    let mut project = Project::from_config_with_progress(&config, &mut msg_printer, &mut timer);
+    let diagnostics = project.analyse_with_progress(&mut timer);
     let duration = start.elapsed().unwrap();
     show_diagnostics(&diagnostics);
 
@@ -88,12 +110,43 @@ fn main() {
             duration.as_millis(),
             duration_per_line.as_nanos()
         );
+        for (phase, total, duration) in timer.phases.iter() {
+            let items = match phase {
+                ProgressPhase::Read => format!("Read {} files", total),
+                ProgressPhase::Parse => format!("Parsed {} files", total),
+                ProgressPhase::Analyze => format!("Analyzed {} design units", total),
+            };
+            println!("{} in {} ms", items, duration.as_millis());
+        }
     }
 
     // Exit without running Drop on entire allocated AST
     std::process::exit(0);
 }
 
+/// Measures the execution time of each phase
+#[derive(Default)]
+struct PhaseTimer {
+    start: Option<Instant>,
+    total: usize,
+    phases: Vec<(ProgressPhase, usize, Duration)>,
+}
+
+impl ProgressHandler for PhaseTimer {
+    fn begin(&mut self, _phase: ProgressPhase, total: usize) {
+        self.start = Some(Instant::now());
+        self.total = total;
+    }
+
+    fn report(&mut self, _phase: ProgressPhase, _done: usize, _total: usize) {}
+
+    fn end(&mut self, phase: ProgressPhase) {
+        if let Some(start) = self.start.take() {
+            self.phases.push((phase, self.total, start.elapsed()));
+        }
+    }
+}
+
 fn show_diagnostics(diagnostics: &[Diagnostic]) {
     for diagnostic in diagnostics {
         println!("{}", diagnostic.show());
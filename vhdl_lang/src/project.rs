@@ -8,16 +8,24 @@ use crate::analysis::DesignRoot;
 use crate::ast::DesignFile;
 use crate::config::Config;
 use crate::data::*;
-use crate::syntax::VHDLParser;
+use crate::standard::VHDLStandard;
+use crate::syntax::{Directives, Suppressions, VHDLParser};
 use fnv::{FnvHashMap, FnvHashSet};
 use std::collections::hash_map::Entry;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+/// Cloning is cheap since source files and analyzed design units are shared
+/// with the copy, they are replaced in the original when they change
+#[derive(Clone)]
 pub struct Project {
     parser: VHDLParser,
     root: DesignRoot,
-    files: FnvHashMap<PathBuf, SourceFile>,
+    files: FnvHashMap<PathBuf, Arc<SourceFile>>,
     empty_libraries: FnvHashSet<Symbol>,
+    third_party_libraries: FnvHashSet<Symbol>,
+    // Used to map files which are not part of the project to libraries
+    config: Config,
 }
 
 impl Project {
@@ -27,6 +35,8 @@ impl Project {
             root: DesignRoot::new(parser.symbols.clone()),
             files: FnvHashMap::default(),
             empty_libraries: FnvHashSet::default(),
+            third_party_libraries: FnvHashSet::default(),
+            config: Config::default(),
             parser,
         }
     }
@@ -34,10 +44,21 @@ impl Project {
     /// Create instance from given configuration.
     /// Files referred by configuration are parsed into corresponding libraries.
     pub fn from_config(config: &Config, messages: &mut dyn MessageHandler) -> Project {
+        Self::from_config_with_progress(config, messages, &mut NoProgress::default())
+    }
+
+    /// Create instance from given configuration and report the progress of reading and parsing files.
+    pub fn from_config_with_progress(
+        config: &Config,
+        messages: &mut dyn MessageHandler,
+        progress: &mut dyn ProgressHandler,
+    ) -> Project {
         let mut project = Project::new();
+        project.config = config.clone();
+        project.parser.identifiers = config.conditional_analysis().clone();
 
         let files = project.load_files_from_config(config, messages);
-        project.parse_and_add_files(files, messages);
+        project.parse_and_add_files(files, messages, progress);
 
         project
     }
@@ -46,60 +67,106 @@ impl Project {
     /// The design state is reset, new files are added and parsed. Existing source files will be
     /// kept and parsed from in-memory source (required for incremental document updates).
     pub fn update_config(&mut self, config: &Config, messages: &mut dyn MessageHandler) {
-        self.parser = VHDLParser::default();
+        self.update_config_with_progress(config, messages, &mut NoProgress::default())
+    }
+
+    /// Replace active project configuration and report the progress of reading and parsing new files.
+    pub fn update_config_with_progress(
+        &mut self,
+        config: &Config,
+        messages: &mut dyn MessageHandler,
+        progress: &mut dyn ProgressHandler,
+    ) {
+        self.parser = VHDLParser {
+            identifiers: config.conditional_analysis().clone(),
+            ..VHDLParser::default()
+        };
         self.root = DesignRoot::new(self.parser.symbols.clone());
+        self.config = config.clone();
 
         // Reset library associations for known files,
         // all project files are added to the corresponding libraries later on.
         self.files
             .values_mut()
-            .for_each(|source_file| source_file.library_names.clear());
+            .for_each(|source_file| Arc::make_mut(source_file).library_names.clear());
 
         // Files might already be part of self.files, these have to be parsed
         // from in-memory source. New files can be parsed as usual.
         let (known_files, new_files) = self
             .load_files_from_config(config, messages)
             .into_iter()
-            .partition(|(file_name, _library_names)| self.files.contains_key(file_name));
+            .partition(|(file_name, _)| self.files.contains_key(file_name));
 
-        for (file_name, library_names) in known_files {
+        for (file_name, (library_names, standard, _)) in known_files {
             if let Some(source_file) = self.files.get_mut(&file_name) {
+                let source_file = Arc::make_mut(source_file);
                 source_file.parser_diagnostics.clear();
                 source_file.library_names = library_names;
-                source_file.design_file = self
-                    .parser
-                    .parse_design_source(&source_file.source, &mut source_file.parser_diagnostics);
+                source_file.standard = standard;
+                let parsed = self.parser.parse_design_source(
+                    &source_file.source,
+                    standard,
+                    &mut source_file.parser_diagnostics,
+                );
+                source_file.design_file = parsed.design_file;
+                source_file.suppressions = parsed.suppressions;
+                source_file.directives = parsed.directives;
             }
         }
 
-        self.parse_and_add_files(new_files, messages);
+        self.parse_and_add_files(new_files, messages, progress);
     }
 
     fn load_files_from_config(
         &mut self,
         config: &Config,
         messages: &mut dyn MessageHandler,
-    ) -> FnvHashMap<PathBuf, FnvHashSet<Symbol>> {
-        let mut files: FnvHashMap<PathBuf, FnvHashSet<Symbol>> = FnvHashMap::default();
+    ) -> FnvHashMap<PathBuf, (FnvHashSet<Symbol>, VHDLStandard, Option<Encoding>)> {
+        let mut files: FnvHashMap<PathBuf, (FnvHashSet<Symbol>, VHDLStandard, Option<Encoding>)> =
+            FnvHashMap::default();
+        // The library which decided the VHDL standard of each file
+        let mut standard_libraries: FnvHashMap<PathBuf, &str> = FnvHashMap::default();
         self.empty_libraries.clear();
+        self.third_party_libraries.clear();
+
+        // Sorted such that the library deciding the standard of a file is deterministic
+        let mut libraries: Vec<_> = config.iter_libraries().collect();
+        libraries.sort_unstable_by_key(|library| library.name());
 
-        for library in config.iter_libraries() {
+        for library in libraries {
             let library_name =
                 Latin1String::from_utf8(library.name()).expect("Library name not latin-1 encoded");
             let library_name = self.parser.symbol(&library_name);
 
+            if library.is_third_party() {
+                self.third_party_libraries.insert(library_name.clone());
+            }
+
             let mut empty_library = true;
-            for file_name in library.file_names(messages) {
+            for (file_name, standard) in library.files(messages) {
                 empty_library = false;
 
                 match files.entry(file_name.clone()) {
                     Entry::Occupied(mut entry) => {
-                        entry.get_mut().insert(library_name.clone());
+                        let (library_names, file_standard, _) = entry.get_mut();
+                        library_names.insert(library_name.clone());
+
+                        if *file_standard != standard {
+                            messages.push(Message::warning(format!(
+                                "File {} is part of libraries with different VHDL standards, using {} of library {} instead of {} of library {}",
+                                file_name.to_string_lossy(),
+                                file_standard,
+                                standard_libraries.get(&file_name).copied().unwrap_or_default(),
+                                standard,
+                                library.name()
+                            )));
+                        }
                     }
                     Entry::Vacant(entry) => {
                         let mut set = FnvHashSet::default();
                         set.insert(library_name.clone());
-                        entry.insert(set);
+                        entry.insert((set, standard, library.encoding()));
+                        standard_libraries.insert(file_name, library.name());
                     }
                 }
             }
@@ -113,40 +180,52 @@ impl Project {
 
     fn parse_and_add_files(
         &mut self,
-        files_to_parse: FnvHashMap<PathBuf, FnvHashSet<Symbol>>,
+        files_to_parse: FnvHashMap<PathBuf, (FnvHashSet<Symbol>, VHDLStandard, Option<Encoding>)>,
         messages: &mut dyn MessageHandler,
+        progress: &mut dyn ProgressHandler,
     ) {
-        use rayon::prelude::*;
-
-        let parsed: Vec<_> = files_to_parse
-            .into_par_iter()
-            .map_init(
-                || &self.parser,
-                |parser, (file_name, library_names)| {
-                    let mut diagnostics = Vec::new();
-                    let result = parser.parse_design_file(&file_name, &mut diagnostics);
-                    (file_name, library_names, diagnostics, result)
-                },
-            )
-            .collect();
+        let read = par_map_with_progress(
+            files_to_parse.into_iter().collect(),
+            ProgressPhase::Read,
+            progress,
+            |(file_name, (library_names, standard, encoding))| {
+                let result = Source::from_file(&file_name, encoding);
+                (file_name, library_names, standard, result)
+            },
+        );
 
-        for (file_name, library_names, parser_diagnostics, result) in parsed.into_iter() {
-            let (source, design_file) = match result {
-                Ok(result) => result,
-                Err(err) => {
-                    messages.push(Message::file_error(err.to_string(), &file_name));
-                    continue;
-                }
-            };
+        let mut sources = Vec::with_capacity(read.len());
+        for (file_name, library_names, standard, result) in read.into_iter() {
+            match result {
+                Ok(source) => sources.push((source, library_names, standard)),
+                Err(err) => messages.push(Message::file_error(err.to_string(), &file_name)),
+            }
+        }
 
-            self.files.insert(
-                source.file_name().to_owned(),
+        let parser = &self.parser;
+        let parsed = par_map_with_progress(
+            sources,
+            ProgressPhase::Parse,
+            progress,
+            |(source, library_names, standard)| {
+                let mut parser_diagnostics = Vec::new();
+                let parsed = parser.parse_design_source(&source, standard, &mut parser_diagnostics);
                 SourceFile {
                     source,
                     library_names,
+                    standard,
                     parser_diagnostics,
-                    design_file,
-                },
+                    design_file: parsed.design_file,
+                    suppressions: parsed.suppressions,
+                    directives: parsed.directives,
+                }
+            },
+        );
+
+        for source_file in parsed.into_iter() {
+            self.files.insert(
+                source_file.source.file_name().to_owned(),
+                Arc::new(source_file),
             );
         }
     }
@@ -155,10 +234,17 @@ impl Project {
         self.files.get(file_name).map(|file| file.source.clone())
     }
 
+    /// Replace the contents of a source file or add it to the project
+    ///
+    /// The whole file is parsed again, design units with unchanged text at the same
+    /// position keep their analysis result such that only the edited units and their
+    /// users are analyzed again.
     pub fn update_source(&mut self, source: &Source) {
         let mut source_file = {
-            if let Some(mut source_file) = self.files.remove(source.file_name()) {
+            if let Some(source_file) = self.files.remove(source.file_name()) {
                 // File is already part of the project
+                let mut source_file =
+                    Arc::try_unwrap(source_file).unwrap_or_else(|shared| (*shared).clone());
                 for library_name in source_file.library_names.iter() {
                     self.root.remove_source(library_name.clone(), source);
                 }
@@ -166,44 +252,102 @@ impl Project {
                 source_file
             } else {
                 // File is not part of the project
-                // @TODO use config wildcards to map to library
-
-                // Add unmapped files to an anonymous library work
-                // To still get some semantic analysis for unmapped files
-                let mut library_names = FnvHashSet::default();
-                library_names.insert(self.root.symbol_utf8("work"));
+                let (library_names, standard) = self.map_to_libraries(source.file_name());
 
                 SourceFile {
                     source: source.clone(),
                     library_names,
+                    standard,
                     parser_diagnostics: vec![],
                     design_file: DesignFile::default(),
+                    suppressions: Suppressions::default(),
+                    directives: Directives::default(),
                 }
             }
         };
         source_file.parser_diagnostics.clear();
-        source_file.design_file = self
-            .parser
-            .parse_design_source(source, &mut source_file.parser_diagnostics);
+        let parsed = self.parser.parse_design_source(
+            source,
+            source_file.standard,
+            &mut source_file.parser_diagnostics,
+        );
+        source_file.design_file = parsed.design_file;
+        source_file.suppressions = parsed.suppressions;
+        source_file.directives = parsed.directives;
         self.files
-            .insert(source.file_name().to_owned(), source_file);
+            .insert(source.file_name().to_owned(), Arc::new(source_file));
+    }
+
+    /// Remove a file from the project, for example when it has been deleted
+    pub fn remove_source(&mut self, file_name: &Path) {
+        if let Some(source_file) = self.files.remove(file_name) {
+            for library_name in source_file.library_names.iter() {
+                self.root
+                    .remove_source(library_name.clone(), &source_file.source);
+            }
+        }
+    }
+
+    /// Returns true if a file which is not part of the project would be added to a
+    /// configured library or the default library
+    pub fn is_mapped_by_config(&self, file_name: &Path) -> bool {
+        self.config.default_library().is_some() || !self.config.map_file(file_name).is_empty()
+    }
+
+    /// Map a file which is not part of the project to the libraries whose file patterns
+    /// match the file, otherwise to the default library
+    fn map_to_libraries(&self, file_name: &Path) -> (FnvHashSet<Symbol>, VHDLStandard) {
+        let mut library_names = FnvHashSet::default();
+        let mut standard = None;
+
+        for (library_name, file_standard) in self.config.map_file(file_name) {
+            library_names.insert(self.root.symbol_utf8(library_name));
+            standard.get_or_insert(file_standard);
+        }
+
+        if library_names.is_empty() {
+            if let Some(library_name) = self.config.default_library() {
+                library_names.insert(self.root.symbol_utf8(library_name));
+                standard = self
+                    .config
+                    .get_library(library_name)
+                    .and_then(|library| library.standard());
+            } else {
+                // Add unmapped files to an anonymous library work
+                // To still get some semantic analysis for unmapped files
+                library_names.insert(self.root.symbol_utf8("work"));
+            }
+        }
+
+        (library_names, standard.unwrap_or_default())
     }
 
     pub fn analyse(&mut self) -> Vec<Diagnostic> {
+        self.analyse_with_progress(&mut NoProgress::default())
+    }
+
+    /// Analyse the project and report the progress of analyzing design units
+    pub fn analyse_with_progress(&mut self, progress: &mut dyn ProgressHandler) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
         for source_file in self.files.values_mut() {
-            let design_file = source_file.take_design_file();
+            // Files which were not parsed since the last analysis stay shared with copies
+            let design_file = if source_file.design_file.design_units.is_empty() {
+                DesignFile::default()
+            } else {
+                Arc::make_mut(source_file).take_design_file()
+            };
             // Avoid cloning design files for single library
             let mut design_files = multiply(design_file, source_file.library_names.len());
 
             for library_name in source_file.library_names.iter() {
                 let design_file = design_files.pop().unwrap();
-                self.root.add_design_file(library_name.clone(), design_file);
+                self.root
+                    .add_design_file(library_name.clone(), design_file, source_file.standard);
             }
 
             for diagnostic in source_file.parser_diagnostics.iter().cloned() {
-                diagnostics.push(diagnostic);
+                diagnostics.push(diagnostic.or_code(ErrorCode::Syntax));
             }
         }
 
@@ -211,8 +355,56 @@ impl Project {
             self.root.ensure_library(library_name.clone());
         }
 
-        self.root.analyze(&mut diagnostics);
-        diagnostics
+        self.root.analyze(&mut diagnostics, progress);
+
+        let lint = self.config.lint();
+        self.apply_suppressions(diagnostics)
+            .into_iter()
+            .filter(|diagnostic| !self.is_third_party(diagnostic))
+            .filter_map(|diagnostic| lint.apply(diagnostic))
+            .collect()
+    }
+
+    /// Diagnostics within files that only belong to third-party libraries are not reported
+    fn is_third_party(&self, diagnostic: &Diagnostic) -> bool {
+        if let Some(source_file) = self.files.get(diagnostic.pos.source.file_name()) {
+            !source_file.library_names.is_empty()
+                && source_file
+                    .library_names
+                    .iter()
+                    .all(|library_name| self.third_party_libraries.contains(library_name))
+        } else {
+            false
+        }
+    }
+
+    /// Remove diagnostics suppressed by comments in the source files
+    /// Suppressions which do not suppress anything produce a warning
+    fn apply_suppressions(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let mut used: FnvHashMap<&Path, FnvHashSet<usize>> = FnvHashMap::default();
+        let mut result = Vec::with_capacity(diagnostics.len());
+
+        for diagnostic in diagnostics.into_iter() {
+            let file_name = diagnostic.pos.source.file_name();
+            if let Some(source_file) = self.files.get(file_name) {
+                let suppressed_by = source_file.suppressions.suppresses(&diagnostic);
+                if !suppressed_by.is_empty() {
+                    used.entry(source_file.source.file_name())
+                        .or_default()
+                        .extend(suppressed_by);
+                    continue;
+                }
+            }
+            result.push(diagnostic);
+        }
+
+        let no_used = FnvHashSet::default();
+        for source_file in self.files.values() {
+            let used = used.get(source_file.source.file_name()).unwrap_or(&no_used);
+            result.extend(source_file.suppressions.diagnostics(used));
+        }
+
+        result
     }
 
     /// Search for reference at position
@@ -237,7 +429,7 @@ impl Project {
     }
 
     pub fn files(&self) -> impl Iterator<Item = &SourceFile> {
-        self.files.values()
+        self.files.values().map(|source_file| source_file.as_ref())
     }
 }
 
@@ -264,11 +456,15 @@ impl Default for Project {
     }
 }
 
+#[derive(Clone)]
 pub struct SourceFile {
     library_names: FnvHashSet<Symbol>,
     source: Source,
+    standard: VHDLStandard,
     design_file: DesignFile,
     parser_diagnostics: Vec<Diagnostic>,
+    suppressions: Suppressions,
+    directives: Directives,
 }
 
 impl SourceFile {
@@ -279,6 +475,20 @@ impl SourceFile {
     pub fn num_lines(&self) -> usize {
         self.source.contents().num_lines()
     }
+
+    /// The VHDL standard the file is parsed and analyzed with
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
+    pub fn file_name(&self) -> &Path {
+        self.source.file_name()
+    }
+
+    /// The regions of the file which are excluded by conditional analysis
+    pub fn inactive_ranges(&self) -> &[Range] {
+        self.directives.inactive_ranges()
+    }
 }
 
 #[cfg(test)]
@@ -399,6 +609,46 @@ use_lib.files = ['use_file.vhd']
         check_no_diagnostics(&project.analyse());
     }
 
+    #[test]
+    fn test_same_file_in_libraries_with_different_standards() {
+        let root = tempfile::tempdir().unwrap();
+        let vhdl_file_path = root.path().join("file.vhd");
+        std::fs::write(
+            &vhdl_file_path,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib1.standard = '93'
+lib1.files = ['file.vhd']
+lib2.files = ['file.vhd']
+lib3.standard = '93'
+lib3.files = ['file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        let file_name = dunce::canonicalize(&vhdl_file_path).unwrap();
+        assert_eq!(
+            messages,
+            vec![Message::warning(format!(
+                "File {} is part of libraries with different VHDL standards, using VHDL-93 of library lib1 instead of VHDL-2008 of library lib2",
+                file_name.to_string_lossy()
+            ))]
+        );
+        assert_eq!(
+            project.files().next().unwrap().standard(),
+            VHDLStandard::VHDL1993
+        );
+        check_no_diagnostics(&project.analyse());
+    }
+
     fn update(project: &mut Project, source: &mut Source, contents: &str) {
         std::fs::write(&std::path::Path::new(source.file_name()), contents).unwrap();
         *source = Source::from_latin1_file(source.file_name()).unwrap();
@@ -567,4 +817,423 @@ lib.files = ['file2.vhd']
         assert_eq!(diagnostics[0].pos.source, source2); // No such library
         assert_eq!(diagnostics[1].pos.source, source2); // No declaration
     }
+
+    /// Test that diagnostics within third-party libraries are not reported
+    #[test]
+    fn test_third_party_library() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let vendor_path = root.join("vendor.vhd");
+        std::fs::write(
+            &vendor_path,
+            "
+package vendor_pkg is
+  type enum_t is (alpha);
+  type enum_t is (beta);
+end package;
+        ",
+        )
+        .unwrap();
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+library vendor;
+use vendor.vendor_pkg.missing;
+
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        let source = Source::from_latin1_file(&path).unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+vendor.files = ['vendor.vhd']
+vendor.is_third_party = true
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        // Only the missing declaration within the non third-party library is reported
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pos.source, source);
+        assert_eq!(diagnostics[0].code, Some(ErrorCode::Unresolved));
+    }
+
+    /// Test that diagnostics can be suppressed by comments
+    #[test]
+    fn test_suppression_comments() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+package pkg is
+  type enum_t is (alpha);
+  -- vhdl_ls: disable=duplicate-declaration
+  type enum_t is (beta);
+  -- vhdl_ls: disable=unresolved
+  type other_t is (gamma);
+end package;
+        ",
+        )
+        .unwrap();
+        let source = Source::from_latin1_file(&path).unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pos.source, source);
+        assert_eq!(diagnostics[0].code, Some(ErrorCode::UnusedSuppression));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    /// Test that a diagnostic uses all suppressions which suppress it
+    #[test]
+    fn test_overlapping_suppression_comments() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+-- vhdl_ls: disable-file=duplicate-declaration
+package pkg is
+  type enum_t is (alpha);
+  -- vhdl_ls: disable=duplicate-declaration
+  type enum_t is (beta);
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+        assert_eq!(project.analyse(), vec![]);
+    }
+
+    /// Test that conditional analysis uses the identifiers of the configuration
+    #[test]
+    fn test_conditional_analysis() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+package pkg is
+`if TOOL_TYPE = \"SIMULATION\" then
+  type enum_t is (alpha);
+`else
+  type enum_t is (beta);
+`end if
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+lib.standard = '2019'
+
+[conditional_analysis]
+TOOL_TYPE = 'SIMULATION'
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        let source_file = project.files().next().unwrap();
+        assert_eq!(source_file.file_name(), path);
+        assert_eq!(
+            source_file.inactive_ranges(),
+            &[Range::new(Position::new(4, 5), Position::new(6, 0))]
+        );
+    }
+
+    /// Test that the diagnostics of warning and error directives can be configured and suppressed
+    #[test]
+    fn test_warning_and_error_directives() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+`warning \"not yet verified\"
+-- vhdl_ls: disable=error-directive
+`error \"not supported\"
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+lib.standard = '2019'
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some(ErrorCode::WarningDirective));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+        let config = Config::from_str(
+            &format!("{config_str}\n[lint]\nwarning-directive = 'off'"),
+            &root,
+        )
+        .unwrap();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+    }
+
+    /// Test that encrypted IP is skipped and that its interface can be declared by a stub file
+    #[test]
+    fn test_protected_envelope_with_stub() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        std::fs::write(
+            root.join("encrypted.vhd"),
+            "
+`protect begin_protected
+`protect encoding = (enctype = \"BASE64\", line_length = 76, bytes = 64)
+`protect data_block
+/ZZyQ1kV3xz9+0Hf4H8dYc/vHsxUXZp+q2lM0bBz4f9NzA5v1fQ1
+`protect end_protected
+        ",
+        )
+        .unwrap();
+
+        std::fs::write(
+            root.join("stub.vhd"),
+            "
+entity secret_ip is
+end entity;
+        ",
+        )
+        .unwrap();
+
+        std::fs::write(
+            root.join("top.vhd"),
+            "
+library ip;
+
+entity top is
+end entity;
+
+architecture a of top is
+begin
+  inst: entity ip.secret_ip;
+end architecture;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+ip.files = ['encrypted.vhd', 'stub.vhd']
+ip.standard = '93'
+lib.files = ['top.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+    }
+
+    /// Test that the lint configuration changes the severity of diagnostics
+    #[test]
+    fn test_lint_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+library lib;
+
+package pkg is
+  type enum_t is (alpha);
+  type enum_t is (beta);
+end package;
+        ",
+        )
+        .unwrap();
+
+        let path = root.join("syntax_error.vhd");
+        std::fs::write(
+            &path,
+            "
+package is
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd', 'syntax_error.vhd']
+
+[lint]
+duplicate-declaration = 'warning'
+unnecessary-library-clause = 'off'
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let mut diagnostics: Vec<_> = project
+            .analyse()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.severity))
+            .collect();
+        diagnostics.sort_by_key(|(code, _)| code.map(|code| code.as_str()));
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                (Some(ErrorCode::DuplicateDeclaration), Severity::Warning),
+                (Some(ErrorCode::Syntax), Severity::Error),
+            ]
+        );
+    }
+
+    /// Test that files which are not part of the project are mapped to libraries
+    /// by the file patterns of the configuration
+    #[test]
+    fn test_new_file_is_mapped_by_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::create_dir(root.join("lib1")).unwrap();
+
+        let use_path = root.join("use_file.vhd");
+        std::fs::write(
+            &use_path,
+            "
+library lib1;
+use lib1.pkg.all;
+
+package use_pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['lib1/*.vhd']
+lib2.files = ['use_file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(project.analyse().len(), 1);
+
+        // A new file matching the pattern of lib1 is added to lib1
+        let pkg_path = root.join("lib1").join("pkg.vhd");
+        std::fs::write(
+            &pkg_path,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        assert!(project.is_mapped_by_config(&pkg_path));
+        let source = Source::from_latin1_file(&pkg_path).unwrap();
+        project.update_source(&source);
+        check_no_diagnostics(&project.analyse());
+
+        // The reference is unresolved again after the file has been removed
+        project.remove_source(&pkg_path);
+        assert_eq!(project.analyse().len(), 1);
+    }
+
+    /// Test that files not matched by any library are added to the default library
+    #[test]
+    fn test_new_file_is_added_to_default_library() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let config_str = "
+default_library = 'lib'
+
+[libraries]
+lib.files = []
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(&config, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let path = root.join("file.vhd");
+        std::fs::write(
+            &path,
+            "
+package pkg is
+end package;
+
+library lib;
+use lib.pkg.all;
+
+package use_pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        assert!(project.is_mapped_by_config(&path));
+        let source = Source::from_latin1_file(&path).unwrap();
+        project.update_source(&source);
+        check_no_diagnostics(&project.analyse());
+    }
 }
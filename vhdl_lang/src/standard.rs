@@ -0,0 +1,84 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Revisions of the VHDL language standard
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A revision of the VHDL standard (IEEE 1076)
+///
+/// The revisions are ordered such that a later revision compares greater than an earlier one.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug, Default)]
+pub enum VHDLStandard {
+    VHDL1993,
+    VHDL2002,
+    #[default]
+    VHDL2008,
+    VHDL2019,
+}
+
+impl VHDLStandard {
+    /// Returns the short name used in the configuration file such as "2008"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VHDLStandard::VHDL1993 => "93",
+            VHDLStandard::VHDL2002 => "2002",
+            VHDLStandard::VHDL2008 => "2008",
+            VHDLStandard::VHDL2019 => "2019",
+        }
+    }
+}
+
+impl FromStr for VHDLStandard {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<VHDLStandard, String> {
+        match string {
+            "93" | "1993" => Ok(VHDLStandard::VHDL1993),
+            "02" | "2002" => Ok(VHDLStandard::VHDL2002),
+            "08" | "2008" => Ok(VHDLStandard::VHDL2008),
+            "19" | "2019" => Ok(VHDLStandard::VHDL2019),
+            _ => Err(format!(
+                "Invalid VHDL standard '{}', expected '93', '2002', '2008' or '2019'",
+                string
+            )),
+        }
+    }
+}
+
+impl fmt::Display for VHDLStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VHDLStandard::VHDL1993 => write!(f, "VHDL-93"),
+            VHDLStandard::VHDL2002 => write!(f, "VHDL-2002"),
+            VHDLStandard::VHDL2008 => write!(f, "VHDL-2008"),
+            VHDLStandard::VHDL2019 => write!(f, "VHDL-2019"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_from_str() {
+        assert_eq!("93".parse(), Ok(VHDLStandard::VHDL1993));
+        assert_eq!("1993".parse(), Ok(VHDLStandard::VHDL1993));
+        assert_eq!("2002".parse(), Ok(VHDLStandard::VHDL2002));
+        assert_eq!("2008".parse(), Ok(VHDLStandard::VHDL2008));
+        assert_eq!("2019".parse(), Ok(VHDLStandard::VHDL2019));
+        assert!("87".parse::<VHDLStandard>().is_err());
+    }
+
+    #[test]
+    fn standards_are_ordered() {
+        assert!(VHDLStandard::VHDL1993 < VHDLStandard::VHDL2002);
+        assert!(VHDLStandard::VHDL2002 < VHDLStandard::VHDL2008);
+        assert!(VHDLStandard::VHDL2008 < VHDLStandard::VHDL2019);
+    }
+}
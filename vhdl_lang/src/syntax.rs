@@ -16,20 +16,28 @@ mod configuration;
 mod context;
 mod declarative_part;
 mod design_unit;
+mod directives;
+mod disconnection;
 mod expression;
+mod group_declaration;
 mod interface_declaration;
 mod names;
 mod object_declaration;
 mod parser;
+mod psl;
 mod range;
 mod sequential_statement;
 mod subprogram;
 mod subtype_indication;
+mod suppression;
 mod type_declaration;
+mod view_declaration;
 mod waveform;
 
 #[cfg(test)]
 pub mod test;
 
-pub use parser::{ParserResult, VHDLParser};
+pub use directives::Directives;
+pub use parser::{ParsedSource, ParserResult, VHDLParser};
+pub use suppression::Suppressions;
 pub use tokens::Symbols;
@@ -11,10 +11,10 @@ use super::subprogram::parse_signature;
 use super::tokens::{Kind::*, TokenStream};
 use crate::ast::{
     Attribute, AttributeDeclaration, AttributeSpecification, Designator, EntityClass, EntityName,
-    EntityTag,
+    EntityTag, WithRef,
 };
 
-fn parse_entity_class(stream: &mut TokenStream) -> ParseResult<EntityClass> {
+pub fn parse_entity_class(stream: &mut TokenStream) -> ParseResult<EntityClass> {
     let token = stream.expect()?;
     Ok(try_token_kind!(
         token,
@@ -29,7 +29,13 @@ fn parse_entity_class(stream: &mut TokenStream) -> ParseResult<EntityClass> {
         Component => EntityClass::Component,
         Constant => EntityClass::Constant,
         Type => EntityClass::Type,
-        Label => EntityClass::Label
+        Subtype => EntityClass::Subtype,
+        Label => EntityClass::Label,
+        Units => EntityClass::Units,
+        Group => EntityClass::Group,
+        File => EntityClass::File,
+        Property => EntityClass::Property,
+        Sequence => EntityClass::Sequence
     ))
 }
 
@@ -43,8 +49,8 @@ pub fn parse_entity_name_list(stream: &mut TokenStream) -> ParseResult<Vec<Entit
                 let designator_token = stream.expect()?;
                 let designator = try_token_kind!(
                     designator_token,
-                    Identifier => designator_token.expect_ident()?.map_into(Designator::Identifier),
-                    StringLiteral => designator_token.expect_string()?.map_into(Designator::OperatorSymbol));
+                    Identifier => designator_token.expect_ident()?.map_into(Designator::Identifier).into_ref(),
+                    StringLiteral => designator_token.expect_string()?.map_into(Designator::OperatorSymbol).into_ref());
 
                 let signature = {
                     if stream.peek_kind()? == Some(LeftSquare) {
@@ -111,7 +117,7 @@ pub fn parse_attribute(stream: &mut TokenStream) -> ParseResult<Vec<Attribute>>
                 .into_iter()
                 .map(|entity_name| {
                     Attribute::Specification(AttributeSpecification {
-                        ident: ident.clone(),
+                        ident: WithRef::new(ident.clone()),
                         entity_name,
                         entity_class,
                         expr: expr.clone(),
@@ -144,9 +150,9 @@ mod tests {
         assert_eq!(
             code.with_stream(parse_attribute),
             vec![Attribute::Specification(AttributeSpecification {
-                ident: code.s1("attr_name").ident(),
+                ident: WithRef::new(code.s1("attr_name").ident()),
                 entity_name: EntityName::Name(EntityTag {
-                    designator: code.s1("foo").designator(),
+                    designator: code.s1("foo").designator_ref(),
                     signature: None
                 }),
                 entity_class: EntityClass::Signal,
@@ -161,9 +167,9 @@ mod tests {
         assert_eq!(
             code.with_stream(parse_attribute),
             vec![Attribute::Specification(AttributeSpecification {
-                ident: code.s1("attr_name").ident(),
+                ident: WithRef::new(code.s1("attr_name").ident()),
                 entity_name: EntityName::Name(EntityTag {
-                    designator: code.s1("\"**\"").designator(),
+                    designator: code.s1("\"**\"").designator_ref(),
                     signature: None
                 }),
                 entity_class: EntityClass::Function,
@@ -179,18 +185,18 @@ mod tests {
             code.with_stream(parse_attribute),
             vec![
                 Attribute::Specification(AttributeSpecification {
-                    ident: code.s1("attr_name").ident(),
+                    ident: WithRef::new(code.s1("attr_name").ident()),
                     entity_name: EntityName::Name(EntityTag {
-                        designator: code.s1("foo").designator(),
+                        designator: code.s1("foo").designator_ref(),
                         signature: None
                     }),
                     entity_class: EntityClass::Signal,
                     expr: code.s1("0+1").expr()
                 }),
                 Attribute::Specification(AttributeSpecification {
-                    ident: code.s1("attr_name").ident(),
+                    ident: WithRef::new(code.s1("attr_name").ident()),
                     entity_name: EntityName::Name(EntityTag {
-                        designator: code.s1("bar").designator(),
+                        designator: code.s1("bar").designator_ref(),
                         signature: None
                     }),
                     entity_class: EntityClass::Signal,
@@ -206,7 +212,7 @@ mod tests {
         assert_eq!(
             code.with_stream(parse_attribute),
             vec![Attribute::Specification(AttributeSpecification {
-                ident: code.s1("attr_name").ident(),
+                ident: WithRef::new(code.s1("attr_name").ident()),
                 entity_name: EntityName::All,
                 entity_class: EntityClass::Signal,
                 expr: code.s1("0+1").expr()
@@ -220,7 +226,7 @@ mod tests {
         assert_eq!(
             code.with_stream(parse_attribute),
             vec![Attribute::Specification(AttributeSpecification {
-                ident: code.s1("attr_name").ident(),
+                ident: WithRef::new(code.s1("attr_name").ident()),
                 entity_name: EntityName::Others,
                 entity_class: EntityClass::Signal,
                 expr: code.s1("0+1").expr()
@@ -234,9 +240,9 @@ mod tests {
         assert_eq!(
             code.with_stream(parse_attribute),
             vec![Attribute::Specification(AttributeSpecification {
-                ident: code.s1("attr_name").ident(),
+                ident: WithRef::new(code.s1("attr_name").ident()),
                 entity_name: EntityName::Name(EntityTag {
-                    designator: code.s1("foo").designator(),
+                    designator: code.s1("foo").designator_ref(),
                     signature: Some(code.s1("[return natural]").signature())
                 }),
                 entity_class: EntityClass::Function,
@@ -4,9 +4,9 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::tokens::{Kind, TokenStream};
+use super::tokens::{kind_str, Kind, Token, TokenStream};
 use crate::ast::Ident;
-use crate::data::Diagnostic;
+use crate::data::{Diagnostic, DiagnosticHandler};
 
 /// Parse optional part followed by optional keyword
 pub fn parse_optional<F, R>(
@@ -43,4 +43,27 @@ pub fn error_on_end_identifier_mismatch(
     None
 }
 
+/// Expect the kind of statement after the 'end' of a compound statement.
+/// When another kind follows the 'end' it is assumed to end an enclosing statement, such as
+/// when the 'end if' of an if statement being written is still missing. The missing end is
+/// reported and the stream is moved back before the 'end' to let the enclosing statement
+/// parse it. Returns false when the end was missing.
+pub fn expect_end_kind(
+    stream: &mut TokenStream,
+    end_token: &Token,
+    kind: Kind,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> ParseResult<bool> {
+    if stream.skip_if_kind(kind)? {
+        Ok(true)
+    } else {
+        diagnostics.push(Diagnostic::error(
+            end_token,
+            format!("Missing 'end {}'", kind_str(kind)),
+        ));
+        stream.move_before(end_token);
+        Ok(false)
+    }
+}
+
 pub type ParseResult<T> = Result<T, Diagnostic>;
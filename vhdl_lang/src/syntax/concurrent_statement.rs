@@ -4,8 +4,8 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::common::error_on_end_identifier_mismatch;
 use super::common::ParseResult;
+use super::common::{error_on_end_identifier_mismatch, expect_end_kind};
 use super::declarative_part::{is_declarative_part, parse_declarative_part};
 use super::expression::parse_aggregate_leftpar_known;
 use super::expression::{parse_choices, parse_expression};
@@ -14,15 +14,17 @@ use super::names::{
     expression_to_ident, into_selected_name, parse_association_list, parse_name_initial_token,
     parse_selected_name,
 };
+use super::psl::parse_psl_directive_known_keyword;
 use super::range::parse_discrete_range;
 use super::sequential_statement::{
     parse_assert_statement_known_keyword, parse_labeled_sequential_statements, parse_selection,
     parse_signal_assignment_right_hand, parse_target,
 };
-use super::tokens::{Kind::*, Token, TokenStream};
+use super::tokens::{Kind, Kind::*, Token, TokenStream};
 use super::waveform::{parse_delay_mechanism, parse_waveform};
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 
 /// LRM 11.2 Block statement
 pub fn parse_block_statement(
@@ -44,11 +46,14 @@ pub fn parse_block_statement(
     stream.pop_if_kind(Is)?;
     let header = parse_block_header(stream, diagnostics)?;
     let decl = parse_declarative_part(stream, diagnostics, true)?;
-    let statements = parse_labeled_concurrent_statements(stream, diagnostics)?;
-    stream.expect_kind(Block)?;
-    // @TODO check name
-    stream.pop_if_kind(Identifier)?;
-    stream.expect_kind(SemiColon)?;
+    let (statements, end_token) =
+        parse_labeled_concurrent_statements_end_token(stream, diagnostics)?;
+    try_token_kind!(end_token, End => {});
+    if expect_end_kind(stream, &end_token, Block, diagnostics)? {
+        // @TODO check name
+        stream.pop_if_kind(Identifier)?;
+        stream.expect_kind(SemiColon)?;
+    }
     Ok(BlockStatement {
         guard_condition,
         header,
@@ -165,6 +170,15 @@ pub fn parse_process_statement(
                 let token = stream.expect()?;
 
                 if token.kind == All {
+                    if stream.standard() < VHDLStandard::VHDL2008 {
+                        diagnostics.push(Diagnostic::error(
+                            &token,
+                            format!(
+                                "Keyword 'all' in a process sensitivity list is not allowed in {}",
+                                stream.standard()
+                            ),
+                        ));
+                    }
                     stream.expect_kind(RightPar)?;
                     Some(SensitivityList::All)
                 } else {
@@ -205,7 +219,14 @@ pub fn parse_process_statement(
             ));
         }
     }
-    stream.expect_kind(Process)?;
+    if !expect_end_kind(stream, &end_token, Process, diagnostics)? {
+        return Ok(ProcessStatement {
+            postponed,
+            sensitivity_list,
+            decl,
+            statements,
+        });
+    }
     if let Ok(Some(token)) = stream.pop_if_kind(Identifier) {
         if let Some(label) = label {
             use crate::syntax::tokens::Value::Identifier;
@@ -263,11 +284,9 @@ fn to_procedure_call(
 fn parse_assignment_known_target(
     stream: &mut TokenStream,
     target: WithPos<Target>,
+    postponed: bool,
 ) -> ParseResult<ConcurrentStatement> {
-    // @TODO postponed
-    let postponed = false;
-    // @TODO guarded
-    let guarded = false;
+    let guarded = stream.skip_if_kind(Guarded)?;
     let delay_mechanism = parse_delay_mechanism(stream)?;
     Ok(ConcurrentStatement::Assignment(
         ConcurrentSignalAssignment {
@@ -284,14 +303,15 @@ fn parse_assignment_or_procedure_call(
     stream: &mut TokenStream,
     token: &Token,
     target: WithPos<Target>,
+    postponed: bool,
 ) -> ParseResult<ConcurrentStatement> {
     match_token_kind!(
     token,
     LTE => {
-        parse_assignment_known_target(stream, target)
+        parse_assignment_known_target(stream, target, postponed)
     },
     SemiColon => {
-        Ok(ConcurrentStatement::ProcedureCall(to_procedure_call(target, false)?))
+        Ok(ConcurrentStatement::ProcedureCall(to_procedure_call(target, postponed)?))
     })
 }
 
@@ -303,8 +323,7 @@ fn parse_selected_signal_assignment(
     stream.expect_kind(Select)?;
     let target = parse_target(stream)?;
     stream.expect_kind(LTE)?;
-    // @TODO guarded
-    let guarded = false;
+    let guarded = stream.skip_if_kind(Guarded)?;
     let delay_mechanism = parse_delay_mechanism(stream)?;
     let rhs = AssignmentRightHand::Selected(parse_selection(stream, expression, parse_waveform)?);
     Ok(ConcurrentSignalAssignment {
@@ -326,6 +345,22 @@ pub fn parse_concurrent_assert_statement(
     })
 }
 
+/// An assert is a PSL assertion when its condition is not a VHDL expression
+/// followed by the remainder of an assert statement
+fn is_psl_assert(stream: &mut TokenStream) -> ParseResult<bool> {
+    if stream.standard() < VHDLStandard::VHDL2008 {
+        return Ok(false);
+    }
+
+    let state = stream.state();
+    let is_psl = match parse_expression(stream) {
+        Ok(_) => !matches!(stream.peek_kind()?, Some(Report | Severity | SemiColon)),
+        Err(_) => true,
+    };
+    stream.set_state(state);
+    Ok(is_psl)
+}
+
 #[allow(clippy::type_complexity)]
 pub fn parse_generic_and_port_map(
     stream: &mut TokenStream,
@@ -608,17 +643,31 @@ pub fn parse_concurrent_statement(
             For => ConcurrentStatement::ForGenerate(parse_for_generate_statement(stream, diagnostics)?),
             If => ConcurrentStatement::IfGenerate(parse_if_generate_statement(stream, diagnostics)?),
             Case => ConcurrentStatement::CaseGenerate(parse_case_generate_statement(stream, diagnostics)?),
-            Assert => ConcurrentStatement::Assert(parse_concurrent_assert_statement(stream, false)?),
+            Assert => {
+                if is_psl_assert(stream)? {
+                    ConcurrentStatement::Psl(parse_psl_directive_known_keyword(stream, PslDirectiveKind::Assert)?)
+                } else {
+                    ConcurrentStatement::Assert(parse_concurrent_assert_statement(stream, false)?)
+                }
+            },
+            Assume => ConcurrentStatement::Psl(parse_psl_directive_known_keyword(stream, PslDirectiveKind::Assume)?),
+            Restrict => ConcurrentStatement::Psl(parse_psl_directive_known_keyword(stream, PslDirectiveKind::Restrict)?),
+            Cover => ConcurrentStatement::Psl(parse_psl_directive_known_keyword(stream, PslDirectiveKind::Cover)?),
             Postponed => {
                 let token = stream.expect()?;
                 match token.kind {
                     Process => ConcurrentStatement::Process(parse_process_statement(stream, true, label, diagnostics)?),
                     Assert => ConcurrentStatement::Assert(parse_concurrent_assert_statement(stream, true)?),
                     With => ConcurrentStatement::Assignment(parse_selected_signal_assignment(stream, true)?),
+                    LeftPar => {
+                        let target = parse_aggregate_leftpar_known(stream)?.map_into(Target::Aggregate);
+                        let token = stream.expect()?;
+                        parse_assignment_or_procedure_call(stream, &token, target, true)?
+                    }
                     _ => {
                         let target = parse_name_initial_token(stream, token)?.map_into(Target::Name);
-                        stream.expect_kind(SemiColon)?;
-                        ConcurrentStatement::ProcedureCall(to_procedure_call(target, true)?)
+                        let token = stream.expect()?;
+                        parse_assignment_or_procedure_call(stream, &token, target, true)?
                     }
                 }
             },
@@ -633,19 +682,19 @@ pub fn parse_concurrent_statement(
                     }
                     _ => {
                         stream.move_after(&token);
-                        parse_assignment_or_procedure_call(stream, &token, name.map_into(Target::Name))?
+                        parse_assignment_or_procedure_call(stream, &token, name.map_into(Target::Name), false)?
                     }
                 }
             },
             LtLt => {
                 let name = parse_name_initial_token(stream, token)?;
                 stream.expect_kind(LTE)?;
-                parse_assignment_known_target(stream, name.map_into(Target::Name))?
+                parse_assignment_known_target(stream, name.map_into(Target::Name), false)?
             },
             LeftPar => {
                 let target = parse_aggregate_leftpar_known(stream)?.map_into(Target::Aggregate);
                 let token = stream.expect()?;
-                parse_assignment_or_procedure_call(stream, &token, target)?
+                parse_assignment_or_procedure_call(stream, &token, target, false)?
             }
         )
     };
@@ -656,19 +705,25 @@ pub fn parse_labeled_concurrent_statements_end_token(
     stream: &mut TokenStream,
     diagnostics: &mut dyn DiagnosticHandler,
 ) -> ParseResult<(Vec<LabeledConcurrentStatement>, Token)> {
+    fn is_end(kind: Kind) -> bool {
+        matches!(kind, End | Elsif | Else | When)
+    }
+
     let mut statements = Vec::new();
     loop {
+        let state = stream.state();
         let token = stream.expect()?;
-        match token.kind {
-            End | Elsif | Else | When => {
-                break Ok((statements, token));
-            }
-            _ => {
-                statements.push(parse_labeled_concurrent_statement_initial_token(
-                    stream,
-                    token,
-                    diagnostics,
-                )?);
+        if is_end(token.kind) {
+            break Ok((statements, token));
+        }
+
+        match parse_labeled_concurrent_statement_initial_token(stream, token, diagnostics) {
+            Ok(statement) => statements.push(statement),
+            Err(diagnostic) => {
+                // Continue with the next statement to not lose the rest of the statements
+                let recovered = stream.skip_statement(state, &diagnostic, is_end);
+                diagnostics.push(diagnostic);
+                recovered?;
             }
         }
     }
@@ -697,7 +752,7 @@ pub fn parse_labeled_concurrent_statement_initial_token(
             Ok(LabeledConcurrentStatement { label, statement })
         } else {
             let target = name.map_into(Target::Name);
-            let statement = parse_assignment_or_procedure_call(stream, &token, target)?;
+            let statement = parse_assignment_or_procedure_call(stream, &token, target, false)?;
             Ok(LabeledConcurrentStatement {
                 label: None,
                 statement,
@@ -970,6 +1025,156 @@ end process name;
         assert_eq!(stmt.statement, ConcurrentStatement::Process(process));
     }
 
+    #[test]
+    fn recovers_from_error_in_sequential_statement() {
+        let code = Code::new(
+            "\
+process
+begin
+  a := 1;
+  b := ;
+  c := 2;
+end process;
+",
+        );
+        let process = ProcessStatement {
+            postponed: false,
+            sensitivity_list: None,
+            decl: vec![],
+            statements: vec![
+                code.s1("a := 1;").sequential_statement(),
+                code.s1("c := 2;").sequential_statement(),
+            ],
+        };
+        let (stmt, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s("b := ;", 1).s1(";"),
+                "Expected {expression}"
+            )]
+        );
+        assert_eq!(stmt.statement, ConcurrentStatement::Process(process));
+    }
+
+    #[test]
+    fn recovers_from_missing_semicolon_before_end() {
+        let code = Code::new(
+            "\
+process
+begin
+  a := 1;
+  b := 2
+end process;
+",
+        );
+        let process = ProcessStatement {
+            postponed: false,
+            sensitivity_list: None,
+            decl: vec![],
+            statements: vec![code.s1("a := 1;").sequential_statement()],
+        };
+        let (stmt, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(code.s1("end"), "Expected 'when' or ';'")]
+        );
+        assert_eq!(stmt.statement, ConcurrentStatement::Process(process));
+    }
+
+    #[test]
+    fn recovers_from_error_in_compound_statement_header() {
+        let code = Code::new(
+            "\
+process
+begin
+  if a = then
+    if b then
+      c := 1;
+    end if;
+  end if;
+  d := 2;
+end process;
+",
+        );
+        let process = ProcessStatement {
+            postponed: false,
+            sensitivity_list: None,
+            decl: vec![],
+            statements: vec![code.s1("d := 2;").sequential_statement()],
+        };
+        let (stmt, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(code.s1("then"), "Expected {expression}")]
+        );
+        assert_eq!(stmt.statement, ConcurrentStatement::Process(process));
+    }
+
+    #[test]
+    fn missing_end_if_is_ended_by_end_process() {
+        let code = Code::new(
+            "\
+process
+begin
+  if a then
+    b := 1;
+end process;
+",
+        );
+        let (stmt, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(code.s1("end"), "Missing 'end if'")]
+        );
+        assert_eq!(
+            stmt.statement,
+            ConcurrentStatement::Process(ProcessStatement {
+                postponed: false,
+                sensitivity_list: None,
+                decl: vec![],
+                statements: vec![LabeledSequentialStatement {
+                    label: None,
+                    statement: SequentialStatement::If(IfStatement {
+                        conditionals: vec![Conditional {
+                            condition: code.s1("a").expr(),
+                            item: vec![code.s1("b := 1;").sequential_statement()],
+                        }],
+                        else_item: None,
+                    }),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn recovers_from_error_in_concurrent_statement() {
+        let code = Code::new(
+            "\
+blk : block
+begin
+  a <= 1;
+  proc : process (clk,
+  begin
+  end process;
+  b <= 2;
+end block;
+",
+        );
+        let (stmt, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(diagnostics.len(), 1);
+        match stmt.statement {
+            ConcurrentStatement::Block(block) => assert_eq!(
+                block.statements,
+                vec![
+                    code.s1("a <= 1;").concurrent_statement(),
+                    code.s1("b <= 2;").concurrent_statement(),
+                ]
+            ),
+            _ => panic!("Expected block"),
+        }
+    }
+
     #[test]
     fn test_postponed_process_statement() {
         let code = Code::new(
@@ -1123,6 +1328,32 @@ end process bar;
         );
     }
 
+    #[test]
+    fn test_process_statement_all_before_vhdl2008() {
+        let code = Code::new_with_standard(
+            "\
+process (all) is
+begin
+end process;
+",
+            VHDLStandard::VHDL2002,
+        );
+        let (stmt, diagnostics) = code.with_stream_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("all"),
+                "Keyword 'all' in a process sensitivity list is not allowed in VHDL-2002"
+            )]
+        );
+        match stmt.statement {
+            ConcurrentStatement::Process(process) => {
+                assert_eq!(process.sensitivity_list, Some(SensitivityList::All));
+            }
+            _ => panic!("Expected process statement"),
+        }
+    }
+
     #[test]
     fn test_process_statement_full() {
         let code = Code::new(
@@ -1169,6 +1400,57 @@ assert cond = true;
         assert_eq!(stmt.statement, ConcurrentStatement::Assert(assert));
     }
 
+    #[test]
+    fn test_psl_assert() {
+        let code = Code::new(
+            "\
+a0: assert always req -> next ack report \"no ack\";
+",
+        );
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(stmt.label, Some(code.s1("a0").ident()));
+        match stmt.statement {
+            ConcurrentStatement::Psl(directive) => {
+                assert_eq!(directive.kind, PslDirectiveKind::Assert);
+                assert_eq!(
+                    directive.expression.pos,
+                    code.s1("always req -> next ack").pos()
+                );
+                assert_eq!(directive.report, Some(code.s1("\"no ack\"").expr()));
+            }
+            statement => panic!("Expected PSL directive, got {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_psl_cover() {
+        let code = Code::new(
+            "\
+cover {req; ack};
+",
+        );
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        match stmt.statement {
+            ConcurrentStatement::Psl(directive) => {
+                assert_eq!(directive.kind, PslDirectiveKind::Cover);
+                assert_eq!(directive.expression.pos, code.s1("{req; ack}").pos());
+            }
+            statement => panic!("Expected PSL directive, got {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_assert_is_not_psl_before_vhdl2008() {
+        let code = Code::new_with_standard(
+            "\
+assert cond = true;
+",
+            VHDLStandard::VHDL1993,
+        );
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert!(matches!(stmt.statement, ConcurrentStatement::Assert(..)));
+    }
+
     #[test]
     fn test_postponed_concurrent_assert() {
         let code = Code::new(
@@ -1260,6 +1542,73 @@ with x(0) + 1 select
         );
     }
 
+    #[test]
+    fn test_guarded_concurrent_signal_assignment() {
+        let code = Code::new(
+            "\
+foo <= guarded transport bar after 2 ns;
+",
+        );
+        let assign = ConcurrentSignalAssignment {
+            postponed: false,
+            guarded: true,
+            target: code.s1("foo").name().map_into(Target::Name),
+            delay_mechanism: Some(DelayMechanism::Transport),
+            rhs: AssignmentRightHand::Simple(code.s1("bar after 2 ns").waveform()),
+        };
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(stmt.label, None);
+        assert_eq!(stmt.statement, ConcurrentStatement::Assignment(assign));
+    }
+
+    #[test]
+    fn test_postponed_concurrent_signal_assignment() {
+        let code = Code::new(
+            "\
+lbl: postponed foo <= guarded bar;
+",
+        );
+        let assign = ConcurrentSignalAssignment {
+            postponed: true,
+            guarded: true,
+            target: code.s1("foo").name().map_into(Target::Name),
+            delay_mechanism: None,
+            rhs: AssignmentRightHand::Simple(code.s1("bar").waveform()),
+        };
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(stmt.label, Some(code.s1("lbl").ident()));
+        assert_eq!(stmt.statement, ConcurrentStatement::Assignment(assign));
+    }
+
+    #[test]
+    fn test_postponed_selected_guarded_signal_assignment() {
+        let code = Code::new(
+            "\
+postponed with sel select
+   foo <= guarded bar when others;",
+        );
+
+        let selection = Selection {
+            expression: code.s1("sel").expr(),
+            alternatives: vec![Alternative {
+                choices: code.s1("others").choices(),
+                item: code.s1("bar").waveform(),
+            }],
+        };
+
+        let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
+        assert_eq!(
+            stmt.statement,
+            ConcurrentStatement::Assignment(ConcurrentSignalAssignment {
+                postponed: true,
+                guarded: true,
+                target: code.s1("foo").name().map_into(Target::Name),
+                delay_mechanism: None,
+                rhs: AssignmentRightHand::Selected(selection)
+            })
+        );
+    }
+
     #[test]
     fn test_component_instantiation() {
         let code = Code::new(
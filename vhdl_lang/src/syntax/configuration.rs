@@ -4,12 +4,15 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
+use super::attributes::parse_attribute;
 use super::common::error_on_end_identifier_mismatch;
 use super::common::ParseResult;
 use super::concurrent_statement::parse_generic_and_port_map;
-use super::context::parse_use_clause_no_keyword;
+use super::context::{parse_use_clause, parse_use_clause_no_keyword};
+use super::group_declaration::parse_group;
 use super::names::{parse_name, parse_name_initial_token, parse_selected_name};
-use super::tokens::{Kind::*, TokenStream};
+use super::tokens::{Kind, Kind::*, TokenStream};
+use crate::ast;
 use crate::ast::*;
 use crate::data::*;
 
@@ -147,15 +150,15 @@ fn parse_component_specification_or_name(
                     let ident = to_simple_name(name)?;
                     let component_name = parse_selected_name(stream)?;
                     Ok(ComponentSpecificationOrName::ComponentSpec(ComponentSpecification {
-                        instantiation_list: InstantiationList::Labels(vec![ident]),
+                        instantiation_list: InstantiationList::Labels(vec![WithRef::new(ident)]),
                         component_name,
                     }))
                 }
                 Comma => {
                     stream.move_after(&sep_token);
-                    let mut idents = vec![to_simple_name(name)?];
+                    let mut idents = vec![WithRef::new(to_simple_name(name)?)];
                     loop {
-                        idents.push(stream.expect_ident()?);
+                        idents.push(WithRef::new(stream.expect_ident()?));
                         let next_token = stream.expect()?;
                         try_token_kind!(
                             next_token,
@@ -197,8 +200,10 @@ fn parse_block_configuration_known_name(
     diagnostics: &mut dyn DiagnosticHandler,
 ) -> ParseResult<BlockConfiguration> {
     let block_spec = name;
-    // @TODO use clauses
-    let use_clauses = Vec::new();
+    let mut use_clauses = Vec::new();
+    while stream.peek_kind()? == Some(Use) {
+        use_clauses.push(parse_use_clause(stream)?);
+    }
     let mut items = Vec::new();
 
     loop {
@@ -288,6 +293,29 @@ pub fn parse_configuration_declaration(
                     parse_use_clause_no_keyword(token, stream)?,
                 ));
             }
+            Kind::Attribute => {
+                for attr in parse_attribute(stream)? {
+                    match attr {
+                        ast::Attribute::Specification(spec) => {
+                            decl.push(ConfigurationDeclarativeItem::Attribute(spec))
+                        }
+                        ast::Attribute::Declaration(attr_decl) => {
+                            diagnostics.push(Diagnostic::error(
+                                &attr_decl.ident,
+                                "Attribute declaration not allowed in configuration declaration",
+                            ))
+                        }
+                    }
+                }
+            }
+            Group => match parse_group(stream)? {
+                Declaration::Group(group) => decl.push(ConfigurationDeclarativeItem::Group(group)),
+                Declaration::GroupTemplate(template) => diagnostics.push(Diagnostic::error(
+                    &template.ident,
+                    "Group template declaration not allowed in configuration declaration",
+                )),
+                _ => unreachable!(),
+            },
             _ => break Vec::new(),
         }
     };
@@ -438,6 +466,119 @@ end configuration cfg;
         );
     }
 
+    #[test]
+    fn configuration_attribute_specification_and_group() {
+        let code = Code::new(
+            "\
+configuration cfg of entity_name is
+  attribute attr of grp : group is 0;
+  group grp : path (sig);
+  for rtl(0)
+  end for;
+end configuration cfg;
+",
+        );
+        let spec = match code
+            .s1("attribute attr of grp : group is 0;")
+            .declarative_part()
+            .remove(0)
+        {
+            Declaration::Attribute(ast::Attribute::Specification(spec)) => spec,
+            decl => panic!("Expected attribute specification, got {:?}", decl),
+        };
+        let group = match code
+            .s1("group grp : path (sig);")
+            .declarative_part()
+            .remove(0)
+        {
+            Declaration::Group(group) => group,
+            decl => panic!("Expected group declaration, got {:?}", decl),
+        };
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_configuration_declaration),
+            ConfigurationDeclaration {
+                context_clause: ContextClause::default(),
+                ident: code.s1("cfg").ident(),
+                entity_name: code.s1("entity_name").selected_name(),
+                decl: vec![
+                    ConfigurationDeclarativeItem::Attribute(spec),
+                    ConfigurationDeclarativeItem::Group(group)
+                ],
+                vunit_bind_inds: Vec::new(),
+                block_config: BlockConfiguration {
+                    block_spec: code.s1("rtl(0)").name(),
+                    use_clauses: vec![],
+                    items: vec![],
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn configuration_group_template_not_allowed() {
+        let code = Code::new(
+            "\
+configuration cfg of entity_name is
+  group path is (signal <>);
+  for rtl(0)
+  end for;
+end configuration cfg;
+",
+        );
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_configuration_declaration);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("path"),
+                "Group template declaration not allowed in configuration declaration"
+            )]
+        );
+    }
+
+    #[test]
+    fn configuration_block_configuration_use_clause() {
+        let code = Code::new(
+            "\
+configuration cfg of entity_name is
+  for rtl
+    use lib.foo.all;
+    use lib2.bar;
+    for inst : comp
+    end for;
+  end for;
+end configuration cfg;
+",
+        );
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_configuration_declaration),
+            ConfigurationDeclaration {
+                context_clause: ContextClause::default(),
+                ident: code.s1("cfg").ident(),
+                entity_name: code.s1("entity_name").selected_name(),
+                decl: vec![],
+                vunit_bind_inds: Vec::new(),
+                block_config: BlockConfiguration {
+                    block_spec: code.s1("rtl").name(),
+                    use_clauses: vec![
+                        code.s1("use lib.foo.all;").use_clause(),
+                        code.s1("use lib2.bar;").use_clause()
+                    ],
+                    items: vec![ConfigurationItem::Component(ComponentConfiguration {
+                        spec: ComponentSpecification {
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
+                            component_name: code.s1("comp").selected_name()
+                        },
+                        bind_ind: None,
+                        vunit_bind_inds: Vec::new(),
+                        block_config: None,
+                    })],
+                }
+            }
+        );
+    }
+
     #[test]
     fn configuration_vunit_binding_indication() {
         let code = Code::new(
@@ -567,9 +708,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             component_name: code.s1("lib.pkg.comp").selected_name()
                         },
                         bind_ind: None,
@@ -614,9 +755,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             component_name: code.s1("lib.pkg.comp").selected_name()
                         },
                         bind_ind: Some(BindingIndication {
@@ -667,9 +808,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             component_name: code.s1("lib.pkg.comp").selected_name()
                         },
                         bind_ind: Some(BindingIndication {
@@ -720,9 +861,9 @@ end configuration cfg;
                     items: vec![
                         ConfigurationItem::Component(ComponentConfiguration {
                             spec: ComponentSpecification {
-                                instantiation_list: InstantiationList::Labels(vec![code
-                                    .s1("inst")
-                                    .ident()]),
+                                instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                    code.s1("inst").ident()
+                                )]),
                                 component_name: code.s1("lib.pkg.comp").selected_name()
                             },
                             bind_ind: None,
@@ -732,9 +873,9 @@ end configuration cfg;
                         ConfigurationItem::Component(ComponentConfiguration {
                             spec: ComponentSpecification {
                                 instantiation_list: InstantiationList::Labels(vec![
-                                    code.s1("inst1").ident(),
-                                    code.s1("inst2").ident(),
-                                    code.s1("inst3").ident()
+                                    WithRef::new(code.s1("inst1").ident()),
+                                    WithRef::new(code.s1("inst2").ident()),
+                                    WithRef::new(code.s1("inst3").ident())
                                 ]),
                                 component_name: code.s1("lib2.pkg.comp").selected_name()
                             },
@@ -10,13 +10,18 @@ use super::common::ParseResult;
 use super::component_declaration::parse_component_declaration;
 use super::configuration::parse_configuration_specification;
 use super::context::parse_use_clause;
+use super::disconnection::parse_disconnection_specification;
+use super::group_declaration::parse_group;
 use super::names::{parse_association_list, parse_selected_name};
 use super::object_declaration::{parse_file_declaration, parse_object_declaration};
+use super::psl::{parse_default_clock, parse_psl_declaration};
 use super::subprogram::parse_subprogram;
 use super::tokens::{Kind::*, *};
 use super::type_declaration::parse_type_declaration;
+use super::view_declaration::parse_mode_view_declaration;
 use crate::ast::{ContextClause, Declaration, PackageInstantiation};
 use crate::data::DiagnosticHandler;
+use crate::standard::VHDLStandard;
 
 pub fn parse_package_instantiation(stream: &mut TokenStream) -> ParseResult<PackageInstantiation> {
     stream.expect_kind(Package)?;
@@ -50,7 +55,8 @@ pub fn is_declarative_part(stream: &mut TokenStream, begin_is_end: bool) -> Pars
 fn check_declarative_part(token: &Token, may_end: bool, may_begin: bool) -> ParseResult<()> {
     match token.kind {
         Use | Type | Subtype | Shared | Constant | Signal | Variable | File | Component
-        | Attribute | Alias | Impure | Pure | Function | Procedure | Package | For => Ok(()),
+        | Attribute | Alias | Impure | Pure | Function | Procedure | Package | For | View
+        | Group | Disconnect | Property | Sequence | Default => Ok(()),
         Begin if may_begin => Ok(()),
         End if may_end => Ok(()),
         _ => {
@@ -75,96 +81,121 @@ pub fn parse_declarative_part(
     Ok(decl)
 }
 
-pub fn parse_declarative_part_leave_end_token(
+/// Declarations may start with these tokens
+pub fn is_declaration_start(kind: Kind) -> bool {
+    matches!(
+        kind,
+        Type | Subtype
+            | Component
+            | Impure
+            | Pure
+            | Function
+            | Procedure
+            | Package
+            | For
+            | File
+            | Shared
+            | Constant
+            | Signal
+            | Variable
+            | Attribute
+            | Use
+            | Alias
+            | View
+            | Group
+            | Disconnect
+            | Property
+            | Sequence
+            | Default
+    )
+}
+
+/// Parse a single declaration starting at the next token
+/// Errors which can be recovered from are pushed to the diagnostics
+pub fn parse_declaration(
     stream: &mut TokenStream,
     diagnostics: &mut dyn DiagnosticHandler,
-) -> ParseResult<Vec<Declaration>> {
-    let mut declarations: Vec<Declaration> = Vec::new();
-
-    fn is_recover_token(kind: Kind) -> bool {
-        matches!(
-            kind,
-            Type | Subtype
-                | Component
-                | Impure
-                | Pure
-                | Function
-                | Procedure
-                | Package
-                | For
-                | File
-                | Shared
-                | Constant
-                | Signal
-                | Variable
-                | Attribute
-                | Use
-                | Alias
-        )
-    }
+    declarations: &mut Vec<Declaration>,
+) -> ParseResult<()> {
+    let token = stream.peek_expect()?;
+    match token.kind {
+        Type | Subtype | Component | Impure | Pure | Function | Procedure | Package | For
+        | Group | Property | Sequence | Default => {
+            let decl = match token.kind {
+                Type | Subtype => {
+                    parse_type_declaration(stream, diagnostics).map(Declaration::Type)?
+                }
+                Component => {
+                    parse_component_declaration(stream, diagnostics).map(Declaration::Component)?
+                }
+                Impure | Pure | Function | Procedure => parse_subprogram(stream, diagnostics)?,
+                Package => parse_package_instantiation(stream).map(Declaration::Package)?,
+                For => parse_configuration_specification(stream).map(Declaration::Configuration)?,
+                Group => parse_group(stream)?,
+                Property | Sequence => parse_psl_declaration(stream).map(Declaration::Psl)?,
+                Default => parse_default_clock(stream).map(Declaration::DefaultClock)?,
+                _ => unreachable!(),
+            };
+            declarations.push(decl);
+        }
 
-    while let Some(token) = stream.peek()? {
-        match token.kind {
-            Begin | End => break,
-            Type | Subtype | Component | Impure | Pure | Function | Procedure | Package | For => {
-                let decl = match token.kind {
-                    Type | Subtype => {
-                        parse_type_declaration(stream, diagnostics).map(Declaration::Type)?
-                    }
-                    Component => parse_component_declaration(stream, diagnostics)
-                        .map(Declaration::Component)?,
-                    Impure | Pure | Function | Procedure => parse_subprogram(stream, diagnostics)?,
-                    Package => parse_package_instantiation(stream).map(Declaration::Package)?,
-                    For => {
-                        parse_configuration_specification(stream).map(Declaration::Configuration)?
-                    }
-                    _ => unreachable!(),
-                };
-                declarations.push(decl);
+        File | Shared | Constant | Signal | Variable | Attribute => {
+            let decls: ParseResult<Vec<Declaration>> = match token.kind {
+                File => parse_file_declaration(stream)
+                    .map(|decls| decls.into_iter().map(Declaration::File).collect()),
+                Shared | Constant | Signal | Variable => parse_object_declaration(stream)
+                    .map(|decls| decls.into_iter().map(Declaration::Object).collect()),
+                Attribute => parse_attribute(stream)
+                    .map(|decls| decls.into_iter().map(Declaration::Attribute).collect()),
+                _ => unreachable!(),
+            };
+            match decls.or_recover_until(stream, diagnostics, is_declaration_start) {
+                Ok(ref mut decls) => declarations.append(decls),
+                Err(err) => diagnostics.push(err),
             }
+        }
 
-            File | Shared | Constant | Signal | Variable | Attribute => {
-                let decls: ParseResult<Vec<Declaration>> = match token.kind {
-                    File => parse_file_declaration(stream)
-                        .map(|decls| decls.into_iter().map(Declaration::File).collect()),
-                    Shared | Constant | Signal | Variable => parse_object_declaration(stream)
-                        .map(|decls| decls.into_iter().map(Declaration::Object).collect()),
-                    Attribute => parse_attribute(stream)
-                        .map(|decls| decls.into_iter().map(Declaration::Attribute).collect()),
-                    _ => unreachable!(),
-                };
-                match decls.or_recover_until(stream, diagnostics, is_recover_token) {
-                    Ok(ref mut decls) => declarations.append(decls),
-                    Err(err) => {
-                        diagnostics.push(err);
-                        continue;
-                    }
+        Use | Alias | View | Disconnect => {
+            let decl: ParseResult<Declaration> = match token.kind {
+                Use => parse_use_clause(stream).map(Declaration::Use),
+                Alias => parse_alias_declaration(stream).map(Declaration::Alias),
+                Disconnect => {
+                    parse_disconnection_specification(stream).map(Declaration::Disconnection)
                 }
+                View => parse_mode_view_declaration(stream, diagnostics).map(Declaration::View),
+                _ => unreachable!(),
+            };
+            match decl.or_recover_until(stream, diagnostics, is_declaration_start) {
+                Ok(decl) => declarations.push(decl),
+                Err(err) => diagnostics.push(err),
             }
+        }
 
-            Use | Alias => {
-                let decl: ParseResult<Declaration> = match token.kind {
-                    Use => parse_use_clause(stream).map(Declaration::Use),
-                    Alias => parse_alias_declaration(stream).map(Declaration::Alias),
-                    _ => unreachable!(),
-                };
-                match decl.or_recover_until(stream, diagnostics, is_recover_token) {
-                    Ok(decl) => declarations.push(decl),
-                    Err(err) => {
-                        diagnostics.push(err);
-                        continue;
-                    }
-                }
+        _ => {
+            let mut decl_kinds = vec![
+                Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For, File,
+                Shared, Constant, Signal, Variable, Attribute, Use, Alias,
+            ];
+            if stream.standard() >= VHDLStandard::VHDL2019 {
+                decl_kinds.push(View);
             }
+            diagnostics.push(token.kinds_error(&decl_kinds));
+            stream.skip_until(is_declaration_start)?;
+        }
+    }
+    Ok(())
+}
 
-            _ => {
-                diagnostics.push(token.kinds_error(&[
-                    Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For,
-                    File, Shared, Constant, Signal, Variable, Attribute, Use, Alias,
-                ]));
-                stream.skip_until(is_recover_token)?;
-                continue;
-            }
+pub fn parse_declarative_part_leave_end_token(
+    stream: &mut TokenStream,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> ParseResult<Vec<Declaration>> {
+    let mut declarations: Vec<Declaration> = Vec::new();
+
+    while let Some(token) = stream.peek()? {
+        match token.kind {
+            Begin | End | Private => break,
+            _ => parse_declaration(stream, diagnostics, &mut declarations)?,
         }
     }
 
@@ -238,6 +269,7 @@ constant x: natural := 5;
                 class: ObjectClass::Constant,
                 ident: code.s1("x").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("5").expr())
             })])
         );
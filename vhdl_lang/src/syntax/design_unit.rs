@@ -4,7 +4,7 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::tokens::{Kind::*, TokenStream};
+use super::tokens::{Kind::*, Recover, TokenState, TokenStream};
 
 use super::common::error_on_end_identifier_mismatch;
 use super::common::ParseResult;
@@ -18,6 +18,7 @@ use super::declarative_part::{
     parse_declarative_part, parse_declarative_part_leave_end_token, parse_package_instantiation,
 };
 use super::interface_declaration::parse_generic_interface_list;
+use super::psl::parse_verification_unit;
 use crate::ast::*;
 use crate::data::*;
 
@@ -109,7 +110,14 @@ pub fn parse_package_declaration(
             None
         }
     };
-    let decl = parse_declarative_part(stream, diagnostics, false)?;
+    let decl = parse_declarative_part_leave_end_token(stream, diagnostics)?;
+    // VHDL-2019 private declarations are only visible within the package body
+    let private_decl = if stream.skip_if_kind(Private)? {
+        Some(parse_declarative_part_leave_end_token(stream, diagnostics)?)
+    } else {
+        None
+    };
+    stream.expect_kind(End).log(diagnostics);
     stream.pop_if_kind(Package)?;
     let end_ident = stream.pop_optional_ident()?;
     if let Some(diagnostic) = error_on_end_identifier_mismatch(&ident, &end_ident) {
@@ -122,6 +130,7 @@ pub fn parse_package_declaration(
         ident,
         generic_clause,
         decl,
+        private_decl,
     })
 }
 
@@ -166,103 +175,198 @@ fn context_item_message(context_item: &ContextItem, message: impl AsRef<str>) ->
     format!("{} {}", prefix, message.as_ref())
 }
 
+/// Skip to the next design unit or library clause after a syntax error in the design unit
+/// which started at the given state. Tokens which also occur within a design unit, such as
+/// the package keyword, are only assumed to start a new design unit after a semicolon.
+fn skip_to_design_unit(
+    stream: &mut TokenStream,
+    state: TokenState,
+    diagnostic: Diagnostic,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> ParseResult<()> {
+    let error_pos = diagnostic.pos.start();
+    diagnostics.push(diagnostic);
+
+    stream.set_state(state);
+    let mut last_kind = stream.pop_kind()?;
+    let mut is_first_after_error = true;
+
+    while let Some(token) = stream.peek()? {
+        if token.pos.start() >= error_pos {
+            let is_design_unit = matches!(
+                token.kind,
+                Library
+                    | Context
+                    | Entity
+                    | Architecture
+                    | Configuration
+                    | Package
+                    | Vunit
+                    | Vmode
+                    | Vprop
+            );
+
+            // Verification units end with a closing brace
+            let is_after_unit = matches!(last_kind, Some(SemiColon | RightBrace));
+            if is_design_unit && (is_first_after_error || is_after_unit) {
+                break;
+            }
+            is_first_after_error = false;
+        }
+        stream.move_after(&token);
+        last_kind = Some(token.kind);
+    }
+    Ok(())
+}
+
 pub fn parse_design_file(
     stream: &mut TokenStream,
     diagnostics: &mut dyn DiagnosticHandler,
 ) -> ParseResult<DesignFile> {
     let mut context_clause = vec![];
     let mut design_units = vec![];
+    let mut unit_ranges = vec![];
+    let mut unit_start = Position::default();
 
     while let Some(token) = stream.peek()? {
-        try_token_kind!(
-            token,
-            Library => {
-                match parse_library_clause(stream) {
-                    Ok(library) => {
-                        context_clause.push(library.map_into(ContextItem::Library));
-                    },
-                    Err(diagnostic) => diagnostics.push(diagnostic),
+        let state = stream.state();
+        match token.kind {
+            Library => match parse_library_clause(stream) {
+                Ok(library) => {
+                    context_clause.push(library.map_into(ContextItem::Library));
                 }
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
             },
-            Use => {
-                match parse_use_clause(stream) {
-                    Ok(use_clause) => {
-                        context_clause.push(use_clause.map_into(ContextItem::Use));
-                    },
-                    Err(diagnostic) => diagnostics.push(diagnostic),
+            Use => match parse_use_clause(stream) {
+                Ok(use_clause) => {
+                    context_clause.push(use_clause.map_into(ContextItem::Use));
                 }
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
             },
             Context => match parse_context(stream, diagnostics) {
                 Ok(DeclarationOrReference::Declaration(context_decl)) => {
                     if !context_clause.is_empty() {
-                        let mut diagnostic = Diagnostic::error(&context_decl.ident, "Context declaration may not be preceeded by a context clause");
+                        let mut diagnostic = Diagnostic::error(
+                            &context_decl.ident,
+                            "Context declaration may not be preceeded by a context clause",
+                        );
 
                         for context_item in context_clause.iter() {
-                            diagnostic.add_related(&context_item, context_item_message(&context_item.item, "may not come before context declaration"));
+                            diagnostic.add_related(
+                                &context_item,
+                                context_item_message(
+                                    &context_item.item,
+                                    "may not come before context declaration",
+                                ),
+                            );
                         }
 
                         diagnostics.push(diagnostic);
                         context_clause.clear();
                     }
 
-                    design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::Context(context_decl)));
+                    design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::Context(
+                        context_decl,
+                    )));
                 }
                 Ok(DeclarationOrReference::Reference(context_ref)) => {
                     context_clause.push(context_ref.map_into(ContextItem::Context));
                 }
-                Err(diagnostic) => diagnostics.push(diagnostic),
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
             },
             Entity => match parse_entity_declaration(stream, diagnostics) {
                 Ok(mut entity) => {
                     entity.context_clause = take_context_clause(&mut context_clause);
                     design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(entity)));
                 }
-                Err(diagnostic) => diagnostics.push(diagnostic),
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
             },
 
             Architecture => match parse_architecture_body(stream, diagnostics) {
                 Ok(mut architecture) => {
                     architecture.context_clause = take_context_clause(&mut context_clause);
-                    design_units.push(AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture)));
+                    design_units.push(AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(
+                        architecture,
+                    )));
                 }
-                Err(diagnostic) => diagnostics.push(diagnostic),
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
             },
 
             Configuration => match parse_configuration_declaration(stream, diagnostics) {
                 Ok(mut configuration) => {
                     configuration.context_clause = take_context_clause(&mut context_clause);
-                    design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(configuration)));
+                    design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(
+                        configuration,
+                    )));
                 }
-                Err(diagnostic) => diagnostics.push(diagnostic),
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
             },
             Package => {
                 if stream.next_kinds_are(&[Package, Body])? {
                     match parse_package_body(stream, diagnostics) {
                         Ok(mut package_body) => {
                             package_body.context_clause = take_context_clause(&mut context_clause);
-                            design_units.push(AnyDesignUnit::Secondary(AnySecondaryUnit::PackageBody(package_body)));
+                            design_units.push(AnyDesignUnit::Secondary(
+                                AnySecondaryUnit::PackageBody(package_body),
+                            ));
+                        }
+                        Err(diagnostic) => {
+                            skip_to_design_unit(stream, state, diagnostic, diagnostics)?
                         }
-                        Err(diagnostic) => diagnostics.push(diagnostic),
                     };
                 } else if stream.next_kinds_are(&[Package, Identifier, Is, New])? {
                     match parse_package_instantiation(stream) {
                         Ok(mut inst) => {
                             inst.context_clause = take_context_clause(&mut context_clause);
-                            design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::PackageInstance(inst)))
-                        },
-                        Err(diagnostic) => diagnostics.push(diagnostic),
+                            design_units.push(AnyDesignUnit::Primary(
+                                AnyPrimaryUnit::PackageInstance(inst),
+                            ))
+                        }
+                        Err(diagnostic) => {
+                            skip_to_design_unit(stream, state, diagnostic, diagnostics)?
+                        }
                     }
                 } else {
                     match parse_package_declaration(stream, diagnostics) {
                         Ok(mut package) => {
                             package.context_clause = take_context_clause(&mut context_clause);
-                            design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package)))
+                            design_units
+                                .push(AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package)))
+                        }
+                        Err(diagnostic) => {
+                            skip_to_design_unit(stream, state, diagnostic, diagnostics)?
                         }
-                        Err(diagnostic) => diagnostics.push(diagnostic),
                     };
                 }
             }
-        );
+            Vunit | Vmode | Vprop => match parse_verification_unit(stream, diagnostics) {
+                Ok(mut unit) => {
+                    unit.context_clause = take_context_clause(&mut context_clause);
+                    design_units.push(AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(
+                        unit,
+                    )));
+                }
+                Err(diagnostic) => skip_to_design_unit(stream, state, diagnostic, diagnostics)?,
+            },
+            _ => {
+                let diagnostic = token.kinds_error(&[
+                    Library,
+                    Use,
+                    Context,
+                    Entity,
+                    Architecture,
+                    Configuration,
+                    Package,
+                ]);
+                skip_to_design_unit(stream, state, diagnostic, diagnostics)?;
+            }
+        }
+
+        if design_units.len() > unit_ranges.len() {
+            let unit_end = stream.state().start().pos();
+            unit_ranges.push(crate::data::Range::new(unit_start, unit_end));
+            unit_start = unit_end;
+        }
     }
 
     for context_item in context_clause {
@@ -272,7 +376,10 @@ pub fn parse_design_file(
         ));
     }
 
-    Ok(DesignFile { design_units })
+    Ok(DesignFile {
+        design_units,
+        unit_ranges,
+    })
 }
 
 #[cfg(test)]
@@ -280,6 +387,7 @@ mod tests {
     use super::*;
 
     use crate::data::Diagnostic;
+    use crate::standard::VHDLStandard;
     use crate::syntax::test::{check_diagnostics, check_no_diagnostics, Code};
 
     fn parse_str(code: &str) -> (Code, DesignFile, Vec<Diagnostic>) {
@@ -591,6 +699,7 @@ end package;
                 ident: code.s1("pkg_name").ident(),
                 generic_clause: None,
                 decl: vec![],
+                private_decl: None,
             }
         );
     }
@@ -617,6 +726,7 @@ end package;
   constant bar : natural := 0;
 ")
                     .declarative_part(),
+                private_decl: None,
             }
         );
     }
@@ -642,7 +752,32 @@ end package;
                     code.s1("type foo").generic(),
                     code.s1("type bar").generic()
                 ]),
-                decl: vec![]
+                decl: vec![],
+                private_decl: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_package_declaration_private_region() {
+        let code = Code::new_with_standard(
+            "
+package pkg_name is
+  constant foo : natural;
+private
+  constant bar : natural := 0;
+end package;
+",
+            VHDLStandard::VHDL2019,
+        );
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_package_declaration),
+            PackageDeclaration {
+                context_clause: ContextClause::default(),
+                ident: code.s1("pkg_name").ident(),
+                generic_clause: None,
+                decl: code.s1("constant foo : natural;").declarative_part(),
+                private_decl: Some(code.s1("constant bar : natural := 0;").declarative_part()),
             }
         );
     }
@@ -677,11 +812,38 @@ end entity;
                         decl: vec![],
                         statements: vec![],
                     }
-                ))]
+                ))],
+                unit_ranges: vec![crate::data::Range::new(
+                    Position::default(),
+                    code.s1("end entity;").end()
+                )],
             }
         );
     }
 
+    #[test]
+    fn unit_ranges_start_where_previous_unit_ends() {
+        let (code, design_file) = parse_ok(
+            "
+entity myent is
+end entity;
+
+-- Comment
+library lib;
+package pkg is
+end package;
+",
+        );
+        let first_end = code.s1("end entity;").end();
+        assert_eq!(
+            design_file.unit_ranges,
+            vec![
+                crate::data::Range::new(Position::default(), first_end),
+                crate::data::Range::new(first_end, code.s1("end package;").end()),
+            ]
+        );
+    }
+
     #[test]
     fn warning_on_orphan_context_clause() {
         let code = Code::new(
@@ -712,11 +874,59 @@ context lib.ctx;
         assert_eq!(
             design_file,
             DesignFile {
-                design_units: vec![]
+                design_units: vec![],
+                unit_ranges: vec![],
             }
         );
     }
 
+    #[test]
+    fn recovers_from_error_in_design_unit() {
+        let code = Code::new(
+            "
+entity ent
+  port (a : in bit);
+end entity;
+
+package pkg is
+  constant c : natural := 0;
+end package;
+
+architecture a of ent is
+begin
+end architecture
+
+library ieee;
+entity ent2 is
+end entity;
+",
+        );
+        let (design_file, diagnostics) = code.with_stream_diagnostics(parse_design_file);
+        check_diagnostics(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("port"), "Expected 'is'"),
+                Diagnostic::error(code.s1("library"), "Expected ';'"),
+            ],
+        );
+
+        let names: Vec<_> = design_file
+            .design_units
+            .iter()
+            .map(|unit| match unit {
+                AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package)) => {
+                    package.ident.item.name().to_string()
+                }
+                AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(entity)) => {
+                    assert_eq!(entity.context_clause.len(), 1);
+                    entity.ident.item.name().to_string()
+                }
+                _ => panic!("Unexpected design unit"),
+            })
+            .collect();
+        assert_eq!(names, vec!["pkg", "ent2"]);
+    }
+
     #[test]
     fn error_on_context_clause_before_context_declaration() {
         let code = Code::new(
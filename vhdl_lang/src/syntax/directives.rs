@@ -0,0 +1,837 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Tool directives (LRM 16.11)
+//!
+//! `identifier ...
+//!   A tool directive which ends at the end of the line, unknown directives are ignored
+//!
+//! `if condition then, `elsif condition then, `else, `end [if]
+//!   Conditional analysis directives which were added in VHDL-2019
+//!   The code of inactive branches is skipped by the parser
+//!
+//! `warning "message", `error "message"
+//!   Report a diagnostic when analysis reaches the directive
+//!
+//! `protect begin_protected ... `protect end_protected
+//!   An IEEE 1735 protected envelope whose encrypted contents are skipped by the parser
+//!   Envelopes are recognized in all revisions of the standard since vendor IP is often VHDL-93
+
+use super::tokens::Kind::{
+    And, Else, Elsif, End, GraveAccent, Identifier, If, LeftPar, Not, Or, RightPar, Then, Xnor,
+    Xor, EQ, GT, GTE, LT, LTE, NE,
+};
+use super::tokens::{kind_str, Kind, SkippedRange, Token, TokenState, TokenStream, Tokenizer};
+use crate::data::*;
+use crate::standard::VHDLStandard;
+use fnv::FnvHashMap;
+
+/// The tool directives of a single source file
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Directives {
+    // Regions which are excluded by conditional analysis
+    inactive: Vec<Range>,
+}
+
+impl Directives {
+    /// The regions which are excluded by conditional analysis
+    pub fn inactive_ranges(&self) -> &[Range] {
+        &self.inactive
+    }
+}
+
+/// The value of a predefined conditional analysis identifier
+fn predefined_identifier(name: &str, standard: VHDLStandard) -> Option<String> {
+    let value = match name {
+        "VHDL_VERSION" => match standard {
+            VHDLStandard::VHDL1993 => "1993",
+            VHDLStandard::VHDL2002 => "2002",
+            VHDLStandard::VHDL2008 => "2008",
+            VHDLStandard::VHDL2019 => "2019",
+        },
+        "TOOL_NAME" => "vhdl_ls",
+        "TOOL_VERSION" => env!("CARGO_PKG_VERSION"),
+        "TOOL_TYPE" | "TOOL_VENDOR" | "TOOL_EDITION" => "",
+        _ => return None,
+    };
+    Some(value.to_owned())
+}
+
+/// An `if directive and its branches
+struct Branch {
+    pos: SrcPos,
+    // The region which contains the `if directive is active
+    enclosing_active: bool,
+    // A previous or the current branch is active
+    taken: bool,
+    active: bool,
+    has_else: bool,
+}
+
+/// Evaluates the tool directives of a source when the tokenizer reaches them
+///
+/// The evaluator reads the directives and the inactive regions after them with its own
+/// tokenizer such that the tokenizer of the parser can skip them.
+pub(crate) struct Evaluator<'a> {
+    stream: TokenStream<'a>,
+    standard: VHDLStandard,
+    identifiers: &'a FnvHashMap<String, String>,
+    branches: Vec<Branch>,
+    // The begin_protected directive of the current protected envelope
+    envelope: Option<SrcPos>,
+    // The start of the current inactive region
+    inactive_start: Option<Position>,
+    directives: Directives,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Evaluator<'a> {
+    /// The values of the conditional analysis identifiers take precedence over the predefined ones
+    pub fn new(tokenizer: Tokenizer<'a>, identifiers: &'a FnvHashMap<String, String>) -> Self {
+        Evaluator {
+            standard: tokenizer.standard(),
+            stream: TokenStream::new(tokenizer),
+            identifiers,
+            branches: Vec::new(),
+            envelope: None,
+            inactive_start: None,
+            directives: Directives::default(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Evaluate the directive starting at the grave accent and return the range to skip,
+    /// which also covers the inactive region or protected envelope after the directive
+    pub fn evaluate(&mut self, start: ReaderState) -> SkippedRange {
+        let mut diagnostics = std::mem::take(&mut self.diagnostics);
+        self.stream.set_state(TokenState::new(start));
+
+        loop {
+            match self.stream.pop() {
+                Ok(Some(token)) => {
+                    if token.kind == GraveAccent {
+                        self.directive(token, &mut diagnostics);
+                        if !self.is_skipped() {
+                            break;
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    // Tokenization errors within inactive regions are not reported
+                }
+            }
+        }
+
+        self.diagnostics = diagnostics;
+        SkippedRange {
+            start: start.pos(),
+            end: self.stream.state().start(),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.branches
+            .last()
+            .map(|branch| branch.active)
+            .unwrap_or(true)
+    }
+
+    /// Tokens are skipped in inactive regions and protected envelopes
+    fn is_skipped(&self) -> bool {
+        !self.is_active() || self.envelope.is_some()
+    }
+
+    /// Evaluate a directive whose grave accent has already been consumed
+    fn directive(&mut self, grave_accent: Token, diagnostics: &mut dyn DiagnosticHandler) {
+        let was_active = self.is_active();
+        let start = grave_accent.pos.start();
+        let line = start.line;
+
+        if let Err(err) = self.evaluate_directive(&grave_accent, diagnostics) {
+            diagnostics.push(err);
+        }
+        self.skip_line(line);
+
+        let is_active = self.is_active();
+        if was_active && !is_active {
+            self.inactive_start = Some(self.stream.state().start().pos());
+        } else if !was_active && is_active {
+            if let Some(inactive_start) = self.inactive_start.take() {
+                self.directives
+                    .inactive
+                    .push(Range::new(inactive_start, start));
+            }
+        }
+    }
+
+    fn evaluate_directive(
+        &mut self,
+        grave_accent: &Token,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> DiagnosticResult<()> {
+        let token = self.stream.peek_expect()?;
+        let name = match token.kind {
+            If | Elsif | Else | End => kind_str(token.kind).to_owned(),
+            Identifier if token.pos.start().line == grave_accent.pos.start().line => token
+                .clone()
+                .expect_ident()?
+                .item
+                .name_utf8()
+                .to_lowercase(),
+            _ => String::new(),
+        };
+
+        if name == "protect" {
+            self.stream.move_after(&token);
+            return self.protect_directive(grave_accent);
+        } else if self.envelope.is_some() {
+            // Other directives within a protected envelope are part of the encrypted contents
+            return Ok(());
+        } else if token.pos.start().line != grave_accent.pos.start().line {
+            return Err(Diagnostic::error(
+                grave_accent,
+                "Expected tool directive after '`'",
+            ));
+        }
+
+        // LRM 15.11 Tool directives were added in VHDL-2008
+        if self.standard < VHDLStandard::VHDL2008 {
+            return Err(Diagnostic::error(
+                grave_accent.pos.combine(&token),
+                format!("Tool directives are not allowed in {}", self.standard),
+            ));
+        }
+
+        if !matches!(
+            name.as_str(),
+            "if" | "elsif" | "else" | "end" | "warning" | "error"
+        ) {
+            // Other tool directives are ignored
+            return Ok(());
+        }
+
+        self.stream.move_after(&token);
+        let pos = grave_accent.pos.combine(&token);
+        if self.standard < VHDLStandard::VHDL2019 {
+            return Err(Diagnostic::error(
+                pos,
+                format!("Conditional analysis is not allowed in {}", self.standard),
+            ));
+        }
+
+        match token.kind {
+            If => {
+                let enclosing_active = self.is_active();
+                let condition = self.condition_then(enclosing_active);
+                let active = enclosing_active && *condition.as_ref().unwrap_or(&false);
+                self.branches.push(Branch {
+                    pos,
+                    enclosing_active,
+                    taken: active,
+                    active,
+                    has_else: false,
+                });
+                condition.map(|_| ())
+            }
+            Elsif => {
+                let evaluate = self
+                    .branches
+                    .last()
+                    .map(|branch| branch.enclosing_active && !branch.taken)
+                    .unwrap_or(false);
+                let condition = self.condition_then(evaluate);
+                let active = evaluate && *condition.as_ref().unwrap_or(&false);
+
+                let branch = self.last_branch(&pos, "`elsif")?;
+                if branch.has_else {
+                    return Err(Diagnostic::error(pos, "`elsif after `else"));
+                }
+                branch.active = active;
+                branch.taken |= active;
+                condition.map(|_| ())
+            }
+            Else => {
+                let branch = self.last_branch(&pos, "`else")?;
+                if branch.has_else {
+                    return Err(Diagnostic::error(pos, "Duplicate `else"));
+                }
+                branch.has_else = true;
+                branch.active = branch.enclosing_active && !branch.taken;
+                branch.taken = true;
+                Ok(())
+            }
+            End => {
+                self.last_branch(&pos, "`end")?;
+                self.branches.pop();
+                if let Ok(Some(token)) = self.stream.peek() {
+                    if token.kind == If && token.pos.start().line == pos.start().line {
+                        self.stream.move_after(&token);
+                    }
+                }
+                Ok(())
+            }
+            _ => {
+                let message = self.stream.expect()?.expect_string()?;
+                if self.is_active() {
+                    let message = message.item.to_string();
+                    if token
+                        .expect_ident()?
+                        .item
+                        .name_utf8()
+                        .eq_ignore_ascii_case("warning")
+                    {
+                        diagnostics.push(
+                            Diagnostic::warning(pos, message)
+                                .with_code(ErrorCode::WarningDirective),
+                        );
+                    } else {
+                        diagnostics.push(
+                            Diagnostic::error(pos, message).with_code(ErrorCode::ErrorDirective),
+                        );
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// IEEE 1735 protect directives, only the begin and end of envelopes are of interest
+    fn protect_directive(&mut self, grave_accent: &Token) -> DiagnosticResult<()> {
+        // Envelopes within inactive regions are skipped anyway
+        if !self.is_active() {
+            return Ok(());
+        }
+
+        let token = match self.stream.peek() {
+            Ok(Some(token))
+                if token.kind == Identifier
+                    && token.pos.start().line == grave_accent.pos.start().line =>
+            {
+                token
+            }
+            _ => return Ok(()),
+        };
+        self.stream.move_after(&token);
+        let pos = grave_accent.pos.combine(&token);
+        let keyword = token.expect_ident()?.item.name_utf8().to_lowercase();
+
+        match keyword.as_str() {
+            "begin_protected" => {
+                self.envelope.get_or_insert(pos);
+                Ok(())
+            }
+            "end_protected" => self.envelope.take().map(|_| ()).ok_or_else(|| {
+                Diagnostic::error(
+                    pos,
+                    "`protect end_protected without matching begin_protected",
+                )
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn last_branch(&mut self, pos: &SrcPos, name: &str) -> DiagnosticResult<&mut Branch> {
+        self.branches
+            .last_mut()
+            .ok_or_else(|| Diagnostic::error(pos, format!("{} without matching `if", name)))
+    }
+
+    /// Skip the remaining tokens of a directive which end at the end of the line
+    fn skip_line(&mut self, line: u32) {
+        loop {
+            match self.stream.peek() {
+                Ok(Some(token)) if token.pos.start().line == line => {
+                    self.stream.move_after(&token);
+                }
+                Err(err) if err.pos.start().line == line => {
+                    self.stream.pop().ok();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Parse a condition followed by the then keyword
+    /// The condition is only evaluated when the evaluate argument is true
+    fn condition_then(&mut self, evaluate: bool) -> DiagnosticResult<bool> {
+        let value = self.condition(evaluate)?;
+        self.stream.expect_kind(Then)?;
+        Ok(value)
+    }
+
+    /// LRM 16.11.2 conditional_analysis_expression
+    fn condition(&mut self, evaluate: bool) -> DiagnosticResult<bool> {
+        let mut value = self.relation(evaluate)?;
+        let mut operator: Option<Kind> = None;
+
+        loop {
+            let token = self.stream.peek_expect()?;
+            if !matches!(token.kind, And | Or | Xor | Xnor) {
+                return Ok(value);
+            }
+
+            if operator.map(|kind| kind != token.kind).unwrap_or(false) {
+                return Err(Diagnostic::error(
+                    &token,
+                    "Mixed logical operators must be separated by parentheses",
+                ));
+            }
+            self.stream.move_after(&token);
+            operator = Some(token.kind);
+
+            let rhs = self.relation(evaluate)?;
+            value = match token.kind {
+                And => value && rhs,
+                Or => value || rhs,
+                Xor => value != rhs,
+                _ => value == rhs,
+            };
+        }
+    }
+
+    /// LRM 16.11.2 conditional_analysis_relation
+    fn relation(&mut self, evaluate: bool) -> DiagnosticResult<bool> {
+        let token = self.stream.expect()?;
+        Ok(try_token_kind!(
+            token,
+            LeftPar => {
+                let value = self.condition(evaluate)?;
+                self.stream.expect_kind(RightPar)?;
+                value
+            },
+            Not => {
+                self.stream.expect_kind(LeftPar)?;
+                let value = self.condition(evaluate)?;
+                self.stream.expect_kind(RightPar)?;
+                !value
+            },
+            Identifier => {
+                let ident = token.expect_ident()?;
+                let operator = self.stream.expect()?;
+                let operator = try_token_kind!(
+                    operator,
+                    EQ | NE | LT | LTE | GT | GTE => operator.kind
+                );
+                let string = self.stream.expect()?.expect_string()?;
+
+                if !evaluate {
+                    return Ok(false);
+                }
+
+                let name = ident.item.name_utf8().to_uppercase();
+                let value = self
+                    .identifiers
+                    .get(&name)
+                    .cloned()
+                    .or_else(|| predefined_identifier(&name, self.standard))
+                    .ok_or_else(|| {
+                        Diagnostic::error(
+                            &ident,
+                            format!("Undefined conditional analysis identifier '{}'", ident.item),
+                        )
+                    })?;
+                let value = value.as_str();
+                let string = string.item.to_string();
+
+                match operator {
+                    EQ => value == string,
+                    NE => value != string,
+                    LT => value < string.as_str(),
+                    LTE => value <= string.as_str(),
+                    GT => value > string.as_str(),
+                    _ => value >= string.as_str(),
+                }
+            }
+        ))
+    }
+
+    /// The evaluated directives of the source, the tokenizer shall have reached the end of the source
+    pub fn finish(mut self, diagnostics: &mut dyn DiagnosticHandler) -> Directives {
+        for diagnostic in self.diagnostics.drain(..) {
+            diagnostics.push(diagnostic);
+        }
+
+        for branch in self.branches.iter() {
+            diagnostics.push(Diagnostic::error(
+                &branch.pos,
+                "Missing `end of conditional analysis",
+            ));
+        }
+
+        if let Some(ref envelope) = self.envelope {
+            diagnostics.push(Diagnostic::error(
+                envelope,
+                "Missing `protect end_protected of protected envelope",
+            ));
+        }
+
+        // An inactive region which is not ended extends to the end of the file
+        if !self.is_active() {
+            if let Some(inactive_start) = self.inactive_start.take() {
+                let end = self.stream.state().start().pos();
+                self.directives
+                    .inactive
+                    .push(Range::new(inactive_start, end));
+            }
+        }
+
+        self.directives
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+    use pretty_assertions::assert_eq;
+
+    /// The tokens which remain after skipping the directives and inactive regions
+    /// together with the evaluated directives and their diagnostics
+    fn directives(
+        code: &Code,
+        identifiers: &[(&str, &str)],
+    ) -> (Vec<SrcPos>, Directives, Vec<Diagnostic>) {
+        let identifiers = identifiers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let contents = code.source().contents();
+        let mut tokenizer = Tokenizer::new(
+            &code.symbols,
+            code.source(),
+            ContentReader::new(&contents),
+            code.standard,
+        );
+        tokenizer.evaluate_directives(&identifiers);
+
+        let mut tokens = Vec::new();
+        while let Some(token) = tokenizer.pop().unwrap() {
+            tokens.push(token.pos);
+        }
+        let mut diagnostics = Vec::new();
+        let directives = tokenizer.take_directives(&mut diagnostics);
+        (tokens, directives, diagnostics)
+    }
+
+    fn code(code: &str) -> Code {
+        Code::new_with_standard(code, VHDLStandard::VHDL2019)
+    }
+
+    #[test]
+    fn if_elsif_else() {
+        let code = code(
+            "
+`if TOOL_TYPE = \"SIMULATION\" then
+  sim_only
+`elsif TOOL_TYPE = \"SYNTHESIS\" then
+  syn_only
+`else
+  other_only
+`end if
+after
+",
+        );
+
+        let (tokens, directives, diagnostics) = directives(&code, &[("TOOL_TYPE", "SYNTHESIS")]);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(
+            tokens,
+            vec![code.s1("syn_only").pos(), code.s1("after").pos()]
+        );
+        assert_eq!(
+            directives.inactive_ranges(),
+            &[
+                Range::new(code.s1("then").end(), code.s1("`elsif").start()),
+                Range::new(code.s1("`else").end(), code.s1("`end").start()),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_conditions_in_inactive_region() {
+        let code = code(
+            "
+`if VHDL_VERSION < \"2019\" then
+  `if UNDEFINED = \"1\" then
+    first
+  `else
+    second
+  `end
+  $ illegal
+`end
+after
+",
+        );
+
+        let (tokens, directives, diagnostics) = directives(&code, &[]);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(tokens, vec![code.s1("after").pos()]);
+        assert_eq!(
+            directives.inactive_ranges(),
+            &[Range::new(code.s1("then").end(), code.s("`end", 2).start())]
+        );
+    }
+
+    #[test]
+    fn logical_operators() {
+        let code = code(
+            "
+`if not (DEBUG = \"1\") and (level >= \"2\" or LEVEL = \"0\") then
+  first
+`end
+`if DEBUG = \"1\" xor LEVEL = \"0\" then
+  second
+`end
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[("DEBUG", "0"), ("LEVEL", "3")]);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(tokens, vec![code.s1("first").pos()]);
+    }
+
+    #[test]
+    fn mixed_logical_operators_require_parentheses() {
+        let code = code(
+            "
+`if A = \"1\" and B = \"1\" or C = \"1\" then
+`end
+",
+        );
+
+        let (_, _, diagnostics) = directives(&code, &[("A", "1"), ("B", "1"), ("C", "1")]);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("or"),
+                "Mixed logical operators must be separated by parentheses"
+            )]
+        );
+    }
+
+    #[test]
+    fn undefined_identifier() {
+        let code = code(
+            "
+`if UNDEFINED = \"1\" then
+  a
+`end
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("UNDEFINED"),
+                "Undefined conditional analysis identifier 'UNDEFINED'"
+            )]
+        );
+        assert_eq!(tokens, vec![]);
+    }
+
+    #[test]
+    fn warning_and_error_directives() {
+        let code = code(
+            "
+`warning \"active warning\"
+`if TOOL_NAME = \"other\" then
+  `error \"inactive error\"
+`else
+  `error \"active error\"
+`end
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::warning(code.s1("`warning"), "active warning")
+                    .with_code(ErrorCode::WarningDirective),
+                Diagnostic::error(code.s("`error", 2), "active error")
+                    .with_code(ErrorCode::ErrorDirective),
+            ]
+        );
+        assert_eq!(tokens, vec![]);
+    }
+
+    #[test]
+    fn unmatched_directives() {
+        let code = code(
+            "
+`end
+`else
+`if VHDL_VERSION = \"2019\" then
+  a
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(code.s1("`end"), "`end without matching `if"),
+                Diagnostic::error(code.s1("`else"), "`else without matching `if"),
+                Diagnostic::error(code.s1("`if"), "Missing `end of conditional analysis"),
+            ]
+        );
+        assert_eq!(tokens, vec![code.s1("a").pos()]);
+    }
+
+    #[test]
+    fn end_if_only_consumes_if_on_the_same_line() {
+        let code = code(
+            "
+`if VHDL_VERSION = \"2019\" then
+`end
+if
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(tokens, vec![code.s("if", 2).pos()]);
+    }
+
+    #[test]
+    fn other_tool_directives_are_skipped() {
+        let code = code(
+            "
+`other directive ( $
+a
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(tokens, vec![code.s1("a").pos()]);
+    }
+
+    #[test]
+    fn conditional_analysis_before_vhdl2019() {
+        let code = Code::new_with_standard(
+            "
+`if VHDL_VERSION = \"2008\" then
+a
+`end
+",
+            VHDLStandard::VHDL2008,
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(
+                    code.s1("`if"),
+                    "Conditional analysis is not allowed in VHDL-2008"
+                ),
+                Diagnostic::error(
+                    code.s1("`end"),
+                    "Conditional analysis is not allowed in VHDL-2008"
+                ),
+            ]
+        );
+        assert_eq!(tokens, vec![code.s1("a").pos()]);
+    }
+
+    #[test]
+    fn protected_envelope_is_skipped() {
+        for standard in [VHDLStandard::VHDL1993, VHDLStandard::VHDL2019].iter() {
+            let code = Code::new_with_standard(
+                "
+before;
+`protect begin_protected
+`protect version = 1
+`protect encrypt_agent = \"Vendor\", encrypt_agent_info = \"Tool\"
+`protect encoding = (enctype = \"BASE64\", line_length = 76, bytes = 128)
+`protect key_block
+Ld9Yz1V0w+7bYxw/Qfj3mS0Xq5TQ2H0xp3Jm8e1CzV2pbW0+/==
+`protect data_block
+/ZZyQ1kV3xz9+0Hf4H8dYc/vHsxUXZp+q2lM0bBz4f9NzA5v1fQ1
+9k0uU7g+FOoYc3hQ1/end+entity9jX0A7Q==
+`protect end_protected
+after
+",
+                *standard,
+            );
+
+            let (tokens, directives, diagnostics) = directives(&code, &[]);
+            assert_eq!(diagnostics, vec![]);
+            assert_eq!(
+                tokens,
+                vec![
+                    code.s1("before").pos(),
+                    code.s1(";").pos(),
+                    code.s1("after").pos()
+                ]
+            );
+            assert_eq!(directives.inactive_ranges(), &[]);
+        }
+    }
+
+    #[test]
+    fn protected_envelope_without_end() {
+        let code = code(
+            "
+`protect begin_protected
+`protect data_block
+Ld9Yz1V0w+7bYxw/Qfj3mS0Xq5TQ2H0xp3Jm8e1CzV2pbW0+/==
+",
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("`protect begin_protected"),
+                "Missing `protect end_protected of protected envelope"
+            )]
+        );
+        assert_eq!(tokens, vec![]);
+    }
+
+    #[test]
+    fn protect_end_without_begin() {
+        let code = code(
+            "
+`protect end_protected
+",
+        );
+
+        let (_, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("`protect end_protected"),
+                "`protect end_protected without matching begin_protected"
+            )]
+        );
+    }
+
+    #[test]
+    fn tool_directives_before_vhdl2008() {
+        let code = Code::new_with_standard(
+            "
+`other directive
+a
+",
+            VHDLStandard::VHDL2002,
+        );
+
+        let (tokens, _, diagnostics) = directives(&code, &[]);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("`other"),
+                "Tool directives are not allowed in VHDL-2002"
+            )]
+        );
+        assert_eq!(tokens, vec![code.s1("a").pos()]);
+    }
+}
@@ -0,0 +1,90 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::common::ParseResult;
+use super::expression::parse_expression;
+use super::names::{parse_name, parse_selected_name};
+use super::tokens::{Kind::*, TokenStream};
+use crate::ast::*;
+
+/// LRM 7.4 Disconnection specification
+fn parse_guarded_signal_list(stream: &mut TokenStream) -> ParseResult<GuardedSignalList> {
+    if stream.skip_if_kind(All)? {
+        Ok(GuardedSignalList::All)
+    } else if stream.skip_if_kind(Others)? {
+        Ok(GuardedSignalList::Others)
+    } else {
+        let mut names = vec![parse_name(stream)?];
+        while stream.skip_if_kind(Comma)? {
+            names.push(parse_name(stream)?);
+        }
+        Ok(GuardedSignalList::Names(names))
+    }
+}
+
+/// LRM 7.4 Disconnection specification
+pub fn parse_disconnection_specification(
+    stream: &mut TokenStream,
+) -> ParseResult<DisconnectionSpecification> {
+    stream.expect_kind(Disconnect)?;
+    let signal_list = parse_guarded_signal_list(stream)?;
+    stream.expect_kind(Colon)?;
+    let type_mark = parse_selected_name(stream)?;
+    stream.expect_kind(After)?;
+    let after = parse_expression(stream)?;
+    stream.expect_kind(SemiColon)?;
+
+    Ok(DisconnectionSpecification {
+        signal_list,
+        type_mark,
+        after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn parse_disconnection_of_names() {
+        let code = Code::new("disconnect sig1, sig2 : bit after 5 ns;");
+        assert_eq!(
+            code.with_stream(parse_disconnection_specification),
+            DisconnectionSpecification {
+                signal_list: GuardedSignalList::Names(vec![
+                    code.s1("sig1").name(),
+                    code.s1("sig2").name()
+                ]),
+                type_mark: code.s1("bit").selected_name(),
+                after: code.s1("5 ns").expr(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_disconnection_of_others_and_all() {
+        let code = Code::new("disconnect others : bit after 0 ns;");
+        assert_eq!(
+            code.with_stream(parse_disconnection_specification),
+            DisconnectionSpecification {
+                signal_list: GuardedSignalList::Others,
+                type_mark: code.s1("bit").selected_name(),
+                after: code.s1("0 ns").expr(),
+            }
+        );
+
+        let code = Code::new("disconnect all : lib.pkg.typ after 0 ns;");
+        assert_eq!(
+            code.with_stream(parse_disconnection_specification),
+            DisconnectionSpecification {
+                signal_list: GuardedSignalList::All,
+                type_mark: code.s1("lib.pkg.typ").selected_name(),
+                after: code.s1("0 ns").expr(),
+            }
+        );
+    }
+}
@@ -11,6 +11,7 @@ use super::tokens::{Kind, Kind::*, Token, TokenStream};
 use crate::ast;
 use crate::ast::*;
 use crate::data::{Diagnostic, WithPos};
+use crate::standard::VHDLStandard;
 
 fn name_to_expression(name: WithPos<Name>) -> WithPos<Expression> {
     WithPos {
@@ -421,6 +422,64 @@ pub fn parse_expression_initial_token(
     parse_expr_initial_token(stream, token, 0)
 }
 
+/// LRM 9.3.8 Conditional expressions
+/// conditional_expression ::= expression { when condition else expression }
+///
+/// Before VHDL-2019 this is just an expression.
+/// When allow_trailing_condition is set the final else may be omitted as in
+/// conditional_or_unaffected_expression of a return statement
+fn parse_conditional_expression_with(
+    stream: &mut TokenStream,
+    allow_trailing_condition: bool,
+) -> ParseResult<WithPos<Expression>> {
+    let expr = parse_expression(stream)?;
+    if stream.standard() < VHDLStandard::VHDL2019 || stream.peek_kind()? != Some(When) {
+        return Ok(expr);
+    }
+
+    let mut pos = expr.pos.clone();
+    let mut conditionals = Vec::new();
+    let mut item = expr;
+
+    let else_item = loop {
+        stream.expect_kind(When)?;
+        let condition = parse_expression(stream)?;
+        pos = pos.combine(&condition.pos);
+        conditionals.push(Conditional { condition, item });
+
+        if stream.skip_if_kind(Else)? {
+            item = parse_expression(stream)?;
+            pos = pos.combine(&item.pos);
+            if stream.peek_kind()? != Some(When) {
+                break Some(item);
+            }
+        } else if allow_trailing_condition {
+            break None;
+        } else {
+            return Err(stream.peek_expect()?.kinds_error(&[Else]));
+        }
+    };
+
+    Ok(WithPos::new(
+        Expression::Conditional(Box::new(Conditionals {
+            conditionals,
+            else_item,
+        })),
+        pos,
+    ))
+}
+
+pub fn parse_conditional_expression(stream: &mut TokenStream) -> ParseResult<WithPos<Expression>> {
+    parse_conditional_expression_with(stream, false)
+}
+
+/// The conditional expression of a VHDL-2019 return statement where the final else is optional
+pub fn parse_conditional_or_unaffected_expression(
+    stream: &mut TokenStream,
+) -> ParseResult<WithPos<Expression>> {
+    parse_conditional_expression_with(stream, true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,143 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::attributes::parse_entity_class;
+use super::common::ParseResult;
+use super::names::{parse_name, parse_selected_name};
+use super::tokens::{Kind::*, TokenStream};
+use crate::ast::*;
+use crate::data::*;
+
+/// LRM 6.9 Group template declarations
+fn parse_group_template_known_is(
+    stream: &mut TokenStream,
+    ident: Ident,
+) -> ParseResult<GroupTemplateDeclaration> {
+    stream.expect_kind(LeftPar)?;
+    let mut entity_classes = Vec::new();
+    loop {
+        let entity_class = parse_entity_class(stream)?;
+        let is_repeated = stream.skip_if_kind(BOX)?;
+        entity_classes.push(EntityClassEntry {
+            entity_class,
+            is_repeated,
+        });
+
+        let token = stream.expect()?;
+        try_token_kind!(
+            token,
+            Comma => {
+                if is_repeated {
+                    return Err(Diagnostic::error(
+                        &token,
+                        "Only the last entity class entry may be followed by <>",
+                    ));
+                }
+            },
+            RightPar => break
+        );
+    }
+    stream.expect_kind(SemiColon)?;
+
+    Ok(GroupTemplateDeclaration {
+        ident,
+        entity_classes,
+    })
+}
+
+/// LRM 6.10 Group declarations
+fn parse_group_declaration_known_colon(
+    stream: &mut TokenStream,
+    ident: Ident,
+) -> ParseResult<GroupDeclaration> {
+    let template_name = parse_selected_name(stream)?;
+    stream.expect_kind(LeftPar)?;
+    let mut constituents = Vec::new();
+    loop {
+        constituents.push(parse_name(stream)?);
+        let token = stream.expect()?;
+        try_token_kind!(
+            token,
+            Comma => {},
+            RightPar => break
+        );
+    }
+    stream.expect_kind(SemiColon)?;
+
+    Ok(GroupDeclaration {
+        ident,
+        template_name,
+        constituents,
+    })
+}
+
+/// Parse either a group template declaration or a group declaration
+pub fn parse_group(stream: &mut TokenStream) -> ParseResult<Declaration> {
+    stream.expect_kind(Group)?;
+    let ident = stream.expect_ident()?;
+    let token = stream.expect()?;
+    Ok(try_token_kind!(
+        token,
+        Is => Declaration::GroupTemplate(parse_group_template_known_is(stream, ident)?),
+        Colon => Declaration::Group(parse_group_declaration_known_colon(stream, ident)?)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn parse_group_template() {
+        let code = Code::new("group path is (signal, label <>);");
+        assert_eq!(
+            code.with_stream(parse_group),
+            Declaration::GroupTemplate(GroupTemplateDeclaration {
+                ident: code.s1("path").ident(),
+                entity_classes: vec![
+                    EntityClassEntry {
+                        entity_class: EntityClass::Signal,
+                        is_repeated: false
+                    },
+                    EntityClassEntry {
+                        entity_class: EntityClass::Label,
+                        is_repeated: true
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn parse_group_template_box_must_be_last() {
+        let code = Code::new("group path is (signal <>, label);");
+        assert_eq!(
+            code.with_partial_stream(parse_group),
+            Err(Diagnostic::error(
+                code.s1(","),
+                "Only the last entity class entry may be followed by <>"
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_group_declaration() {
+        let code = Code::new("group g1 : lib.pkg.path (sig, lab1, 'a');");
+        assert_eq!(
+            code.with_stream(parse_group),
+            Declaration::Group(GroupDeclaration {
+                ident: code.s1("g1").ident(),
+                template_name: code.s1("lib.pkg.path").selected_name(),
+                constituents: vec![
+                    code.s1("sig").name(),
+                    code.s1("lab1").name(),
+                    code.s1("'a'").name()
+                ]
+            })
+        );
+    }
+}
@@ -10,9 +10,11 @@ use super::object_declaration::{parse_file_declaration_no_semi, parse_optional_a
 use super::subprogram::parse_subprogram_declaration_no_semi;
 use super::subtype_indication::parse_subtype_indication;
 use super::tokens::{Kind::*, *};
+use super::view_declaration::parse_mode_view_indication_known_keyword;
 /// LRM 6.5 Interface declarations
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 
 fn parse_optional_mode(stream: &mut TokenStream) -> ParseResult<Option<Mode>> {
     Ok(match stream.peek_kind()? {
@@ -93,6 +95,26 @@ fn parse_interface_object_declaration(
 
     stream.expect_kind(Colon)?;
 
+    if let Some(token) = stream.pop_if_kind(View)? {
+        let object_class = explicit_object_class.unwrap_or(ObjectClass::Signal);
+        if list_type == InterfaceListType::Generic || object_class != ObjectClass::Signal {
+            return Err(Diagnostic::error(
+                &token,
+                "Mode view is only allowed for interface signals",
+            ));
+        }
+        let view = parse_mode_view_indication_known_keyword(stream)?;
+        return Ok(idents
+            .into_iter()
+            .map(|ident| {
+                InterfaceDeclaration::View(InterfaceViewDeclaration {
+                    ident,
+                    view: view.clone(),
+                })
+            })
+            .collect());
+    }
+
     let mode = parse_optional_mode(stream)?;
 
     let mode_pos = match mode {
@@ -111,6 +133,11 @@ fn parse_interface_object_declaration(
     };
 
     let subtype = parse_subtype_indication(stream)?;
+    let signal_kind = if object_class == ObjectClass::Signal && stream.skip_if_kind(Bus)? {
+        Some(SignalKind::Bus)
+    } else {
+        None
+    };
     let expr = parse_optional_assignment(stream)?;
 
     // @TODO maybe move this to a semantic check?
@@ -149,6 +176,7 @@ fn parse_interface_object_declaration(
                 class: object_class,
                 ident,
                 subtype_indication: subtype.clone(),
+                signal_kind,
                 expression: expr.clone(),
             })
         })
@@ -242,13 +270,14 @@ fn parse_interface_declaration(
 }
 
 /// Parse ; separator in generic or port lists.
-/// Expect ; for all but the last item
+/// Expect ; for all but the last item, VHDL-2019 also allows it after the last item
 fn parse_semicolon_separator(stream: &mut TokenStream) -> ParseResult<()> {
     let token = stream.peek_expect()?;
     try_token_kind!(token,
                       SemiColon => {
                           stream.move_after(&token);
-                          if stream.peek_expect()?.kind == RightPar {
+                          if stream.peek_expect()?.kind == RightPar
+                              && stream.standard() < VHDLStandard::VHDL2019 {
                               return Err(Diagnostic::error(&token,
                                                         format!("Last interface element may not end with {}",
                                                     kinds_str(&[SemiColon]))));
@@ -404,6 +433,7 @@ mod tests {
                     class: ObjectClass::Constant,
                     ident: code.s1("foo").ident(),
                     subtype_indication: code.s1("natural").subtype_indication(),
+                    signal_kind: None,
                     expression: None
                 }),
                 InterfaceDeclaration::Object(InterfaceObjectDeclaration {
@@ -412,6 +442,7 @@ mod tests {
                     class: ObjectClass::Constant,
                     ident: code.s1("bar").ident(),
                     subtype_indication: code.s1("natural").subtype_indication(),
+                    signal_kind: None,
                     expression: None
                 })
             ]
@@ -429,6 +460,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("std_logic").subtype_indication(),
+                signal_kind: None,
                 expression: None
             })
         );
@@ -481,6 +513,7 @@ mod tests {
                 class: ObjectClass::Signal,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("std_logic").subtype_indication(),
+                signal_kind: None,
                 expression: None
             })
         );
@@ -549,6 +582,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("std_logic").subtype_indication(),
+                signal_kind: None,
                 expression: None
             })
         );
@@ -565,11 +599,29 @@ mod tests {
                 class: ObjectClass::Signal,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("std_logic").subtype_indication(),
+                signal_kind: None,
                 expression: None
             })
         );
     }
 
+    #[test]
+    fn parses_port_with_bus_signal_kind() {
+        let code = Code::new("signal foo : inout std_logic bus := '0'");
+        assert_eq!(
+            code.with_stream(parse_port),
+            InterfaceDeclaration::Object(InterfaceObjectDeclaration {
+                list_type: InterfaceListType::Port,
+                mode: Mode::InOut,
+                class: ObjectClass::Signal,
+                ident: code.s1("foo").ident(),
+                subtype_indication: code.s1("std_logic").subtype_indication(),
+                signal_kind: Some(SignalKind::Bus),
+                expression: Some(code.s1("'0'").expr())
+            })
+        );
+    }
+
     #[test]
     fn parse_generic_non_in_mode_error() {
         let code = Code::new("foo : out boolean");
@@ -625,6 +677,66 @@ bar : natural)",
         );
     }
 
+    #[test]
+    fn test_parse_generic_interface_list_last_semi_colon_vhdl2019() {
+        let code = Code::new_with_standard(
+            "\
+(constant foo : std_logic;
+ bar : natural;
+)",
+            VHDLStandard::VHDL2019,
+        );
+
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_generic_interface_list),
+            vec![
+                code.s1("constant foo : std_logic").generic(),
+                code.s1("bar : natural").generic()
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_port_with_mode_view() {
+        let code = Code::new_with_standard("signal foo, bar : view master", VHDLStandard::VHDL2019);
+        let view = ModeViewIndication {
+            kind: ModeViewKind::Record,
+            name: ModeViewName {
+                name: code.s1("master").selected_name(),
+                converse: false,
+            },
+            subtype_indication: None,
+        };
+
+        assert_eq!(
+            code.with_stream_no_diagnostics(|stream, diagnostics| {
+                parse_interface_declaration(stream, diagnostics, InterfaceListType::Port)
+            }),
+            vec![
+                InterfaceDeclaration::View(InterfaceViewDeclaration {
+                    ident: code.s1("foo").ident(),
+                    view: view.clone(),
+                }),
+                InterfaceDeclaration::View(InterfaceViewDeclaration {
+                    ident: code.s1("bar").ident(),
+                    view,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn mode_view_is_not_allowed_in_generic() {
+        let code = Code::new_with_standard("foo : view master", VHDLStandard::VHDL2019);
+        assert_eq!(
+            code.with_partial_stream(parse_generic),
+            Err(Diagnostic::error(
+                code.s1("view"),
+                "Mode view is only allowed for interface signals"
+            ))
+        );
+    }
+
     #[test]
     fn test_parse_port_interface_list() {
         let code = Code::new(
@@ -7,6 +7,7 @@
 use super::common::ParseResult;
 /// LRM 8. Names
 use super::expression::{parse_expression, parse_expression_initial_token};
+use super::psl::is_repetition;
 use super::subprogram::parse_signature;
 use super::subtype_indication::parse_subtype_indication;
 use super::tokens::{Kind::*, Token, TokenStream};
@@ -193,7 +194,7 @@ fn parse_attribute_name(
     name: WithPos<Name>,
     signature: Option<WithPos<Signature>>,
 ) -> ParseResult<WithPos<Name>> {
-    let attr = stream.expect_ident_or_range()?;
+    let attr = stream.expect_attribute_designator()?;
 
     let (expression, pos) = {
         if stream.skip_if_kind(LeftPar)? {
@@ -208,7 +209,7 @@ fn parse_attribute_name(
     Ok(WithPos {
         item: Name::Attribute(Box::new(AttributeName {
             name,
-            attr,
+            attr: WithRef::new(attr),
             signature,
             expr: expression.map(Box::new),
         })),
@@ -340,6 +341,10 @@ pub fn parse_name_initial_token(
                 }
             }
             LeftSquare => {
+                // PSL repetitions such as a[*2] are not signatures
+                if is_repetition(stream)? {
+                    break;
+                }
                 let state = stream.state();
                 let signature = Some(parse_signature(stream)?);
                 if !stream.skip_if_kind(Tick)? {
@@ -611,7 +616,7 @@ mod tests {
         let attr = WithPos {
             item: Name::Attribute(Box::new(AttributeName {
                 name: prefix,
-                attr: code.s1("foo").ident(),
+                attr: code.s1("foo").ident().into_ref(),
                 signature: None,
                 expr: None,
             })),
@@ -633,7 +638,8 @@ mod tests {
                 attr: WithPos {
                     item: code.symbol("range"),
                     pos: code.s1("range").pos(),
-                },
+                }
+                .into_ref(),
                 signature: None,
                 expr: None,
             })),
@@ -642,6 +648,29 @@ mod tests {
         assert_eq!(code.with_stream(parse_name), attr);
     }
 
+    #[test]
+    fn test_attribute_name_subtype() {
+        let code = Code::new("prefix'subtype");
+        let prefix = WithPos {
+            item: Name::Designator(Designator::Identifier(code.symbol("prefix")).into_ref()),
+            pos: code.s1("prefix").pos(),
+        };
+        let attr = WithPos {
+            item: Name::Attribute(Box::new(AttributeName {
+                name: prefix,
+                attr: WithPos {
+                    item: code.symbol("subtype"),
+                    pos: code.s1("subtype").pos(),
+                }
+                .into_ref(),
+                signature: None,
+                expr: None,
+            })),
+            pos: code.s1("prefix'subtype").pos(),
+        };
+        assert_eq!(code.with_stream(parse_name), attr);
+    }
+
     #[test]
     fn test_attribute_name_expression() {
         let code = Code::new("prefix'foo(expr+1)");
@@ -652,7 +681,7 @@ mod tests {
         let attr = WithPos {
             item: Name::Attribute(Box::new(AttributeName {
                 name: prefix,
-                attr: code.s1("foo").ident(),
+                attr: code.s1("foo").ident().into_ref(),
                 signature: None,
                 expr: Some(Box::new(code.s1("expr+1").expr())),
             })),
@@ -671,7 +700,7 @@ mod tests {
         let attr = WithPos {
             item: Name::Attribute(Box::new(AttributeName {
                 name: prefix,
-                attr: code.s1("foo").ident(),
+                attr: code.s1("foo").ident().into_ref(),
                 signature: Some(code.s1("[return natural]").signature()),
                 expr: Some(Box::new(code.s1("expr+1").expr())),
             })),
@@ -5,7 +5,7 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use super::common::ParseResult;
-use super::expression::parse_expression;
+use super::expression::{parse_conditional_expression, parse_expression};
 use super::names::parse_identifier_list;
 use super::subtype_indication::parse_subtype_indication;
 use super::tokens::{Kind::*, TokenStream};
@@ -17,13 +17,24 @@ pub fn parse_optional_assignment(
     stream: &mut TokenStream,
 ) -> ParseResult<Option<WithPos<Expression>>> {
     if stream.pop_if_kind(ColonEq)?.is_some() {
-        let expr = parse_expression(stream)?;
+        let expr = parse_conditional_expression(stream)?;
         Ok(Some(expr))
     } else {
         Ok(None)
     }
 }
 
+/// LRM 6.4.2.3 Signal declarations
+pub fn parse_optional_signal_kind(stream: &mut TokenStream) -> ParseResult<Option<SignalKind>> {
+    if stream.skip_if_kind(Register)? {
+        Ok(Some(SignalKind::Register))
+    } else if stream.skip_if_kind(Bus)? {
+        Ok(Some(SignalKind::Bus))
+    } else {
+        Ok(None)
+    }
+}
+
 fn parse_object_declaration_kind(
     stream: &mut TokenStream,
     class: ObjectClass,
@@ -31,6 +42,11 @@ fn parse_object_declaration_kind(
     let idents = parse_identifier_list(stream)?;
     stream.expect_kind(Colon)?;
     let subtype = parse_subtype_indication(stream)?;
+    let signal_kind = if class == ObjectClass::Signal {
+        parse_optional_signal_kind(stream)?
+    } else {
+        None
+    };
     let opt_expression = parse_optional_assignment(stream)?;
 
     Ok(idents
@@ -39,6 +55,7 @@ fn parse_object_declaration_kind(
             class,
             ident,
             subtype_indication: subtype.clone(),
+            signal_kind,
             expression: opt_expression.clone(),
         })
         .collect())
@@ -115,6 +132,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -129,6 +147,34 @@ mod tests {
                 class: ObjectClass::Signal,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
+                expression: None
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_signal_kind() {
+        let code = Code::new("signal foo : natural register := 0;");
+        assert_eq!(
+            code.with_stream(parse_object_declaration),
+            vec![ObjectDeclaration {
+                class: ObjectClass::Signal,
+                ident: code.s1("foo").ident(),
+                subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: Some(SignalKind::Register),
+                expression: Some(code.s1("0").expr())
+            }]
+        );
+
+        let code = Code::new("signal foo : natural bus;");
+        assert_eq!(
+            code.with_stream(parse_object_declaration),
+            vec![ObjectDeclaration {
+                class: ObjectClass::Signal,
+                ident: code.s1("foo").ident(),
+                subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: Some(SignalKind::Bus),
                 expression: None
             }]
         );
@@ -143,6 +189,7 @@ mod tests {
                 class: ObjectClass::Variable,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -157,6 +204,7 @@ mod tests {
                 class: ObjectClass::SharedVariable,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: None
             }]
         );
@@ -213,6 +261,7 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("0").expr())
             }]
         );
@@ -227,12 +276,14 @@ mod tests {
                 class: ObjectClass::Constant,
                 ident: code.s1("foo").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("0").expr()),
             },
             ObjectDeclaration {
                 class: ObjectClass::Constant,
                 ident: code.s1("bar").ident(),
                 subtype_indication: code.s1("natural").subtype_indication(),
+                signal_kind: None,
                 expression: Some(code.s1("0").expr()),
             },
         ];
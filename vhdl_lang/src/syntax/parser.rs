@@ -5,49 +5,96 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use super::design_unit::parse_design_file;
+use super::directives::Directives;
+use super::suppression::Suppressions;
 use super::tokens::{Symbols, TokenStream, Tokenizer};
 use crate::ast::DesignFile;
 use crate::data::*;
+use crate::standard::VHDLStandard;
+use fnv::FnvHashMap;
 use std::io;
 use std::sync::Arc;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct VHDLParser {
     pub symbols: Arc<Symbols>,
+    // The configured values of conditional analysis identifiers
+    pub identifiers: FnvHashMap<String, String>,
 }
 
 pub type ParserResult = Result<(Source, DesignFile), io::Error>;
 
+/// A design file together with what else was found while parsing its source
+#[derive(Default)]
+pub struct ParsedSource {
+    pub design_file: DesignFile,
+    // The suppression comments of the source
+    pub suppressions: Suppressions,
+    // The tool directives of the source such as conditional analysis
+    pub directives: Directives,
+}
+
 impl VHDLParser {
     pub fn symbol(&self, name: &Latin1String) -> Symbol {
         self.symbols.symtab().insert(name)
     }
 
+    /// Parse a source skipping its directives and inactive regions
+    /// The tool directives are evaluated when the tokenizer reaches them and
+    /// the suppression comments are collected from the tokens read by the parser
     pub fn parse_design_source(
         &self,
         source: &Source,
+        standard: VHDLStandard,
         diagnostics: &mut dyn DiagnosticHandler,
-    ) -> DesignFile {
+    ) -> ParsedSource {
         let contents = source.contents();
-        let tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
+        let mut tokenizer = Tokenizer::new(
+            &self.symbols,
+            source,
+            ContentReader::new(&contents),
+            standard,
+        );
+        tokenizer.evaluate_directives(&self.identifiers);
+        let has_suppressions = Suppressions::may_have_suppressions(source);
+        if has_suppressions {
+            tokenizer.record_comments();
+        }
         let mut stream = TokenStream::new(tokenizer);
 
-        match parse_design_file(&mut stream, diagnostics) {
+        let design_file = match parse_design_file(&mut stream, diagnostics) {
             Ok(design_file) => design_file,
             Err(diagnostic) => {
                 diagnostics.push(diagnostic);
                 DesignFile::default()
             }
+        };
+
+        // The comments and directives after a fatal parse error are not read by the parser
+        while !matches!(stream.pop(), Ok(None)) {}
+        let directives = stream.tokenizer().take_directives(diagnostics);
+
+        let suppressions = if has_suppressions {
+            Suppressions::from_comments(source, stream.tokenizer().take_comments())
+        } else {
+            Suppressions::default()
+        };
+
+        ParsedSource {
+            design_file,
+            suppressions,
+            directives,
         }
     }
 
     pub fn parse_design_file(
         &self,
         file_name: &Path,
+        standard: VHDLStandard,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> ParserResult {
-        let source = Source::from_latin1_file(file_name)?;
-        let design_file = self.parse_design_source(&source, diagnostics);
-        Ok((source, design_file))
+        let source = Source::from_file(file_name, None)?;
+        let parsed = self.parse_design_source(&source, standard, diagnostics);
+        Ok((source, parsed.design_file))
     }
 }
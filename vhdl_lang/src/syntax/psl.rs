@@ -0,0 +1,830 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::common::{parse_optional, ParseResult};
+use super::concurrent_statement::parse_labeled_concurrent_statement_initial_token;
+use super::declarative_part::{is_declaration_start, parse_declaration};
+use super::expression::parse_expression;
+use super::tokens::{Kind, Kind::*, Token, TokenStream, Value};
+use crate::ast::*;
+use crate::data::*;
+
+/// The PSL keywords which are not reserved words of VHDL
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum PslKeyword {
+    Always,
+    Never,
+    Eventually,
+    NextA,
+    NextE,
+    NextEvent,
+    NextEventA,
+    NextEventE,
+    Before,
+    BeforeInclusive,
+    UntilInclusive,
+    Abort,
+    AsyncAbort,
+    SyncAbort,
+    Within,
+    Inf,
+    Inherit,
+    Clock,
+    Const,
+    Boolean,
+}
+
+fn psl_keyword(token: &Token) -> Option<PslKeyword> {
+    let name = match token.value {
+        Value::Identifier(ref symbol) if token.kind == Identifier => {
+            symbol.name_utf8().to_ascii_lowercase()
+        }
+        _ => return None,
+    };
+
+    Some(match name.as_str() {
+        "always" => PslKeyword::Always,
+        "never" => PslKeyword::Never,
+        "eventually" => PslKeyword::Eventually,
+        "next_a" => PslKeyword::NextA,
+        "next_e" => PslKeyword::NextE,
+        "next_event" => PslKeyword::NextEvent,
+        "next_event_a" => PslKeyword::NextEventA,
+        "next_event_e" => PslKeyword::NextEventE,
+        "before" => PslKeyword::Before,
+        "before_" => PslKeyword::BeforeInclusive,
+        "until_" => PslKeyword::UntilInclusive,
+        "abort" => PslKeyword::Abort,
+        "async_abort" => PslKeyword::AsyncAbort,
+        "sync_abort" => PslKeyword::SyncAbort,
+        "within" => PslKeyword::Within,
+        "inf" => PslKeyword::Inf,
+        "inherit" => PslKeyword::Inherit,
+        "clock" => PslKeyword::Clock,
+        "const" => PslKeyword::Const,
+        "boolean" => PslKeyword::Boolean,
+        _ => return None,
+    })
+}
+
+/// A PSL repetition such as [*2], [+], [=2] or [->] follows
+pub fn is_repetition(stream: &mut TokenStream) -> ParseResult<bool> {
+    for kind in [Times, Plus, EQ, Implication].iter() {
+        if stream.next_kinds_are(&[LeftSquare, *kind])? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn binary(
+    op: PslBinary,
+    op_pos: &SrcPos,
+    strong: bool,
+    left: WithPos<PslExpression>,
+    right: WithPos<PslExpression>,
+) -> WithPos<PslExpression> {
+    let pos = left.pos.combine(&right.pos);
+    WithPos::new(
+        PslExpression::Binary(Box::new(PslBinaryExpression {
+            op: WithPos::new(op, op_pos),
+            strong,
+            left,
+            right,
+        })),
+        pos,
+    )
+}
+
+/// Parse a left associative sequence of binary operators
+fn parse_binary(
+    stream: &mut TokenStream,
+    parse_operand: fn(&mut TokenStream) -> ParseResult<WithPos<PslExpression>>,
+    to_op: fn(&Token) -> Option<PslBinary>,
+) -> ParseResult<WithPos<PslExpression>> {
+    let mut left = parse_operand(stream)?;
+    while let Some(token) = stream.peek()? {
+        if let Some(op) = to_op(&token) {
+            stream.move_after(&token);
+            let right = parse_operand(stream)?;
+            left = binary(op, &token.pos, false, left, right);
+        } else {
+            break;
+        }
+    }
+    Ok(left)
+}
+
+/// Parse the count of a repetition or an occurrence operator
+fn parse_count(stream: &mut TokenStream) -> ParseResult<PslCount> {
+    let low = parse_expression(stream)?;
+    if stream.skip_if_kind(To)? {
+        let token = stream.peek_expect()?;
+        let high = if psl_keyword(&token) == Some(PslKeyword::Inf) {
+            stream.move_after(&token);
+            None
+        } else {
+            Some(parse_expression(stream)?)
+        };
+        Ok(PslCount::Range(low, high))
+    } else {
+        Ok(PslCount::Number(low))
+    }
+}
+
+/// PSL SERE repetition of an optional operand
+fn parse_repetition(
+    stream: &mut TokenStream,
+    operand: Option<WithPos<PslExpression>>,
+) -> ParseResult<WithPos<PslExpression>> {
+    let start = stream.expect_kind(LeftSquare)?;
+    let token = stream.expect()?;
+    let (kind, count) = try_token_kind!(
+        token,
+        Times => {
+            if stream.peek_kind()? == Some(RightSquare) {
+                (PslRepetitionKind::Consecutive, None)
+            } else {
+                (PslRepetitionKind::Consecutive, Some(parse_count(stream)?))
+            }
+        },
+        Plus => (PslRepetitionKind::Plus, None),
+        EQ => (PslRepetitionKind::NonConsecutive, Some(parse_count(stream)?)),
+        Implication => {
+            if stream.peek_kind()? == Some(RightSquare) {
+                (PslRepetitionKind::Goto, None)
+            } else {
+                (PslRepetitionKind::Goto, Some(parse_count(stream)?))
+            }
+        }
+    );
+    let end = stream.expect_kind(RightSquare)?;
+
+    let pos = match operand {
+        Some(ref operand) => operand.pos.combine(&end),
+        None => start.pos.combine(&end),
+    };
+    Ok(WithPos::new(
+        PslExpression::Repetition(Box::new(PslRepetition {
+            kind,
+            count,
+            operand,
+        })),
+        pos,
+    ))
+}
+
+fn parse_boolean(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    let expr = parse_expression(stream)?;
+    let pos = expr.pos.clone();
+    Ok(WithPos::new(PslExpression::Boolean(expr), pos))
+}
+
+/// Parse a braced SERE, a parenthesized property or a VHDL boolean
+/// followed by any repetitions and clocking
+fn parse_primary(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    let token = stream.peek_expect()?;
+    let mut expr = match token.kind {
+        LeftBrace => {
+            stream.move_after(&token);
+            let sere = parse_sere(stream)?;
+            let end = stream.expect_kind(RightBrace)?;
+            WithPos::new(
+                PslExpression::Sequence(Box::new(sere)),
+                token.pos.combine(&end),
+            )
+        }
+        LeftSquare => parse_repetition(stream, None)?,
+        LeftPar => {
+            // A parenthesized VHDL expression is a boolean
+            let state = stream.state();
+            match parse_boolean(stream) {
+                Ok(expr) => expr,
+                Err(_) => {
+                    stream.set_state(state);
+                    stream.move_after(&token);
+                    let expr = parse_psl_expression(stream)?;
+                    let end = stream.expect_kind(RightPar)?;
+                    expr.combine_pos_with(&token).combine_pos_with(&end)
+                }
+            }
+        }
+        _ => parse_boolean(stream)?,
+    };
+
+    loop {
+        if is_repetition(stream)? {
+            expr = parse_repetition(stream, Some(expr))?;
+        } else if stream.skip_if_kind(CommAt)? {
+            let clock = parse_expression(stream)?;
+            let pos = expr.pos.combine(&clock.pos);
+            expr = WithPos::new(PslExpression::Clocked(Box::new(expr), clock), pos);
+        } else {
+            break;
+        }
+    }
+
+    Ok(expr)
+}
+
+fn parse_sere_within(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    parse_binary(stream, parse_primary, |token| {
+        if psl_keyword(token) == Some(PslKeyword::Within) {
+            Some(PslBinary::Within)
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_sere_and(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    parse_binary(stream, parse_sere_within, |token| match token.kind {
+        AndAnd => Some(PslBinary::LengthMatchingAnd),
+        Concat => Some(PslBinary::NonLengthMatchingAnd),
+        _ => None,
+    })
+}
+
+fn parse_sere_or(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    parse_binary(stream, parse_sere_and, |token| match token.kind {
+        Bar => Some(PslBinary::SequenceOr),
+        _ => None,
+    })
+}
+
+/// PSL sequential extended regular expression within braces
+fn parse_sere(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    parse_binary(stream, parse_sere_or, |token| match token.kind {
+        SemiColon => Some(PslBinary::Concatenation),
+        Colon => Some(PslBinary::Fusion),
+        _ => None,
+    })
+}
+
+fn parse_termination(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    parse_binary(stream, parse_primary, |token| match psl_keyword(token) {
+        Some(PslKeyword::Abort) => Some(PslBinary::Abort),
+        Some(PslKeyword::AsyncAbort) => Some(PslBinary::AsyncAbort),
+        Some(PslKeyword::SyncAbort) => Some(PslBinary::SyncAbort),
+        _ => None,
+    })
+}
+
+/// PSL FL occurrence and invariance operators
+fn parse_occurrence(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    let token = stream.peek_expect()?;
+    let op = if token.kind == Next {
+        PslUnary::Next
+    } else {
+        match psl_keyword(&token) {
+            Some(PslKeyword::Always) => PslUnary::Always,
+            Some(PslKeyword::Never) => PslUnary::Never,
+            Some(PslKeyword::Eventually) => PslUnary::Eventually,
+            Some(PslKeyword::NextA) => PslUnary::NextA,
+            Some(PslKeyword::NextE) => PslUnary::NextE,
+            Some(PslKeyword::NextEvent) => PslUnary::NextEvent,
+            Some(PslKeyword::NextEventA) => PslUnary::NextEventA,
+            Some(PslKeyword::NextEventE) => PslUnary::NextEventE,
+            _ => return parse_termination(stream),
+        }
+    };
+    stream.move_after(&token);
+
+    // eventually only exists as the strong eventually!
+    let strong = if op == PslUnary::Eventually {
+        stream.expect_kind(Exclamation)?;
+        true
+    } else {
+        stream.skip_if_kind(Exclamation)?
+    };
+
+    let event = if matches!(
+        op,
+        PslUnary::NextEvent | PslUnary::NextEventA | PslUnary::NextEventE
+    ) {
+        stream.expect_kind(LeftPar)?;
+        let event = parse_expression(stream)?;
+        stream.expect_kind(RightPar)?;
+        Some(event)
+    } else {
+        None
+    };
+
+    let count = if stream.skip_if_kind(LeftSquare)? {
+        let count = parse_count(stream)?;
+        stream.expect_kind(RightSquare)?;
+        Some(count)
+    } else {
+        None
+    };
+
+    let operand = match op {
+        PslUnary::Always | PslUnary::Never => parse_psl_expression(stream)?,
+        _ => parse_occurrence(stream)?,
+    };
+
+    let pos = token.pos.combine(&operand.pos);
+    Ok(WithPos::new(
+        PslExpression::Unary(Box::new(PslUnaryExpression {
+            op: WithPos::new(op, &token.pos),
+            strong,
+            event,
+            count,
+            operand,
+        })),
+        pos,
+    ))
+}
+
+fn parse_logical(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    parse_binary(stream, parse_occurrence, |token| match token.kind {
+        And => Some(PslBinary::And),
+        Or => Some(PslBinary::Or),
+        _ => None,
+    })
+}
+
+/// PSL FL bounding operators which are right associative
+fn parse_bounding(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    let left = parse_logical(stream)?;
+    let token = if let Some(token) = stream.peek()? {
+        token
+    } else {
+        return Ok(left);
+    };
+
+    let op = if token.kind == Until {
+        PslBinary::Until
+    } else {
+        match psl_keyword(&token) {
+            Some(PslKeyword::UntilInclusive) => PslBinary::UntilInclusive,
+            Some(PslKeyword::Before) => PslBinary::Before,
+            Some(PslKeyword::BeforeInclusive) => PslBinary::BeforeInclusive,
+            _ => return Ok(left),
+        }
+    };
+    stream.move_after(&token);
+    let strong = stream.skip_if_kind(Exclamation)?;
+    let right = parse_bounding(stream)?;
+    Ok(binary(op, &token.pos, strong, left, right))
+}
+
+/// PSL suffix implication |-> and |=>
+fn parse_suffix_implication(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    let left = parse_bounding(stream)?;
+
+    let op = if stream.next_kinds_are(&[Bar, Implication])? {
+        PslBinary::OverlappingSuffixImplication
+    } else if stream.next_kinds_are(&[Bar, RightArrow])? {
+        PslBinary::SuffixImplication
+    } else {
+        return Ok(left);
+    };
+
+    let bar = stream.expect()?;
+    let arrow = stream.expect()?;
+    let right = parse_suffix_implication(stream)?;
+    Ok(binary(op, &bar.pos.combine(&arrow), false, left, right))
+}
+
+/// Parse a PSL property or sequence
+pub fn parse_psl_expression(stream: &mut TokenStream) -> ParseResult<WithPos<PslExpression>> {
+    let left = parse_suffix_implication(stream)?;
+    let token = if let Some(token) = stream.peek()? {
+        token
+    } else {
+        return Ok(left);
+    };
+
+    let op = match token.kind {
+        Implication => PslBinary::Implication,
+        Equivalence => PslBinary::Equivalence,
+        _ => return Ok(left),
+    };
+    stream.move_after(&token);
+    let right = parse_psl_expression(stream)?;
+    Ok(binary(op, &token.pos, false, left, right))
+}
+
+/// PSL verification directives
+/// The directive keyword has already been consumed
+pub fn parse_psl_directive_known_keyword(
+    stream: &mut TokenStream,
+    kind: PslDirectiveKind,
+) -> ParseResult<PslDirective> {
+    let expression = parse_psl_expression(stream)?;
+
+    let report = if matches!(kind, PslDirectiveKind::Assert | PslDirectiveKind::Cover) {
+        parse_optional(stream, Report, parse_expression)?
+    } else {
+        None
+    };
+
+    let severity = if kind == PslDirectiveKind::Assert {
+        parse_optional(stream, Severity, parse_expression)?
+    } else {
+        None
+    };
+
+    stream.expect_kind(SemiColon)?;
+    Ok(PslDirective {
+        kind,
+        expression,
+        report,
+        severity,
+    })
+}
+
+fn parse_parameter_kind(stream: &mut TokenStream) -> ParseResult<PslParameterKind> {
+    let token = stream.expect()?;
+    match token.kind {
+        Property => Ok(PslParameterKind::Property),
+        Sequence => Ok(PslParameterKind::Sequence),
+        _ => match psl_keyword(&token) {
+            Some(PslKeyword::Const) => Ok(PslParameterKind::Const),
+            Some(PslKeyword::Boolean) => Ok(PslParameterKind::Boolean),
+            _ => Err(Diagnostic::error(
+                &token,
+                "Expected 'const', 'boolean', 'property' or 'sequence'",
+            )),
+        },
+    }
+}
+
+/// PSL named property and sequence declarations
+pub fn parse_psl_declaration(stream: &mut TokenStream) -> ParseResult<PslDeclaration> {
+    let token = stream.expect()?;
+    let kind = try_token_kind!(
+        token,
+        Property => PslDeclarationKind::Property,
+        Sequence => PslDeclarationKind::Sequence
+    );
+    let ident = stream.expect_ident()?;
+
+    let mut parameters = Vec::new();
+    if stream.skip_if_kind(LeftPar)? {
+        loop {
+            let kind = parse_parameter_kind(stream)?;
+            loop {
+                parameters.push(PslParameter {
+                    kind,
+                    ident: stream.expect_ident()?,
+                });
+                if !stream.skip_if_kind(Comma)? {
+                    break;
+                }
+            }
+
+            let token = stream.expect()?;
+            try_token_kind!(
+                token,
+                SemiColon => {},
+                RightPar => break
+            );
+        }
+    }
+
+    stream.expect_kind(Is)?;
+    let expression = parse_psl_expression(stream)?;
+    stream.expect_kind(SemiColon)?;
+
+    Ok(PslDeclaration {
+        kind,
+        ident,
+        parameters,
+        expression,
+    })
+}
+
+/// PSL default clock declaration
+pub fn parse_default_clock(stream: &mut TokenStream) -> ParseResult<WithPos<Expression>> {
+    stream.expect_kind(Default)?;
+    let token = stream.expect()?;
+    if psl_keyword(&token) != Some(PslKeyword::Clock) {
+        return Err(Diagnostic::error(&token, "Expected 'clock'"));
+    }
+    stream.expect_kind(Is)?;
+    let clock = parse_expression(stream)?;
+    stream.expect_kind(SemiColon)?;
+    Ok(clock)
+}
+
+/// PSL verification units
+pub fn parse_verification_unit(
+    stream: &mut TokenStream,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> ParseResult<VerificationUnit> {
+    let token = stream.expect()?;
+    let kind = try_token_kind!(
+        token,
+        Vunit => VerificationUnitKind::Vunit,
+        Vmode => VerificationUnitKind::Vmode,
+        Vprop => VerificationUnitKind::Vprop
+    );
+    let ident = stream.expect_ident()?;
+
+    let (entity_name, architecture_name) = if stream.skip_if_kind(LeftPar)? {
+        let entity_name = stream.expect_ident()?;
+        let architecture_name = if stream.skip_if_kind(LeftPar)? {
+            let architecture_name = stream.expect_ident()?;
+            stream.expect_kind(RightPar)?;
+            Some(WithRef::new(architecture_name))
+        } else {
+            None
+        };
+        stream.expect_kind(RightPar)?;
+        (Some(WithRef::new(entity_name)), architecture_name)
+    } else {
+        (None, None)
+    };
+
+    stream.expect_kind(LeftBrace)?;
+
+    fn is_end(kind: Kind) -> bool {
+        kind == RightBrace
+    }
+
+    let mut inherits = Vec::new();
+    let mut decl = Vec::new();
+    let mut statements = Vec::new();
+    loop {
+        let state = stream.state();
+        let token = stream.expect()?;
+        if is_end(token.kind) {
+            break;
+        }
+
+        if psl_keyword(&token) == Some(PslKeyword::Inherit) {
+            loop {
+                inherits.push(WithRef::new(stream.expect_ident()?));
+                if !stream.skip_if_kind(Comma)? {
+                    break;
+                }
+            }
+            stream.expect_kind(SemiColon)?;
+        } else if is_declaration_start(token.kind) && token.kind != For {
+            // A for within a verification unit is a generate statement
+            stream.set_state(state);
+            parse_declaration(stream, diagnostics, &mut decl)?;
+        } else {
+            match parse_labeled_concurrent_statement_initial_token(stream, token, diagnostics) {
+                Ok(statement) => statements.push(statement),
+                Err(diagnostic) => {
+                    let recovered = stream.skip_statement(state, &diagnostic, is_end);
+                    diagnostics.push(diagnostic);
+                    recovered?;
+                }
+            }
+        }
+    }
+
+    Ok(VerificationUnit {
+        context_clause: ContextClause::default(),
+        kind,
+        ident,
+        entity_name,
+        architecture_name,
+        inherits,
+        decl,
+        statements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    fn boolean(code: &Code) -> WithPos<PslExpression> {
+        WithPos::new(PslExpression::Boolean(code.expr()), code.pos())
+    }
+
+    fn binary_expr(
+        op: PslBinary,
+        op_code: Code,
+        left: WithPos<PslExpression>,
+        right: WithPos<PslExpression>,
+    ) -> WithPos<PslExpression> {
+        binary(op, &op_code.pos(), false, left, right)
+    }
+
+    #[test]
+    fn parse_implication() {
+        let code = Code::new("req -> ack");
+        assert_eq!(
+            code.with_stream(parse_psl_expression),
+            binary_expr(
+                PslBinary::Implication,
+                code.s1("->"),
+                boolean(&code.s1("req")),
+                boolean(&code.s1("ack")),
+            )
+        );
+    }
+
+    #[test]
+    fn parse_always_next_with_clock() {
+        let code = Code::new("always (req -> next ack) @ rising_edge(clk)");
+        let implication = binary_expr(
+            PslBinary::Implication,
+            code.s1("->"),
+            boolean(&code.s1("req")),
+            WithPos::new(
+                PslExpression::Unary(Box::new(PslUnaryExpression {
+                    op: WithPos::new(PslUnary::Next, code.s1("next")),
+                    strong: false,
+                    event: None,
+                    count: None,
+                    operand: boolean(&code.s1("ack")),
+                })),
+                code.s1("next ack"),
+            ),
+        )
+        .combine_pos_with(&code.s1("(req"))
+        .combine_pos_with(&code.s1("ack)"));
+
+        assert_eq!(
+            code.with_stream(parse_psl_expression),
+            WithPos::new(
+                PslExpression::Unary(Box::new(PslUnaryExpression {
+                    op: WithPos::new(PslUnary::Always, code.s1("always")),
+                    strong: false,
+                    event: None,
+                    count: None,
+                    operand: WithPos::new(
+                        PslExpression::Clocked(
+                            Box::new(implication),
+                            code.s1("rising_edge(clk)").expr()
+                        ),
+                        code.s1("(req -> next ack) @ rising_edge(clk)")
+                    ),
+                })),
+                code.pos()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_sere_with_repetitions() {
+        let code = Code::new("{req; busy[*2 to inf]; ack[->]} |=> done");
+        let busy = WithPos::new(
+            PslExpression::Repetition(Box::new(PslRepetition {
+                kind: PslRepetitionKind::Consecutive,
+                count: Some(PslCount::Range(code.s1("2").expr(), None)),
+                operand: Some(boolean(&code.s1("busy"))),
+            })),
+            code.s1("busy[*2 to inf]"),
+        );
+        let ack = WithPos::new(
+            PslExpression::Repetition(Box::new(PslRepetition {
+                kind: PslRepetitionKind::Goto,
+                count: None,
+                operand: Some(boolean(&code.s1("ack"))),
+            })),
+            code.s1("ack[->]"),
+        );
+        let sere = binary_expr(
+            PslBinary::Concatenation,
+            code.s(";", 2),
+            binary_expr(
+                PslBinary::Concatenation,
+                code.s1(";"),
+                boolean(&code.s1("req")),
+                busy,
+            ),
+            ack,
+        );
+
+        assert_eq!(
+            code.with_stream(parse_psl_expression),
+            binary_expr(
+                PslBinary::SuffixImplication,
+                code.s1("|=>"),
+                WithPos::new(
+                    PslExpression::Sequence(Box::new(sere)),
+                    code.s1("{req; busy[*2 to inf]; ack[->]}")
+                ),
+                boolean(&code.s1("done")),
+            )
+        );
+    }
+
+    #[test]
+    fn parse_until_is_right_associative() {
+        let code = Code::new("a until! b before c");
+        assert_eq!(
+            code.with_stream(parse_psl_expression),
+            binary(
+                PslBinary::Until,
+                &code.s1("until").pos(),
+                true,
+                boolean(&code.s1("a")),
+                binary_expr(
+                    PslBinary::Before,
+                    code.s1("before"),
+                    boolean(&code.s1("b")),
+                    boolean(&code.s1("c")),
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn parse_assert_directive() {
+        let code = Code::new("never {a; b} report \"failure\";");
+        assert_eq!(
+            code.with_stream(|stream| parse_psl_directive_known_keyword(
+                stream,
+                PslDirectiveKind::Assert
+            )),
+            PslDirective {
+                kind: PslDirectiveKind::Assert,
+                expression: WithPos::new(
+                    PslExpression::Unary(Box::new(PslUnaryExpression {
+                        op: WithPos::new(PslUnary::Never, code.s1("never")),
+                        strong: false,
+                        event: None,
+                        count: None,
+                        operand: WithPos::new(
+                            PslExpression::Sequence(Box::new(binary_expr(
+                                PslBinary::Concatenation,
+                                code.s1(";"),
+                                boolean(&code.s1("a")),
+                                boolean(&code.s1("b")),
+                            ))),
+                            code.s1("{a; b}")
+                        ),
+                    })),
+                    code.s1("never {a; b}")
+                ),
+                report: Some(code.s1("\"failure\"").expr()),
+                severity: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_sequence_declaration() {
+        let code = Code::new("sequence handshake (boolean req, ack; const n) is {req; ack[*n]};");
+        let decl = code.with_stream(parse_psl_declaration);
+        assert_eq!(decl.kind, PslDeclarationKind::Sequence);
+        assert_eq!(decl.ident, code.s1("handshake").ident());
+        assert_eq!(
+            decl.parameters,
+            vec![
+                PslParameter {
+                    kind: PslParameterKind::Boolean,
+                    ident: code.s1("req").ident()
+                },
+                PslParameter {
+                    kind: PslParameterKind::Boolean,
+                    ident: code.s1("ack").ident()
+                },
+                PslParameter {
+                    kind: PslParameterKind::Const,
+                    ident: code.s1("n)").s1("n").ident()
+                },
+            ]
+        );
+        assert_eq!(decl.expression.pos, code.s1("{req; ack[*n]}").pos());
+    }
+
+    #[test]
+    fn parse_default_clock_declaration() {
+        let code = Code::new("default clock is rising_edge(clk);");
+        assert_eq!(
+            code.with_stream(parse_default_clock),
+            code.s1("rising_edge(clk)").expr()
+        );
+    }
+
+    #[test]
+    fn parse_vunit() {
+        let code = Code::new(
+            "\
+vunit checker (ent(rtl)) {
+  inherit common;
+  default clock is rising_edge(clk);
+  property p is always req -> eventually! ack;
+  signal s : boolean;
+  a0: assert p;
+  s <= req;
+}",
+        );
+        let unit = code.with_stream_no_diagnostics(parse_verification_unit);
+        assert_eq!(unit.kind, VerificationUnitKind::Vunit);
+        assert_eq!(unit.ident, code.s1("checker").ident());
+        assert_eq!(unit.entity_name, Some(WithRef::new(code.s1("ent").ident())));
+        assert_eq!(
+            unit.architecture_name,
+            Some(WithRef::new(code.s1("rtl").ident()))
+        );
+        assert_eq!(unit.inherits, vec![WithRef::new(code.s1("common").ident())]);
+        assert_eq!(unit.decl.len(), 3);
+        assert_eq!(unit.statements.len(), 2);
+        assert_eq!(unit.statements[0].label, Some(code.s1("a0").ident()));
+    }
+}
@@ -4,15 +4,19 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::common::parse_optional;
 use super::common::ParseResult;
-use super::expression::{parse_aggregate_leftpar_known, parse_choices, parse_expression};
+use super::common::{expect_end_kind, parse_optional};
+use super::expression::{
+    parse_aggregate_leftpar_known, parse_choices, parse_conditional_or_unaffected_expression,
+    parse_expression,
+};
 use super::names::{parse_name, parse_name_initial_token};
 use super::range::parse_discrete_range;
-use super::tokens::{Kind::*, Token, TokenStream};
+use super::tokens::{Kind, Kind::*, Token, TokenStream};
 use super::waveform::{parse_delay_mechanism, parse_waveform};
 use crate::ast::*;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 
 /// LRM 10.2 Wait statement
 fn parse_wait_statement_known_keyword(stream: &mut TokenStream) -> ParseResult<WaitStatement> {
@@ -66,19 +70,25 @@ pub fn parse_labeled_sequential_statements(
     stream: &mut TokenStream,
     diagnostics: &mut dyn DiagnosticHandler,
 ) -> ParseResult<(Vec<LabeledSequentialStatement>, Token)> {
+    fn is_end(kind: Kind) -> bool {
+        matches!(kind, End | Else | Elsif | When)
+    }
+
     let mut statements = Vec::new();
     loop {
+        let state = stream.state();
         let token = stream.expect()?;
-        match token.kind {
-            End | Else | Elsif | When => {
-                break Ok((statements, token));
-            }
-            _ => {
-                statements.push(parse_sequential_statement_initial_token(
-                    stream,
-                    token,
-                    diagnostics,
-                )?);
+        if is_end(token.kind) {
+            break Ok((statements, token));
+        }
+
+        match parse_sequential_statement_initial_token(stream, token, diagnostics) {
+            Ok(statement) => statements.push(statement),
+            Err(diagnostic) => {
+                // Continue with the next statement to not lose the rest of the statements
+                let recovered = stream.skip_statement(state, &diagnostic, is_end);
+                diagnostics.push(diagnostic);
+                recovered?;
             }
         }
     }
@@ -116,24 +126,27 @@ fn parse_if_statement_known_keyword(
                 try_token_kind!(
                     end_token,
                     End => {
-                        stream.expect_kind(If)?;
-                        // @TODO check end label
-                        stream.pop_if_kind(Identifier)?;
+                        if expect_end_kind(stream, &end_token, If, diagnostics)? {
+                            // @TODO check end label
+                            stream.pop_if_kind(Identifier)?;
+                            stream.expect_kind(SemiColon)?;
+                        }
                         else_branch = Some(statements);
                         break;
                     }
                 );
             },
             End => {
-                stream.expect_kind(If)?;
-                stream.pop_if_kind(Identifier)?;
+                if expect_end_kind(stream, &end_token, If, diagnostics)? {
+                    stream.pop_if_kind(Identifier)?;
+                    stream.expect_kind(SemiColon)?;
+                }
                 conditionals.push(conditional);
                 break;
             }
         );
     }
 
-    stream.expect_kind(SemiColon)?;
     Ok(IfStatement {
         conditionals,
         else_item: else_branch,
@@ -166,19 +179,20 @@ fn parse_case_statement_known_keyword(
                 continue;
             },
             End => {
-                stream.expect_kind(Case)?;
-                if is_matching {
-                    stream.expect_kind(Que)?;
+                if expect_end_kind(stream, &end_token, Case, diagnostics)? {
+                    if is_matching {
+                        stream.expect_kind(Que)?;
+                    }
+                    // @TODO check end label
+                    stream.pop_if_kind(Identifier)?;
+                    stream.expect_kind(SemiColon)?;
                 }
-                // @TODO check end label
-                stream.pop_if_kind(Identifier)?;
                 alternatives.push(alternative);
                 break;
             }
         );
     }
 
-    stream.expect_kind(SemiColon)?;
     Ok(CaseStatement {
         is_matching,
         expression,
@@ -215,13 +229,14 @@ fn parse_loop_statement_initial_token(
     try_token_kind!(
         end_token,
         End => {
-            stream.expect_kind(Loop)?;
-            // @TODO check end label
-            stream.pop_if_kind(Identifier)?;
+            if expect_end_kind(stream, &end_token, Loop, diagnostics)? {
+                // @TODO check end label
+                stream.pop_if_kind(Identifier)?;
+                stream.expect_kind(SemiColon)?;
+            }
         }
     );
 
-    stream.expect_kind(SemiColon)?;
     Ok(LoopStatement {
         iteration_scheme,
         statements,
@@ -264,15 +279,23 @@ fn parse_exit_statement_known_keyword(stream: &mut TokenStream) -> ParseResult<E
 
 /// LRM 10.13 Return statement
 fn parse_return_statement_known_keyword(stream: &mut TokenStream) -> ParseResult<ReturnStatement> {
+    let mut condition = None;
     let expression = {
-        if stream.peek_kind()? == Some(SemiColon) {
-            None
-        } else {
-            Some(parse_expression(stream)?)
+        match stream.peek_kind()? {
+            Some(SemiColon) => None,
+            Some(When) if stream.standard() >= VHDLStandard::VHDL2019 => {
+                stream.expect_kind(When)?;
+                condition = Some(parse_expression(stream)?);
+                None
+            }
+            _ => Some(parse_conditional_or_unaffected_expression(stream)?),
         }
     };
     stream.expect_kind(SemiColon)?;
-    Ok(ReturnStatement { expression })
+    Ok(ReturnStatement {
+        expression,
+        condition,
+    })
 }
 
 /// LRM 10.5 Signal assignment statement
@@ -1655,7 +1678,10 @@ end loop;
             statement,
             with_label(
                 None,
-                SequentialStatement::Return(ReturnStatement { expression: None })
+                SequentialStatement::Return(ReturnStatement {
+                    expression: None,
+                    condition: None
+                })
             )
         );
     }
@@ -1669,6 +1695,53 @@ end loop;
                 None,
                 SequentialStatement::Return(ReturnStatement {
                     expression: Some(code.s1("1 + 2").expr()),
+                    condition: None
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn parse_return_statement_condition_vhdl2019() {
+        let code = Code::new_with_standard("return when cond;", VHDLStandard::VHDL2019);
+        assert_eq!(
+            code.sequential_statement(),
+            with_label(
+                None,
+                SequentialStatement::Return(ReturnStatement {
+                    expression: None,
+                    condition: Some(code.s1("cond").expr()),
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn parse_return_statement_conditional_expression_vhdl2019() {
+        let code =
+            Code::new_with_standard("return a when c1 else b when c2;", VHDLStandard::VHDL2019);
+        assert_eq!(
+            code.sequential_statement(),
+            with_label(
+                None,
+                SequentialStatement::Return(ReturnStatement {
+                    expression: Some(WithPos::new(
+                        Expression::Conditional(Box::new(Conditionals {
+                            conditionals: vec![
+                                Conditional {
+                                    condition: code.s1("c1").expr(),
+                                    item: code.s1("a").expr()
+                                },
+                                Conditional {
+                                    condition: code.s1("c2").expr(),
+                                    item: code.s1("b").expr()
+                                }
+                            ],
+                            else_item: None
+                        })),
+                        code.s1("a when c1 else b when c2").pos()
+                    )),
+                    condition: None
                 })
             )
         );
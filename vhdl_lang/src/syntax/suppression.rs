@@ -0,0 +1,428 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Inline comments which suppress diagnostics
+//!
+//! -- vhdl_ls: disable=rule1,rule2
+//!   Suppresses the rules for the following line or until a matching enable comment
+//!   When placed after code on the same line only that line is suppressed
+//!
+//! -- vhdl_ls: enable=rule1,rule2
+//!   Ends the region of a previous disable comment
+//!
+//! -- vhdl_ls: disable-file=rule1,rule2
+//!   Suppresses the rules for the whole file
+
+use super::tokens::Comment;
+use crate::data::*;
+use fnv::FnvHashSet;
+
+const PREFIX: &str = "vhdl_ls:";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Scope {
+    Lines { first: u32, last: u32 },
+    File,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Suppression {
+    pos: SrcPos,
+    code: ErrorCode,
+    scope: Scope,
+}
+
+/// The suppression comments of a single source file
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Suppressions {
+    suppressions: Vec<Suppression>,
+    // Diagnostics for malformed suppression comments
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A disable comment waiting for a matching enable comment
+struct OpenSuppression {
+    pos: SrcPos,
+    code: ErrorCode,
+    // The line of the disable comment itself
+    comment_line: u32,
+    // The line suppressed if there is no matching enable comment
+    next_line: u32,
+}
+
+impl Suppressions {
+    /// Returns true if the source may contain suppression comments
+    /// The parser only records the comments of such sources
+    pub fn may_have_suppressions(source: &Source) -> bool {
+        let contents = source.contents();
+        (0..contents.num_lines()).any(|lineno| {
+            contents
+                .get_line(lineno)
+                .map(|line| line.contains(PREFIX))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Create the suppressions of a source from the comments recorded by the parser
+    /// together with the line each comment applies to
+    pub fn from_comments(source: &Source, comments: Vec<(Comment, u32)>) -> Suppressions {
+        let mut suppressions = Suppressions::default();
+
+        let mut open: Vec<OpenSuppression> = Vec::new();
+        for (comment, next_line) in comments {
+            let pos = source.pos(comment.range.start, comment.range.end);
+            let comment_line = comment.range.start.line;
+
+            let directive = if let Some(directive) = parse_directive(&comment.value) {
+                directive
+            } else {
+                continue;
+            };
+
+            let (name, codes) = match directive {
+                Ok(directive) => directive,
+                Err(msg) => {
+                    suppressions.push_invalid(&pos, msg);
+                    continue;
+                }
+            };
+
+            for code in codes {
+                let code = match code {
+                    Ok(code) => code,
+                    Err(msg) => {
+                        suppressions.push_invalid(&pos, msg);
+                        continue;
+                    }
+                };
+
+                match name {
+                    "disable" => open.push(OpenSuppression {
+                        pos: pos.clone(),
+                        code,
+                        comment_line,
+                        next_line,
+                    }),
+                    "enable" => {
+                        if let Some(idx) = open.iter().rposition(|disable| disable.code == code) {
+                            let disable = open.remove(idx);
+                            suppressions.suppressions.push(Suppression {
+                                pos: disable.pos,
+                                code,
+                                scope: Scope::Lines {
+                                    first: disable.comment_line,
+                                    last: comment_line,
+                                },
+                            });
+                        } else {
+                            suppressions.push_invalid(
+                                &pos,
+                                format!("No previous disable of '{}' to enable", code),
+                            );
+                        }
+                    }
+                    "disable-file" => suppressions.suppressions.push(Suppression {
+                        pos: pos.clone(),
+                        code,
+                        scope: Scope::File,
+                    }),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        // Disable comments without a matching enable only suppress the next line
+        for disable in open {
+            suppressions.suppressions.push(Suppression {
+                pos: disable.pos,
+                code: disable.code,
+                scope: Scope::Lines {
+                    first: disable.next_line,
+                    last: disable.next_line,
+                },
+            });
+        }
+
+        suppressions
+    }
+
+    fn push_invalid(&mut self, pos: &SrcPos, msg: impl Into<String>) {
+        self.diagnostics
+            .push(Diagnostic::warning(pos, msg).with_code(ErrorCode::InvalidSuppression));
+    }
+
+    /// Returns the indexes of all suppressions which suppress the diagnostic
+    /// Overlapping suppressions are all used by the diagnostic
+    pub fn suppresses(&self, diagnostic: &Diagnostic) -> Vec<usize> {
+        let code = if let Some(code) = diagnostic.code {
+            code
+        } else {
+            return Vec::new();
+        };
+        let line = diagnostic.pos.range().start.line;
+
+        self.suppressions
+            .iter()
+            .enumerate()
+            .filter(|(_, suppression)| {
+                suppression.code == code
+                    && suppression.pos.source == diagnostic.pos.source
+                    && match suppression.scope {
+                        Scope::Lines { first, last } => first <= line && line <= last,
+                        Scope::File => true,
+                    }
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Diagnostics for malformed suppression comments and
+    /// for suppressions whose index is not within the set of used suppressions
+    pub fn diagnostics(&self, used: &FnvHashSet<usize>) -> Vec<Diagnostic> {
+        let mut diagnostics = self.diagnostics.clone();
+        for (idx, suppression) in self.suppressions.iter().enumerate() {
+            if !used.contains(&idx) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        &suppression.pos,
+                        format!(
+                            "Suppression of '{}' does not suppress any diagnostic",
+                            suppression.code
+                        ),
+                    )
+                    .with_code(ErrorCode::UnusedSuppression),
+                );
+            }
+        }
+        diagnostics
+    }
+}
+
+type Directive<'a> = (&'a str, Vec<Result<ErrorCode, String>>);
+
+/// Parse the contents of a comment
+/// Returns None if the comment is not a suppression comment
+fn parse_directive(comment: &str) -> Option<Result<Directive<'_>, String>> {
+    let comment = comment.trim();
+    if !comment.starts_with(PREFIX) {
+        return None;
+    }
+    let directive = comment[PREFIX.len()..].trim();
+
+    let (name, rules) = if let Some(idx) = directive.find('=') {
+        (directive[..idx].trim(), &directive[idx + 1..])
+    } else {
+        return Some(Err(format!(
+            "Invalid suppression '{}', expected disable=, enable= or disable-file=",
+            directive
+        )));
+    };
+
+    match name {
+        "disable" | "enable" | "disable-file" => {}
+        _ => {
+            return Some(Err(format!(
+                "Invalid suppression '{}', expected disable=, enable= or disable-file=",
+                name
+            )));
+        }
+    }
+
+    let codes = rules
+        .split(',')
+        .map(|rule| rule.trim().parse::<ErrorCode>())
+        .collect();
+    Some(Ok((name, codes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+    use crate::syntax::tokens::Tokenizer;
+    use pretty_assertions::assert_eq;
+
+    fn suppressions(code: &Code) -> Suppressions {
+        let contents = code.source().contents();
+        let mut tokenizer = Tokenizer::new(
+            &code.symbols,
+            code.source(),
+            ContentReader::new(&contents),
+            code.standard,
+        );
+        tokenizer.record_comments();
+        while let Some(_token) = tokenizer.pop().unwrap() {}
+        Suppressions::from_comments(code.source(), tokenizer.take_comments())
+    }
+
+    fn unresolved(code: &Code, name: &str) -> Diagnostic {
+        Diagnostic::error(code.s1(name), "msg").with_code(ErrorCode::Unresolved)
+    }
+
+    #[test]
+    fn disable_next_line() {
+        let code = Code::new(
+            "
+-- vhdl_ls: disable=unresolved
+constant c0 : natural := 0;
+constant c1 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(suppressions.suppresses(&unresolved(&code, "c0")), vec![0]);
+        assert!(suppressions.suppresses(&unresolved(&code, "c1")).is_empty());
+    }
+
+    #[test]
+    fn disable_trailing_comment_suppresses_same_line() {
+        let code = Code::new(
+            "
+constant c0 : natural := 0; -- vhdl_ls: disable=unresolved
+constant c1 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(suppressions.suppresses(&unresolved(&code, "c0")), vec![0]);
+        assert!(suppressions.suppresses(&unresolved(&code, "c1")).is_empty());
+    }
+
+    #[test]
+    fn disable_only_suppresses_given_rules() {
+        let code = Code::new(
+            "
+-- vhdl_ls: disable=invalid-name, type-mismatch
+constant c0 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert!(suppressions.suppresses(&unresolved(&code, "c0")).is_empty());
+        assert_eq!(
+            suppressions.suppresses(
+                &Diagnostic::error(code.s1("c0"), "msg").with_code(ErrorCode::TypeMismatch)
+            ),
+            vec![1]
+        );
+        assert!(suppressions
+            .suppresses(&Diagnostic::error(code.s1("c0"), "msg"))
+            .is_empty());
+    }
+
+    #[test]
+    fn disable_region() {
+        let code = Code::new(
+            "
+-- vhdl_ls: disable=unresolved
+constant c0 : natural := 0;
+
+constant c1 : natural := 0;
+-- vhdl_ls: enable=unresolved
+constant c2 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(suppressions.suppresses(&unresolved(&code, "c0")), vec![0]);
+        assert_eq!(suppressions.suppresses(&unresolved(&code, "c1")), vec![0]);
+        assert!(suppressions.suppresses(&unresolved(&code, "c2")).is_empty());
+    }
+
+    #[test]
+    fn disable_file() {
+        let code = Code::new(
+            "
+constant c0 : natural := 0;
+-- vhdl_ls: disable-file=unresolved
+constant c1 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(suppressions.suppresses(&unresolved(&code, "c0")), vec![0]);
+        assert_eq!(suppressions.suppresses(&unresolved(&code, "c1")), vec![0]);
+    }
+
+    #[test]
+    fn overlapping_suppressions_are_all_used() {
+        let code = Code::new(
+            "
+-- vhdl_ls: disable-file=unresolved
+-- vhdl_ls: disable=unresolved
+constant c0 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(
+            suppressions.suppresses(&unresolved(&code, "c0")),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn ignores_suppression_within_string() {
+        let code = Code::new(
+            "
+constant c0 : string := \"-- vhdl_ls: disable=unresolved\";
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(suppressions, Suppressions::default());
+    }
+
+    #[test]
+    fn unused_suppression() {
+        let code = Code::new(
+            "
+-- vhdl_ls: disable=unresolved
+constant c0 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+
+        let mut used = FnvHashSet::default();
+        assert_eq!(
+            suppressions.diagnostics(&used),
+            vec![Diagnostic::warning(
+                code.s1("-- vhdl_ls: disable=unresolved"),
+                "Suppression of 'unresolved' does not suppress any diagnostic"
+            )
+            .with_code(ErrorCode::UnusedSuppression)]
+        );
+
+        used.insert(0);
+        assert_eq!(suppressions.diagnostics(&used), vec![]);
+    }
+
+    #[test]
+    fn invalid_suppression() {
+        let code = Code::new(
+            "
+-- vhdl_ls: disable=not-a-rule
+-- vhdl_ls: ignore=unresolved
+-- vhdl_ls: enable=unresolved
+constant c0 : natural := 0;
+",
+        );
+        let suppressions = suppressions(&code);
+        assert_eq!(
+            suppressions.diagnostics(&FnvHashSet::default()),
+            vec![
+                Diagnostic::warning(
+                    code.s1("-- vhdl_ls: disable=not-a-rule"),
+                    "Unknown lint rule 'not-a-rule'"
+                )
+                .with_code(ErrorCode::InvalidSuppression),
+                Diagnostic::warning(
+                    code.s1("-- vhdl_ls: ignore=unresolved"),
+                    "Invalid suppression 'ignore', expected disable=, enable= or disable-file="
+                )
+                .with_code(ErrorCode::InvalidSuppression),
+                Diagnostic::warning(
+                    code.s1("-- vhdl_ls: enable=unresolved"),
+                    "No previous disable of 'unresolved' to enable"
+                )
+                .with_code(ErrorCode::InvalidSuppression),
+            ]
+        );
+    }
+}
@@ -26,6 +26,7 @@ use crate::ast;
 use crate::ast::*;
 use crate::data::Range;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
@@ -51,6 +52,7 @@ impl CodeBuilder {
 
         let code = Code {
             symbols: self.symbols.clone(),
+            standard: VHDLStandard::default(),
             pos,
         };
 
@@ -70,6 +72,14 @@ impl CodeBuilder {
         self.code_with_file_name(&file_name, code)
     }
 
+    /// Create code which is tokenized and parsed according to a specific standard
+    pub fn code_with_standard(&self, code: &str, standard: VHDLStandard) -> Code {
+        Code {
+            standard,
+            ..self.code(code)
+        }
+    }
+
     pub fn symbol(&self, name: &str) -> Symbol {
         self.symbols.symtab().insert_utf8(name)
     }
@@ -78,6 +88,7 @@ impl CodeBuilder {
 #[derive(Clone)]
 pub struct Code {
     pub symbols: Arc<Symbols>,
+    pub standard: VHDLStandard,
     pos: SrcPos,
 }
 
@@ -86,6 +97,10 @@ impl Code {
         CodeBuilder::new().code(code)
     }
 
+    pub fn new_with_standard(code: &str, standard: VHDLStandard) -> Code {
+        CodeBuilder::new().code_with_standard(code, standard)
+    }
+
     pub fn new_with_file_name(file_name: &Path, code: &str) -> Code {
         CodeBuilder::new().code_with_file_name(file_name, code)
     }
@@ -93,6 +108,7 @@ impl Code {
     fn in_range(&self, range: Range) -> Code {
         Code {
             symbols: self.symbols.clone(),
+            standard: self.standard,
             pos: SrcPos::new(self.pos.source.clone(), range),
         }
     }
@@ -143,7 +159,8 @@ impl Code {
         {
             let contents = self.pos.source.contents();
             let reader = ContentReader::new(&contents);
-            let mut tokenizer = Tokenizer::new(&self.symbols, &self.pos.source, reader);
+            let mut tokenizer =
+                Tokenizer::new(&self.symbols, &self.pos.source, reader, self.standard);
             loop {
                 let token = tokenizer.pop();
 
@@ -179,7 +196,7 @@ impl Code {
         );
         let contents = source.contents();
         let reader = ContentReader::new(&contents);
-        let tokenizer = Tokenizer::new(&self.symbols, &source, reader);
+        let tokenizer = Tokenizer::new(&self.symbols, &source, reader, self.standard);
         let mut stream = TokenStream::new(tokenizer);
         forward(&mut stream, self.pos.start());
         parse_fun(&mut stream)
@@ -204,7 +221,7 @@ impl Code {
     {
         let contents = self.pos.source.contents();
         let reader = ContentReader::new(&contents);
-        let tokenizer = Tokenizer::new(&self.symbols, &self.pos.source, reader);
+        let tokenizer = Tokenizer::new(&self.symbols, &self.pos.source, reader, self.standard);
         let mut stream = TokenStream::new(tokenizer);
         parse_fun(&mut stream)
     }
@@ -524,7 +541,19 @@ fn diagnostics_to_map(diagnostics: Vec<Diagnostic>) -> HashMap<Diagnostic, usize
 }
 
 /// Check diagnostics are equal without considering order
+/// The rule codes are only compared when some expected diagnostic has a code
 pub fn check_diagnostics(got: Vec<Diagnostic>, expected: Vec<Diagnostic>) {
+    let got = if expected.iter().all(|diagnostic| diagnostic.code.is_none()) {
+        got.into_iter()
+            .map(|diagnostic| Diagnostic {
+                code: None,
+                ..diagnostic
+            })
+            .collect()
+    } else {
+        got
+    };
+
     let mut expected = diagnostics_to_map(expected);
     let mut got = diagnostics_to_map(got);
 
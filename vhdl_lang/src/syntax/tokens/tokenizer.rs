@@ -7,6 +7,10 @@
 use crate::ast;
 use crate::ast::{BaseSpecifier, Ident};
 use crate::data::*;
+use crate::standard::VHDLStandard;
+use crate::syntax::directives::{Directives, Evaluator};
+use fnv::FnvHashMap;
+use std::collections::BTreeMap;
 
 /// The kind of a Token
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -20,6 +24,7 @@ pub enum Kind {
     Process,
     Generate,
     Postponed,
+    Guarded,
     Library,
     Label,
     Use,
@@ -32,6 +37,7 @@ pub enum Kind {
     Of,
     On,
     Generic,
+    Group,
     Map,
     Default,
     Port,
@@ -73,6 +79,9 @@ pub enum Kind {
     Buffer,
     Linkage,
     Signal,
+    Register,
+    Bus,
+    Disconnect,
     Constant,
     Variable,
     File,
@@ -93,6 +102,21 @@ pub enum Kind {
     Function,
     Procedure,
     Vunit,
+    View,
+    Private,
+
+    // PSL reserved words
+    Assume,
+    AssumeGuarantee,
+    Cover,
+    Fairness,
+    Property,
+    Restrict,
+    RestrictGuarantee,
+    Sequence,
+    Strong,
+    Vmode,
+    Vprop,
 
     // Unary operators
     Abs,
@@ -162,6 +186,15 @@ pub enum Kind {
     Comma,
     ColonEq,
     RightArrow,
+    GraveAccent,
+
+    // PSL operators
+    Implication,
+    Equivalence,
+    AndAnd,
+    LeftBrace,
+    RightBrace,
+    Exclamation,
 }
 use self::Kind::*;
 
@@ -226,6 +259,7 @@ pub fn kind_str(kind: Kind) -> &'static str {
         Process => "process",
         Generate => "generate",
         Postponed => "postponed",
+        Guarded => "guarded",
         Library => "library",
         Label => "label",
         Use => "use",
@@ -238,6 +272,7 @@ pub fn kind_str(kind: Kind) -> &'static str {
         Of => "of",
         On => "on",
         Generic => "generic",
+        Group => "group",
         Map => "map",
         Default => "default",
         Port => "port",
@@ -279,6 +314,9 @@ pub fn kind_str(kind: Kind) -> &'static str {
         Buffer => "buffer",
         Linkage => "linkage",
         Signal => "signal",
+        Register => "register",
+        Bus => "bus",
+        Disconnect => "disconnect",
         Constant => "constant",
         Variable => "variable",
         File => "file",
@@ -299,6 +337,19 @@ pub fn kind_str(kind: Kind) -> &'static str {
         Function => "function",
         Procedure => "procedure",
         Vunit => "vunit",
+        View => "view",
+        Private => "private",
+        Assume => "assume",
+        AssumeGuarantee => "assume_guarantee",
+        Cover => "cover",
+        Fairness => "fairness",
+        Property => "property",
+        Restrict => "restrict",
+        RestrictGuarantee => "restrict_guarantee",
+        Sequence => "sequence",
+        Strong => "strong",
+        Vmode => "vmode",
+        Vprop => "vprop",
 
         // Unary operators
         Abs => "abs",
@@ -368,6 +419,13 @@ pub fn kind_str(kind: Kind) -> &'static str {
         Comma => ",",
         ColonEq => ":=",
         RightArrow => "=>",
+        GraveAccent => "`",
+        Implication => "->",
+        Equivalence => "<->",
+        AndAnd => "&&",
+        LeftBrace => "{",
+        RightBrace => "}",
+        Exclamation => "!",
     }
 }
 
@@ -389,6 +447,20 @@ pub fn kinds_str(kinds: &[Kind]) -> String {
     result
 }
 
+/// Returns the first revision of the standard in which the keyword is reserved
+/// Reserved words of VHDL-93 and non-keyword kinds return VHDL-93
+pub fn keyword_standard(kind: Kind) -> VHDLStandard {
+    match kind {
+        Protected => VHDLStandard::VHDL2002,
+        Context | Force | Release | Default | Vunit => VHDLStandard::VHDL2008,
+        // LRM 15.10 The PSL reserved words are reserved words of VHDL-2008
+        Assume | AssumeGuarantee | Cover | Fairness | Property | Restrict | RestrictGuarantee
+        | Sequence | Strong | Vmode | Vprop => VHDLStandard::VHDL2008,
+        View | Private => VHDLStandard::VHDL2019,
+        _ => VHDLStandard::VHDL1993,
+    }
+}
+
 /// The value of a Token
 #[derive(PartialEq, Clone, Debug)]
 pub enum Value {
@@ -547,7 +619,14 @@ impl TokenError {
 
 impl From<Utf8ToLatin1Error> for TokenError {
     fn from(err: Utf8ToLatin1Error) -> TokenError {
-        TokenError::range(err.pos, err.pos.after_char(err.value), err.message())
+        TokenError::range(
+            err.pos,
+            err.pos.after_char(err.value),
+            format!(
+                "{}, other characters are only allowed in comments and strings",
+                err.message()
+            ),
+        )
     }
 }
 
@@ -679,6 +758,11 @@ impl TokenState {
         }
     }
 
+    /// The reader state where the next token starts
+    pub fn start(&self) -> ReaderState {
+        self.start
+    }
+
     /// Set state to after token
     pub fn set_after(&mut self, token: &Token) {
         self.last_token_kind = Some(token.kind);
@@ -703,7 +787,17 @@ fn parse_quoted(
         buffer.bytes.push(quote)
     }
 
-    while let Some(chr) = reader.pop()? {
+    // Strings may contain characters outside of Latin-1, such as from a UTF-8 encoded file,
+    // which are replaced since the string value is Latin-1
+    let allow_non_latin1 = quote == b'"';
+
+    loop {
+        let chr = match reader.pop() {
+            Ok(Some(chr)) => chr,
+            Ok(None) => break,
+            Err(_) if allow_non_latin1 => b'?',
+            Err(err) => return Err(err.into()),
+        };
         is_multiline |= chr == b'\n';
         if chr == quote {
             if reader.peek()? == Some(quote) {
@@ -1024,6 +1118,7 @@ fn parse_basic_identifier_or_keyword(
     buffer: &mut Latin1String,
     reader: &mut ContentReader,
     symbols: &Symbols,
+    standard: VHDLStandard,
 ) -> Result<(Kind, Value), TokenError> {
     buffer.bytes.clear();
     while let Some(b) = reader.peek()? {
@@ -1038,7 +1133,7 @@ fn parse_basic_identifier_or_keyword(
         }
     }
 
-    Ok(symbols.insert_or_keyword(buffer))
+    Ok(symbols.insert_or_keyword(buffer, standard))
 }
 
 /// Assumes leading ' has already been consumed
@@ -1060,13 +1155,50 @@ fn parse_character_literal(
     }
 }
 
-fn get_leading_comments(reader: &mut ContentReader) -> Result<Vec<Comment>, TokenError> {
+/// A part of the source which is skipped by the tokenizer
+/// such as a tool directive or an inactive region of conditional analysis
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SkippedRange {
+    pub start: Position,
+    pub end: ReaderState,
+}
+
+/// Returns the leading comments together with the lines that the first comments apply to
+/// Comments before a skipped range apply to its first line such that they can suppress
+/// the diagnostics of a directive, the remaining comments apply to the following token
+fn get_leading_comments(
+    reader: &mut ContentReader,
+    standard: VHDLStandard,
+    skipped: &mut Vec<SkippedRange>,
+    mut directives: Option<&mut Evaluator>,
+) -> Result<(Vec<Comment>, Vec<u32>), TokenError> {
     let mut comments: Vec<Comment> = Vec::new();
+    let mut lines: Vec<u32> = Vec::new();
 
     loop {
         skip_whitespace(reader);
         let state = reader.state();
 
+        match skipped.binary_search_by_key(&state.pos(), |range| range.start) {
+            Ok(idx) => {
+                reader.set_state(skipped[idx].end);
+                lines.resize(comments.len(), state.pos().line);
+                continue;
+            }
+            Err(idx) => {
+                // A directive is evaluated the first time it is reached and skipped afterwards
+                if let Some(ref mut directives) = directives {
+                    if reader.peek()? == Some(b'`') {
+                        let range = directives.evaluate(state);
+                        reader.set_state(range.end);
+                        skipped.insert(idx, range);
+                        lines.resize(comments.len(), state.pos().line);
+                        continue;
+                    }
+                }
+            }
+        }
+
         let byte = if let Some(byte) = reader.pop()? {
             byte
         } else {
@@ -1074,7 +1206,8 @@ fn get_leading_comments(reader: &mut ContentReader) -> Result<Vec<Comment>, Toke
         };
 
         match byte {
-            b'/' => {
+            // LRM 15.9 Delimited comments were added in VHDL-2008
+            b'/' if standard >= VHDLStandard::VHDL2008 => {
                 if reader.pop()? == Some(b'*') {
                     comments.push(parse_multi_line_comment(reader)?);
                 } else {
@@ -1097,7 +1230,7 @@ fn get_leading_comments(reader: &mut ContentReader) -> Result<Vec<Comment>, Toke
         }
     }
 
-    Ok(comments)
+    Ok((comments, lines))
 }
 
 /// Skip whitespace but not newline
@@ -1159,12 +1292,12 @@ impl Symbols {
         &self.symtab
     }
 
-    fn insert_or_keyword(&self, name: &Latin1String) -> (Kind, Value) {
+    /// Words which are only reserved in later revisions of the standard are identifiers
+    fn insert_or_keyword(&self, name: &Latin1String, standard: VHDLStandard) -> (Kind, Value) {
         let symbol = self.symtab.insert(name);
-        if let Some(kind) = self.keywords.get(symbol.id) {
-            (*kind, Value::NoValue)
-        } else {
-            (Identifier, Value::Identifier(symbol))
+        match self.keywords.get(symbol.id) {
+            Some(kind) if keyword_standard(*kind) <= standard => (*kind, Value::NoValue),
+            _ => (Identifier, Value::Identifier(symbol)),
         }
     }
 }
@@ -1180,6 +1313,7 @@ impl std::default::Default for Symbols {
             ("process", Process),
             ("generate", Generate),
             ("postponed", Postponed),
+            ("guarded", Guarded),
             ("library", Library),
             ("label", Label),
             ("use", Use),
@@ -1192,6 +1326,7 @@ impl std::default::Default for Symbols {
             ("of", Of),
             ("on", On),
             ("generic", Generic),
+            ("group", Group),
             ("map", Map),
             ("default", Default),
             ("port", Port),
@@ -1233,6 +1368,9 @@ impl std::default::Default for Symbols {
             ("buffer", Buffer),
             ("linkage", Linkage),
             ("signal", Signal),
+            ("register", Register),
+            ("bus", Bus),
+            ("disconnect", Disconnect),
             ("constant", Constant),
             ("variable", Variable),
             ("file", File),
@@ -1269,6 +1407,19 @@ impl std::default::Default for Symbols {
             ("mod", Mod),
             ("rem", Rem),
             ("vunit", Vunit),
+            ("view", View),
+            ("private", Private),
+            ("assume", Assume),
+            ("assume_guarantee", AssumeGuarantee),
+            ("cover", Cover),
+            ("fairness", Fairness),
+            ("property", Property),
+            ("restrict", Restrict),
+            ("restrict_guarantee", RestrictGuarantee),
+            ("sequence", Sequence),
+            ("strong", Strong),
+            ("vmode", Vmode),
+            ("vprop", Vprop),
         ];
 
         let symtab = SymbolTable::default();
@@ -1289,13 +1440,21 @@ impl std::default::Default for Symbols {
 
 pub struct Tokenizer<'a> {
     symbols: &'a Symbols,
+    standard: VHDLStandard,
     buffer: Latin1String,
     state: TokenState,
     source: &'a Source,
     reader: ContentReader<'a>,
     final_comments: Option<Vec<Comment>>,
+    // The comments read so far by their start together with the line they apply to
+    // Only recorded when requested since the parser does not need them
+    comments: Option<BTreeMap<Position, (Comment, u32)>>,
+    skipped: Vec<SkippedRange>,
+    // Evaluates the tool directives when they are reached
+    directives: Option<Box<Evaluator<'a>>>,
     range_sym: Symbol,
     reverse_range_sym: Symbol,
+    subtype_sym: Symbol,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -1303,21 +1462,28 @@ impl<'a> Tokenizer<'a> {
         symbols: &'a Symbols,
         source: &'a Source,
         reader: ContentReader<'a>,
+        standard: VHDLStandard,
     ) -> Tokenizer<'a> {
         let range_sym = symbols.symtab().insert(&Latin1String::new(b"range"));
         let reverse_range_sym = symbols
             .symtab()
             .insert(&Latin1String::new(b"reverse_range"));
+        let subtype_sym = symbols.symtab().insert(&Latin1String::new(b"subtype"));
 
         Tokenizer {
             symbols,
+            standard,
             state: TokenState::new(reader.state()),
             buffer: Latin1String::empty(),
             source,
             reader,
             final_comments: None,
+            comments: None,
+            skipped: Vec::new(),
+            directives: None,
             range_sym,
             reverse_range_sym,
+            subtype_sym,
         }
     }
 
@@ -1325,6 +1491,62 @@ impl<'a> Tokenizer<'a> {
         self.state
     }
 
+    /// Skip the given ranges of the source, the ranges must be sorted by their start
+    #[cfg(test)]
+    pub fn set_skipped(&mut self, skipped: Vec<SkippedRange>) {
+        self.skipped = skipped;
+    }
+
+    /// Evaluate the tool directives when they are reached, the directives and the
+    /// regions excluded by conditional analysis are skipped
+    /// The values of the conditional analysis identifiers take precedence over the predefined ones
+    pub fn evaluate_directives(&mut self, identifiers: &'a FnvHashMap<String, String>) {
+        let tokenizer = Tokenizer::new(
+            self.symbols,
+            self.source,
+            self.reader.clone(),
+            self.standard,
+        );
+        self.directives = Some(Box::new(Evaluator::new(tokenizer, identifiers)));
+    }
+
+    /// The evaluated tool directives, the diagnostics of the directives are reported
+    /// The whole source shall have been read
+    pub fn take_directives(&mut self, diagnostics: &mut dyn DiagnosticHandler) -> Directives {
+        self.directives
+            .take()
+            .map(|directives| directives.finish(diagnostics))
+            .unwrap_or_default()
+    }
+
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
+    /// Record the comments of the tokens which are read
+    /// Leading comments apply to the line of the following token
+    /// Trailing comments apply to their own line
+    pub fn record_comments(&mut self) {
+        self.comments.get_or_insert_with(BTreeMap::new);
+    }
+
+    /// The recorded comments in the order of the source together with the line they apply to
+    /// Tokens which are read again after backtracking do not record their comments twice
+    pub fn take_comments(&mut self) -> Vec<(Comment, u32)> {
+        self.comments
+            .take()
+            .map(|comments| comments.into_values().collect())
+            .unwrap_or_default()
+    }
+
+    fn record(&mut self, comment: &Comment, line: u32) {
+        if let Some(ref mut comments) = self.comments {
+            comments
+                .entry(comment.range.start)
+                .or_insert_with(|| (comment.clone(), line));
+        }
+    }
+
     pub fn eof_error(&self) -> Diagnostic {
         Diagnostic::error(
             self.source
@@ -1343,6 +1565,12 @@ impl<'a> Tokenizer<'a> {
         self.reader.set_state(self.state.start);
     }
 
+    /// Move back to before a token which has already been consumed
+    pub fn move_before(&mut self, token: &Token) {
+        self.reader.seek_pos(token.pos.start());
+        self.state = TokenState::new(self.reader.state());
+    }
+
     pub fn range_sym(&self) -> &Symbol {
         &self.range_sym
     }
@@ -1351,6 +1579,10 @@ impl<'a> Tokenizer<'a> {
         &self.reverse_range_sym
     }
 
+    pub fn subtype_sym(&self) -> &Symbol {
+        &self.subtype_sym
+    }
+
     fn parse_token(&mut self) -> Result<Option<(Kind, Value)>, TokenError> {
         macro_rules! illegal_token {
             () => {
@@ -1378,6 +1610,7 @@ impl<'a> Tokenizer<'a> {
                         &mut self.buffer,
                         &mut self.reader,
                         self.symbols,
+                        self.standard,
                     )?
                 }
             }
@@ -1404,7 +1637,11 @@ impl<'a> Tokenizer<'a> {
             }
             b'-' => {
                 self.reader.skip();
-                (Minus, Value::NoValue)
+                if self.reader.skip_if(b'>')? {
+                    (Implication, Value::NoValue)
+                } else {
+                    (Minus, Value::NoValue)
+                }
             }
             b'"' => {
                 self.reader.skip();
@@ -1433,7 +1670,11 @@ impl<'a> Tokenizer<'a> {
             }
             b'&' => {
                 self.reader.skip();
-                (Concat, Value::NoValue)
+                if self.reader.skip_if(b'&')? {
+                    (AndAnd, Value::NoValue)
+                } else {
+                    (Concat, Value::NoValue)
+                }
             }
             b',' => {
                 self.reader.skip();
@@ -1462,6 +1703,17 @@ impl<'a> Tokenizer<'a> {
                         self.reader.skip();
                         (LtLt, Value::NoValue)
                     }
+                    Some(b'-') => {
+                        // Only <-> is a token, a < -b is a less than comparison
+                        let mut lookahead = self.reader.clone();
+                        lookahead.skip();
+                        if lookahead.skip_if(b'>')? {
+                            self.reader.set_to(&lookahead);
+                            (Equivalence, Value::NoValue)
+                        } else {
+                            (LT, Value::NoValue)
+                        }
+                    }
                     _ => (LT, Value::NoValue),
                 }
             }
@@ -1555,6 +1807,22 @@ impl<'a> Tokenizer<'a> {
                 self.reader.skip();
                 (RightSquare, Value::NoValue)
             }
+            b'`' => {
+                self.reader.skip();
+                (GraveAccent, Value::NoValue)
+            }
+            b'{' => {
+                self.reader.skip();
+                (LeftBrace, Value::NoValue)
+            }
+            b'}' => {
+                self.reader.skip();
+                (RightBrace, Value::NoValue)
+            }
+            b'!' => {
+                self.reader.skip();
+                (Exclamation, Value::NoValue)
+            }
             b'\\' => {
                 self.reader.skip();
                 // LRM 15.4.3 Extended identifers
@@ -1567,11 +1835,44 @@ impl<'a> Tokenizer<'a> {
                 illegal_token!();
             }
         };
+
+        // LRM 9.2.3 Matching relational operators and the condition operator were added in VHDL-2008
+        // as well as the operators of the embedded PSL
+        if self.standard < VHDLStandard::VHDL2008
+            && matches!(
+                kind,
+                QueQue
+                    | QueEQ
+                    | QueNE
+                    | QueLT
+                    | QueLTE
+                    | QueGT
+                    | QueGTE
+                    | Implication
+                    | Equivalence
+                    | AndAnd
+                    | LeftBrace
+                    | RightBrace
+                    | Exclamation
+            )
+        {
+            return Err(TokenError::range(
+                self.state.start.pos(),
+                self.reader.pos(),
+                format!("'{}' is not allowed in {}", kind_str(kind), self.standard),
+            ));
+        }
+
         Ok(Some((kind, value)))
     }
 
     fn pop_raw(&mut self) -> Result<Option<Token>, TokenError> {
-        let leading_comments = get_leading_comments(&mut self.reader)?;
+        let (leading_comments, leading_lines) = get_leading_comments(
+            &mut self.reader,
+            self.standard,
+            &mut self.skipped,
+            self.directives.as_deref_mut(),
+        )?;
         self.state.start = self.reader.state();
 
         match self.parse_token()? {
@@ -1580,6 +1881,15 @@ impl<'a> Tokenizer<'a> {
                 let pos_start = self.state.start.pos();
                 let pos_end = self.reader.pos();
                 let trailing_comment = get_trailing_comment(&mut self.reader)?;
+                if self.comments.is_some() {
+                    for (idx, comment) in leading_comments.iter().enumerate() {
+                        let line = leading_lines.get(idx).copied().unwrap_or(pos_start.line);
+                        self.record(comment, line);
+                    }
+                    if let Some(ref comment) = trailing_comment {
+                        self.record(comment, comment.range.start.line);
+                    }
+                }
                 let token_comments = if (!leading_comments.is_empty()) | trailing_comment.is_some()
                 {
                     Some(Box::new(TokenComments {
@@ -1601,6 +1911,15 @@ impl<'a> Tokenizer<'a> {
             }
             None => {
                 // End of file.
+                if self.comments.is_some() {
+                    for (idx, comment) in leading_comments.iter().enumerate() {
+                        let line = leading_lines
+                            .get(idx)
+                            .copied()
+                            .unwrap_or(comment.range.start.line + 1);
+                        self.record(comment, line);
+                    }
+                }
                 self.final_comments = Some(leading_comments);
                 Ok(None)
             }
@@ -1663,7 +1982,10 @@ mod tests {
         assert_eq!(kinds_tokenize("entity"), vec![Entity]);
         assert_eq!(kinds_tokenize("is"), vec![Is]);
         assert_eq!(kinds_tokenize("generic"), vec![Generic]);
+        assert_eq!(kinds_tokenize("group"), vec![Group]);
         assert_eq!(kinds_tokenize("port"), vec![Port]);
+        assert_eq!(kinds_tokenize("guarded"), vec![Guarded]);
+        assert_eq!(kinds_tokenize("disconnect"), vec![Disconnect]);
         assert_eq!(kinds_tokenize("begin"), vec![Begin]);
         assert_eq!(kinds_tokenize("end"), vec![End]);
         assert_eq!(kinds_tokenize("all"), vec![All]);
@@ -1711,6 +2033,45 @@ end entity"
         );
     }
 
+    #[test]
+    fn tokenize_keywords_depending_on_standard() {
+        let kinds_with_standard =
+            |code, standard| kinds(&Code::new_with_standard(code, standard).tokenize());
+
+        assert_eq!(
+            kinds_with_standard("protected", VHDLStandard::VHDL1993),
+            vec![Identifier]
+        );
+        assert_eq!(
+            kinds_with_standard("protected", VHDLStandard::VHDL2002),
+            vec![Protected]
+        );
+        assert_eq!(
+            kinds_with_standard("context force release default", VHDLStandard::VHDL2002),
+            vec![Identifier, Identifier, Identifier, Identifier]
+        );
+        assert_eq!(
+            kinds_with_standard("context force release default", VHDLStandard::VHDL2008),
+            vec![Context, Force, Release, Default]
+        );
+        assert_eq!(
+            kinds_with_standard("property sequence vmode", VHDLStandard::VHDL2002),
+            vec![Identifier, Identifier, Identifier]
+        );
+        assert_eq!(
+            kinds_with_standard("property sequence vmode", VHDLStandard::VHDL2008),
+            vec![Property, Sequence, Vmode]
+        );
+        assert_eq!(
+            kinds_with_standard("view private", VHDLStandard::VHDL2008),
+            vec![Identifier, Identifier]
+        );
+        assert_eq!(
+            kinds_with_standard("view private", VHDLStandard::VHDL2019),
+            vec![View, Private]
+        );
+    }
+
     #[test]
     fn tokenize_keywords_case_insensitive() {
         assert_eq!(kinds_tokenize("entity"), vec![Entity]);
@@ -1847,16 +2208,43 @@ end entity"
             vec![
                 Err(Diagnostic::error(
                     &code.s1("€"),
-                    "Found invalid latin-1 character '€'"
+                    "Found invalid latin-1 character '€', other characters are only allowed in comments and strings"
                 )),
                 Err(Diagnostic::error(
                     &code.s1("\u{1F4A3}"),
-                    "Found invalid latin-1 character '\u{1F4A3}'"
+                    "Found invalid latin-1 character '\u{1F4A3}', other characters are only allowed in comments and strings"
                 ))
             ]
         );
     }
 
+    #[test]
+    fn strings_allow_non_latin1() {
+        let code = Code::new("\"1 \u{1F4A3} = 5 €\" x");
+        let (tokens, _) = code.tokenize_result();
+        let tokens: Vec<_> = tokens.into_iter().map(|token| token.unwrap()).collect();
+
+        assert_eq!(
+            tokens[0].value,
+            Value::String(Latin1String::from_utf8("1 ? = 5 ?").unwrap())
+        );
+        assert_eq!(tokens[0].pos, code.s1("\"1 \u{1F4A3} = 5 €\"").pos());
+        assert_eq!(tokens[1].pos, code.s1("x").pos());
+    }
+
+    #[test]
+    fn extended_identifiers_do_not_allow_non_latin1() {
+        let code = Code::new("\\id€\\");
+        let (tokens, _) = code.tokenize_result();
+        assert_eq!(
+            tokens[0],
+            Err(Diagnostic::error(
+                &code.s1("€"),
+                "Found invalid latin-1 character '€', other characters are only allowed in comments and strings"
+            ))
+        );
+    }
+
     #[test]
     fn tokenize_integer_negative_exponent() {
         let code = Code::new("1e-1");
@@ -2351,6 +2739,94 @@ comment
         );
     }
 
+    #[test]
+    fn tokenize_does_not_ignore_multi_line_comments_before_vhdl2008() {
+        let code = Code::new_with_standard("/* comment */", VHDLStandard::VHDL2002);
+        assert_eq!(
+            kinds(&code.tokenize()),
+            vec![Div, Times, Identifier, Times, Div]
+        );
+    }
+
+    #[test]
+    fn tokenize_questionmark_cmp_before_vhdl2008() {
+        let code = Code::new_with_standard("?=", VHDLStandard::VHDL1993);
+        let (tokens, _) = code.tokenize_result();
+        assert_eq!(
+            tokens,
+            vec![Err(Diagnostic::error(
+                &code.pos(),
+                "'?=' is not allowed in VHDL-93"
+            ))]
+        );
+    }
+
+    #[test]
+    fn tokenize_psl_operators() {
+        assert_eq!(
+            kinds_tokenize("a -> b <-> c && {d} ! a<-b"),
+            vec![
+                Identifier,
+                Implication,
+                Identifier,
+                Equivalence,
+                Identifier,
+                AndAnd,
+                LeftBrace,
+                Identifier,
+                RightBrace,
+                Exclamation,
+                Identifier,
+                LT,
+                Minus,
+                Identifier
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_psl_operators_before_vhdl2008() {
+        let code = Code::new_with_standard("->", VHDLStandard::VHDL2002);
+        let (tokens, _) = code.tokenize_result();
+        assert_eq!(
+            tokens,
+            vec![Err(Diagnostic::error(
+                code.pos(),
+                "'->' is not allowed in VHDL-2002"
+            ))]
+        );
+    }
+
+    #[test]
+    fn tokenize_grave_accent() {
+        assert_eq!(kinds_tokenize("`if"), vec![GraveAccent, If]);
+    }
+
+    #[test]
+    fn tokenize_skipped_ranges() {
+        let code = Code::new("a `if b\nc");
+        let contents = code.source().contents();
+        let mut reader = ContentReader::new(&contents);
+        reader.seek_pos(code.s1("c").pos().start());
+        let skipped = SkippedRange {
+            start: code.s1("`").pos().start(),
+            end: reader.state(),
+        };
+
+        let mut tokenizer = Tokenizer::new(
+            &code.symbols,
+            code.source(),
+            ContentReader::new(&contents),
+            VHDLStandard::default(),
+        );
+        tokenizer.set_skipped(vec![skipped]);
+        let mut tokens = Vec::new();
+        while let Some(token) = tokenizer.pop().unwrap() {
+            tokens.push(token.pos);
+        }
+        assert_eq!(tokens, vec![code.s1("a").pos(), code.s1("c").pos()]);
+    }
+
     #[test]
     fn tokenize_ir1045() {
         // http://www.eda-stds.org/isac/IRs-VHDL-93/IR1045.txt
@@ -2436,7 +2912,7 @@ comment
 
     #[test]
     fn tokenize_illegal() {
-        let code = Code::new("begin!end");
+        let code = Code::new("begin$end");
         let (tokens, _) = code.tokenize_result();
         assert_eq!(
             tokens,
@@ -2448,7 +2924,7 @@ comment
                     next_state: state_at_end(&code.s1("begin")),
                     comments: None,
                 }),
-                Err(Diagnostic::error(&code.s1("!"), "Illegal token")),
+                Err(Diagnostic::error(&code.s1("$"), "Illegal token")),
                 Ok(Token {
                     kind: End,
                     value: Value::NoValue,
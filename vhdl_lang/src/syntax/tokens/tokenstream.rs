@@ -7,7 +7,8 @@
 use super::tokenizer::Kind::*;
 use super::tokenizer::*;
 use crate::ast::Ident;
-use crate::data::{DiagnosticHandler, DiagnosticResult, Symbol};
+use crate::data::{Diagnostic, DiagnosticHandler, DiagnosticResult, Symbol};
+use crate::standard::VHDLStandard;
 
 pub struct TokenStream<'a> {
     tokenizer: Tokenizer<'a>,
@@ -26,10 +27,22 @@ impl<'a> TokenStream<'a> {
         self.tokenizer.reverse_range_sym()
     }
 
+    pub fn subtype_sym(&self) -> &Symbol {
+        self.tokenizer.subtype_sym()
+    }
+
     pub fn state(&self) -> TokenState {
         self.tokenizer.state()
     }
 
+    pub fn tokenizer(&mut self) -> &mut Tokenizer<'a> {
+        &mut self.tokenizer
+    }
+
+    pub fn standard(&self) -> VHDLStandard {
+        self.tokenizer.standard()
+    }
+
     pub fn set_state(&mut self, state: TokenState) {
         self.tokenizer.set_state(state);
     }
@@ -38,6 +51,10 @@ impl<'a> TokenStream<'a> {
         self.tokenizer.move_after(token);
     }
 
+    pub fn move_before(&mut self, token: &Token) {
+        self.tokenizer.move_before(token);
+    }
+
     pub fn pop(&mut self) -> DiagnosticResult<Option<Token>> {
         self.tokenizer.pop()
     }
@@ -121,6 +138,80 @@ impl<'a> TokenStream<'a> {
         }
     }
 
+    /// Recover from an error in a statement which started at the given state by skipping
+    /// the tokens up to where the error was found and then until after the semicolon which
+    /// ends the statement. The if, case, loop, process and block statements are skipped until
+    /// their corresponding end. A token for which `is_end` returns true is not skipped when it
+    /// is where the error was found or ends an enclosing statement, such as when a semicolon
+    /// or an 'end if' is missing before the 'end' of a process.
+    pub fn skip_statement(
+        &mut self,
+        state: TokenState,
+        error: &Diagnostic,
+        is_end: fn(Kind) -> bool,
+    ) -> DiagnosticResult<()> {
+        self.set_state(state);
+
+        let mut blocks: Vec<Kind> = Vec::new();
+        let mut error_token = None;
+        let mut last_kind = None;
+
+        loop {
+            let token = self.peek_expect()?;
+            if error_token.is_none() && token.pos.start() >= error.pos.start() {
+                error_token = Some(token.pos.clone());
+            }
+            let after_error = error_token.is_some();
+
+            if after_error && blocks.is_empty() {
+                if token.kind == SemiColon {
+                    self.move_after(&token);
+                    return Ok(());
+                } else if is_end(token.kind)
+                    && (token.kind == End || error_token.as_ref() == Some(&token.pos))
+                {
+                    return Ok(());
+                }
+            }
+
+            if token.kind == End && !blocks.is_empty() {
+                let state = self.state();
+                self.move_after(&token);
+                let mut next = self.peek_expect()?;
+                if next.kind == Postponed {
+                    self.move_after(&next);
+                    next = self.peek_expect()?;
+                }
+                let innermost = *blocks.last().unwrap();
+
+                if next.kind == innermost
+                    || (next.kind == Generate && matches!(innermost, If | Case))
+                {
+                    blocks.pop();
+                    self.move_after(&next);
+                    last_kind = Some(next.kind);
+                    continue;
+                } else if after_error
+                    && matches!(
+                        next.kind,
+                        If | Case | Loop | Process | Block | Generate | Architecture
+                    )
+                {
+                    // The end of an enclosing statement
+                    self.set_state(state);
+                    return Ok(());
+                }
+            } else if matches!(token.kind, If | Case | Loop | Process | Block)
+                && last_kind != Some(End)
+            {
+                blocks.push(token.kind);
+            }
+
+            self.move_after(&token);
+            last_kind = Some(token.kind);
+        }
+    }
+
     pub fn pop_optional_ident(&mut self) -> DiagnosticResult<Option<Ident>> {
         if let Some(token) = self.pop_if_kind(Identifier)? {
             Ok(Some(token.expect_ident()?))
@@ -135,13 +226,16 @@ impl<'a> TokenStream<'a> {
     }
 
     /// Expect identifier or range keyword
-    pub fn expect_ident_or_range(&mut self) -> DiagnosticResult<Ident> {
+    /// The designator of an attribute name may also be the reserved words range or subtype
+    pub fn expect_attribute_designator(&mut self) -> DiagnosticResult<Ident> {
         let token = self.expect()?;
         match_token_kind!(
             token,
             Identifier => token.expect_ident(),
             Range => Ok(Ident {item: self.range_sym().clone(),
-                               pos: token.pos})
+                               pos: token.pos}),
+            Subtype => Ok(Ident {item: self.subtype_sym().clone(),
+                                 pos: token.pos})
         )
     }
 }
@@ -195,7 +289,12 @@ mod tests {
         ($code:ident, $stream:ident) => {
             let source = $code.source();
             let contents = source.contents();
-            let tokenizer = Tokenizer::new(&$code.symbols, source, ContentReader::new(&contents));
+            let tokenizer = Tokenizer::new(
+                &$code.symbols,
+                source,
+                ContentReader::new(&contents),
+                $code.standard,
+            );
             let mut $stream = TokenStream::new(tokenizer);
         };
     }
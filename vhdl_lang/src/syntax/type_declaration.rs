@@ -14,7 +14,8 @@ use super::subtype_indication::parse_subtype_indication;
 use super::tokens::{Kind::*, TokenStream};
 use crate::ast::*;
 use crate::ast::{AbstractLiteral, Range};
-use crate::data::DiagnosticHandler;
+use crate::data::{Diagnostic, DiagnosticHandler};
+use crate::standard::VHDLStandard;
 
 /// LRM 5.2.2 Enumeration types
 fn parse_enumeration_type_definition(stream: &mut TokenStream) -> ParseResult<TypeDefinition> {
@@ -274,6 +275,13 @@ pub fn parse_type_declaration(
         Record =>  {
             let (def, end_ident) = parse_record_type_definition(stream)?;
             diagnostics.push_some(error_on_end_identifier_mismatch(&ident, &end_ident));
+            if matches!(def, TypeDefinition::Record(ref elems) if elems.is_empty())
+                && stream.standard() < VHDLStandard::VHDL2019 {
+                diagnostics.push(Diagnostic::error(
+                    &ident,
+                    format!("Empty record is not allowed in {}", stream.standard()),
+                ));
+            }
             def
         },
         // Enumeration
@@ -549,6 +557,31 @@ end foo;",
         );
     }
 
+    #[test]
+    fn parse_empty_record_type_definition() {
+        let code =
+            Code::new_with_standard("type foo is record end record;", VHDLStandard::VHDL2019);
+
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_type_declaration),
+            TypeDeclaration {
+                ident: code.s1("foo").ident(),
+                def: TypeDefinition::Record(vec![]),
+            }
+        );
+
+        let code =
+            Code::new_with_standard("type foo is record end record;", VHDLStandard::VHDL2008);
+        let (_, diagnostics) = code.with_stream_diagnostics(parse_type_declaration);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                code.s1("foo"),
+                "Empty record is not allowed in VHDL-2008"
+            )]
+        );
+    }
+
     #[test]
     fn test_parse_subtype_declaration() {
         let code = Code::new("subtype vec_t is integer_vector(2-1 downto 0);");
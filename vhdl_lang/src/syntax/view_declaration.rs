@@ -0,0 +1,225 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+use super::common::error_on_end_identifier_mismatch;
+use super::common::ParseResult;
+use super::names::{parse_identifier_list, parse_selected_name};
+use super::subtype_indication::parse_subtype_indication;
+use super::tokens::{Kind::*, TokenStream};
+use crate::ast::*;
+use crate::data::{Diagnostic, DiagnosticHandler};
+
+/// Parse a mode view name with an optional 'converse attribute
+fn parse_mode_view_name(stream: &mut TokenStream) -> ParseResult<ModeViewName> {
+    let name = parse_selected_name(stream)?;
+    let converse = if stream.skip_if_kind(Tick)? {
+        let attr = stream.expect_ident()?;
+        if !attr.item.name_utf8().eq_ignore_ascii_case("converse") {
+            return Err(Diagnostic::error(
+                &attr,
+                "Only the 'converse attribute is allowed on a mode view name",
+            ));
+        }
+        true
+    } else {
+        false
+    };
+    Ok(ModeViewName { name, converse })
+}
+
+/// Parse the name of a mode view after the view keyword
+/// Either 'name' for a record or '(name)' for an array of records
+fn parse_view_name(stream: &mut TokenStream) -> ParseResult<(ModeViewKind, ModeViewName)> {
+    if stream.skip_if_kind(LeftPar)? {
+        let name = parse_mode_view_name(stream)?;
+        stream.expect_kind(RightPar)?;
+        Ok((ModeViewKind::Array, name))
+    } else {
+        Ok((ModeViewKind::Record, parse_mode_view_name(stream)?))
+    }
+}
+
+/// LRM 6.5.2 mode_view_indication
+/// The view keyword has already been consumed
+pub fn parse_mode_view_indication_known_keyword(
+    stream: &mut TokenStream,
+) -> ParseResult<ModeViewIndication> {
+    let (kind, name) = parse_view_name(stream)?;
+    // The subtype is only optional for record mode views
+    let subtype_indication = if kind == ModeViewKind::Array || stream.peek_kind()? == Some(Of) {
+        stream.expect_kind(Of)?;
+        Some(parse_subtype_indication(stream)?)
+    } else {
+        None
+    };
+
+    Ok(ModeViewIndication {
+        kind,
+        name,
+        subtype_indication,
+    })
+}
+
+fn parse_element_mode(stream: &mut TokenStream) -> ParseResult<ElementMode> {
+    let token = stream.expect()?;
+    Ok(try_token_kind!(
+        token,
+        In => ElementMode::Simple(Mode::In),
+        Out => ElementMode::Simple(Mode::Out),
+        InOut => ElementMode::Simple(Mode::InOut),
+        Buffer => ElementMode::Simple(Mode::Buffer),
+        Linkage => ElementMode::Simple(Mode::Linkage),
+        View => {
+            let (kind, name) = parse_view_name(stream)?;
+            ElementMode::View(kind, name)
+        }
+    ))
+}
+
+/// LRM 6.5.2 Mode view declarations
+pub fn parse_mode_view_declaration(
+    stream: &mut TokenStream,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> ParseResult<ModeViewDeclaration> {
+    stream.expect_kind(View)?;
+    let ident = stream.expect_ident()?;
+    stream.expect_kind(Of)?;
+    let subtype_indication = parse_subtype_indication(stream)?;
+    stream.expect_kind(Is)?;
+
+    let mut elements = Vec::new();
+    loop {
+        let token = stream.peek_expect()?;
+        if token.kind == End {
+            stream.move_after(&token);
+            break;
+        }
+
+        let names = parse_identifier_list(stream)?
+            .into_iter()
+            .map(WithRef::new)
+            .collect();
+        stream.expect_kind(Colon)?;
+        let mode = parse_element_mode(stream)?;
+        stream.expect_kind(SemiColon)?;
+        elements.push(ModeViewElement { names, mode });
+    }
+
+    stream.expect_kind(View)?;
+    let end_ident = stream.pop_optional_ident()?;
+    diagnostics.push_some(error_on_end_identifier_mismatch(&ident, &end_ident));
+    stream.expect_kind(SemiColon)?;
+
+    Ok(ModeViewDeclaration {
+        ident,
+        subtype_indication,
+        elements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standard::VHDLStandard;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn parse_mode_view() {
+        let code = Code::new_with_standard(
+            "\
+view master of rec_t is
+  data, valid : out;
+  ready : in;
+  sub : view sub_view;
+  subs : view (sub_view);
+end view master;",
+            VHDLStandard::VHDL2019,
+        );
+
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_mode_view_declaration),
+            ModeViewDeclaration {
+                ident: code.s1("master").ident(),
+                subtype_indication: code.s1("rec_t").subtype_indication(),
+                elements: vec![
+                    ModeViewElement {
+                        names: vec![
+                            WithRef::new(code.s1("data").ident()),
+                            WithRef::new(code.s1("valid").ident())
+                        ],
+                        mode: ElementMode::Simple(Mode::Out),
+                    },
+                    ModeViewElement {
+                        names: vec![WithRef::new(code.s1("ready").ident())],
+                        mode: ElementMode::Simple(Mode::In),
+                    },
+                    ModeViewElement {
+                        names: vec![WithRef::new(code.s1("sub ").ident())],
+                        mode: ElementMode::View(
+                            ModeViewKind::Record,
+                            ModeViewName {
+                                name: code.s1("sub_view").selected_name(),
+                                converse: false,
+                            }
+                        ),
+                    },
+                    ModeViewElement {
+                        names: vec![WithRef::new(code.s1("subs").ident())],
+                        mode: ElementMode::View(
+                            ModeViewKind::Array,
+                            ModeViewName {
+                                name: code.s("sub_view", 2).selected_name(),
+                                converse: false,
+                            }
+                        ),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_view_end_identifier_mismatch() {
+        let code = Code::new_with_standard(
+            "\
+view master of rec_t is
+  data : out;
+end view other;",
+            VHDLStandard::VHDL2019,
+        );
+
+        let (decl, diagnostics) = code.with_stream_diagnostics(parse_mode_view_declaration);
+        assert_eq!(decl.ident, code.s1("master").ident());
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn parse_array_mode_view_indication_requires_subtype() {
+        let code = Code::new_with_standard("(master)", VHDLStandard::VHDL2019);
+        assert_eq!(
+            code.with_stream_err(parse_mode_view_indication_known_keyword),
+            Diagnostic::error(code.eof_pos(), "Unexpected EOF, when expecting 'of'")
+        );
+    }
+
+    #[test]
+    fn parse_mode_view_indication() {
+        let code =
+            Code::new_with_standard("(master'converse) of rec_vec_t", VHDLStandard::VHDL2019);
+
+        assert_eq!(
+            code.with_stream(parse_mode_view_indication_known_keyword),
+            ModeViewIndication {
+                kind: ModeViewKind::Array,
+                name: ModeViewName {
+                    name: code.s1("master").selected_name(),
+                    converse: true,
+                },
+                subtype_indication: Some(code.s1("rec_vec_t").subtype_indication()),
+            }
+        );
+    }
+}
@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2021, Olof Kraigher olof.kraigher@gmail.com
+
+//! Loading and analysis of the project on a separate thread such that the server
+//! keeps receiving messages from the client while the project is analyzed.
+//!
+//! The analysis thread owns the project while changes are applied and analyzed.
+//! When it is done the project is handed back together with a read-only copy
+//! which requests are answered from until the next analysis is done. The copy
+//! shares the source files and analyzed design units with the project.
+
+use crossbeam_channel::Sender;
+use std::path::PathBuf;
+use std::sync::Arc;
+use vhdl_lang::{
+    Config, Diagnostic, Message, MessageHandler, ProgressHandler, ProgressPhase, Project, Range,
+    Source,
+};
+
+/// A change to the project which is applied before the next analysis
+pub enum ProjectChange {
+    /// Replace the configuration and reload the project
+    Config(Config),
+    /// A document opened by the client
+    Open(Source),
+    /// A document changed by the client
+    Change(Source),
+    /// A file created on disk, it is only added when mapped by the configuration
+    Create(PathBuf),
+    /// A file deleted from disk
+    Delete(PathBuf),
+}
+
+/// Sent from the analysis thread to the server thread
+pub enum AnalysisEvent {
+    Message(Message),
+    Progress(ProgressEvent),
+    /// The analysis is done and the project is handed back,
+    /// there is no result when the changes did not affect the project
+    Done(Box<Project>, Option<AnalysisResult>),
+}
+
+pub enum ProgressEvent {
+    Begin(ProgressPhase, usize),
+    Report(ProgressPhase, usize, usize),
+    End(ProgressPhase),
+}
+
+pub struct AnalysisResult {
+    /// A copy of the analyzed project which is not changed by later analysis
+    pub snapshot: Arc<Project>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The regions excluded by conditional analysis of each file of the project
+    pub inactive_regions: Vec<(PathBuf, Vec<Range>)>,
+}
+
+/// Apply the changes and analyze the project on a new thread
+/// Messages, progress and finally the result are sent as events
+pub fn spawn_analysis(
+    mut project: Box<Project>,
+    changes: Vec<ProjectChange>,
+    events: Sender<AnalysisEvent>,
+) {
+    std::thread::spawn(move || {
+        let result = apply_and_analyze(&mut project, changes, &events);
+        // The server might have been shut down
+        events.send(AnalysisEvent::Done(project, result)).ok();
+    });
+}
+
+fn apply_and_analyze(
+    project: &mut Project,
+    changes: Vec<ProjectChange>,
+    events: &Sender<AnalysisEvent>,
+) -> Option<AnalysisResult> {
+    let mut messages = EventSender { events };
+    let mut progress = EventSender { events };
+    let mut project_has_changed = false;
+    // Progress is reported when loading the project but not for each edit
+    let loads_project = changes
+        .iter()
+        .any(|change| matches!(change, ProjectChange::Config(..)));
+
+    for change in changes.into_iter() {
+        match change {
+            ProjectChange::Config(config) => {
+                project.update_config_with_progress(&config, &mut messages, &mut progress);
+                project_has_changed = true;
+            }
+            ProjectChange::Open(source) => {
+                let file_name = source.file_name();
+                if project.get_source(file_name).is_none() {
+                    if project.is_mapped_by_config(file_name) {
+                        messages.push(Message::log(format!(
+                            "Adding file {} to the project",
+                            file_name.to_string_lossy()
+                        )));
+                    } else {
+                        messages.push(Message::warning(format!(
+                            "Opening file {} that is not part of the project",
+                            file_name.to_string_lossy()
+                        )));
+                    }
+                }
+                project.update_source(&source);
+                project_has_changed = true;
+            }
+            ProjectChange::Change(source) => {
+                project.update_source(&source);
+                project_has_changed = true;
+            }
+            ProjectChange::Create(file_name) => {
+                // Files which are already part of the project are kept as they might be open
+                // Files which are not mapped by the configuration are ignored
+                if project.get_source(&file_name).is_none()
+                    && project.is_mapped_by_config(&file_name)
+                {
+                    match Source::from_file(&file_name, None) {
+                        Ok(source) => {
+                            project.update_source(&source);
+                            project_has_changed = true;
+                        }
+                        Err(err) => {
+                            messages.push(Message::file_error(err.to_string(), &file_name));
+                        }
+                    }
+                }
+            }
+            ProjectChange::Delete(file_name) => {
+                if project.get_source(&file_name).is_some() {
+                    project.remove_source(&file_name);
+                    project_has_changed = true;
+                }
+            }
+        }
+    }
+
+    if !project_has_changed {
+        return None;
+    }
+
+    let diagnostics = if loads_project {
+        project.analyse_with_progress(&mut progress)
+    } else {
+        project.analyse()
+    };
+    let inactive_regions = project
+        .files()
+        .map(|source_file| {
+            (
+                source_file.file_name().to_owned(),
+                source_file.inactive_ranges().to_vec(),
+            )
+        })
+        .collect();
+
+    Some(AnalysisResult {
+        snapshot: Arc::new(project.clone()),
+        diagnostics,
+        inactive_regions,
+    })
+}
+
+/// Forwards messages and progress to the server thread
+struct EventSender<'a> {
+    events: &'a Sender<AnalysisEvent>,
+}
+
+impl<'a> EventSender<'a> {
+    fn send(&self, event: AnalysisEvent) {
+        self.events.send(event).ok();
+    }
+}
+
+impl<'a> MessageHandler for EventSender<'a> {
+    fn push(&mut self, message: Message) {
+        self.send(AnalysisEvent::Message(message));
+    }
+}
+
+impl<'a> ProgressHandler for EventSender<'a> {
+    fn begin(&mut self, phase: ProgressPhase, total: usize) {
+        self.send(AnalysisEvent::Progress(ProgressEvent::Begin(phase, total)));
+    }
+
+    fn report(&mut self, phase: ProgressPhase, done: usize, total: usize) {
+        self.send(AnalysisEvent::Progress(ProgressEvent::Report(
+            phase, done, total,
+        )));
+    }
+
+    fn end(&mut self, phase: ProgressPhase) {
+        self.send(AnalysisEvent::Progress(ProgressEvent::End(phase)));
+    }
+}
@@ -6,8 +6,9 @@
 
 //! Contains the RpcChannel Traid and associated convenience functions
 
+use lsp_server::{RequestId, Response};
 use lsp_types::*;
-use vhdl_lang::{Message, MessageHandler};
+use vhdl_lang::{Message, MessageHandler, ProgressHandler, ProgressPhase};
 
 pub trait RpcChannel {
     /// Send notification to the client.
@@ -17,8 +18,12 @@ pub trait RpcChannel {
         notification: impl serde::ser::Serialize,
     );
 
-    /// Send request to the client.
-    fn send_request(&self, method: impl Into<String>, params: impl serde::ser::Serialize);
+    /// Send request to the client and return the id of the request.
+    fn send_request(
+        &self,
+        method: impl Into<String>,
+        params: impl serde::ser::Serialize,
+    ) -> RequestId;
 
     fn window_show_message(&self, typ: MessageType, message: impl Into<String>) {
         self.send_notification(
@@ -67,6 +72,125 @@ impl<'a, T: RpcChannel> MessageHandler for MessageChannel<'a, T> {
     }
 }
 
+/// Reports the progress of loading and analyzing the project with $/progress notifications
+/// Nothing is sent unless the client supports work done progress
+///
+/// The progress of a token is queued until the client has responded to the
+/// window/workDoneProgress/create request of the token.
+pub struct ProgressChannel<T: RpcChannel> {
+    channel: T,
+    enabled: bool,
+    // Tokens waiting to be created by the client together with their queued progress
+    pending: Vec<(RequestId, String, Vec<serde_json::Value>)>,
+}
+
+impl<T: RpcChannel> ProgressChannel<T> {
+    pub fn new(channel: T, enabled: bool) -> ProgressChannel<T> {
+        ProgressChannel {
+            channel,
+            enabled,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Handle the response of the client to a window/workDoneProgress/create request
+    /// Returns false if the response does not belong to such a request
+    pub fn handle_response(&mut self, response: &Response) -> bool {
+        let idx = match self.pending.iter().position(|(id, ..)| *id == response.id) {
+            Some(idx) => idx,
+            None => return false,
+        };
+
+        let (_, token, values) = self.pending.remove(idx);
+        if let Some(ref error) = response.error {
+            // The token may not be used when the client failed to create it
+            debug!("Failed to create progress token {}: {:?}", token, error);
+        } else {
+            for value in values {
+                self.notify_progress(&token, value);
+            }
+        }
+        true
+    }
+
+    fn send_progress(&mut self, phase: ProgressPhase, value: serde_json::Value) {
+        let token = progress_token(phase);
+        match self.pending.iter_mut().rev().find(|(_, t, _)| *t == token) {
+            Some((_, _, values)) => values.push(value),
+            None => self.notify_progress(&token, value),
+        }
+    }
+
+    fn notify_progress(&self, token: &str, value: serde_json::Value) {
+        self.channel.send_notification(
+            "$/progress",
+            serde_json::json!({
+                "token": token,
+                "value": value,
+            }),
+        );
+    }
+}
+
+impl<T: RpcChannel> ProgressHandler for ProgressChannel<T> {
+    fn begin(&mut self, phase: ProgressPhase, total: usize) {
+        if !self.enabled {
+            return;
+        }
+        let token = progress_token(phase);
+        let id = self.channel.send_request(
+            "window/workDoneProgress/create",
+            serde_json::json!({ "token": token }),
+        );
+        self.pending.push((id, token, Vec::new()));
+        let (title, items) = progress_title(phase);
+        self.send_progress(
+            phase,
+            serde_json::json!({
+                "kind": "begin",
+                "title": title,
+                "cancellable": false,
+                "message": format!("0/{} {}", total, items),
+                "percentage": 0,
+            }),
+        );
+    }
+
+    fn report(&mut self, phase: ProgressPhase, done: usize, total: usize) {
+        if !self.enabled {
+            return;
+        }
+        let (_, items) = progress_title(phase);
+        self.send_progress(
+            phase,
+            serde_json::json!({
+                "kind": "report",
+                "message": format!("{}/{} {}", done, total, items),
+                "percentage": 100 * done / std::cmp::max(total, 1),
+            }),
+        );
+    }
+
+    fn end(&mut self, phase: ProgressPhase) {
+        if !self.enabled {
+            return;
+        }
+        self.send_progress(phase, serde_json::json!({ "kind": "end" }));
+    }
+}
+
+fn progress_token(phase: ProgressPhase) -> String {
+    format!("vhdl_ls/{}", phase.as_ref())
+}
+
+fn progress_title(phase: ProgressPhase) -> (&'static str, &'static str) {
+    match phase {
+        ProgressPhase::Read => ("Reading files", "files"),
+        ProgressPhase::Parse => ("Parsing files", "files"),
+        ProgressPhase::Analyze => ("Analyzing design units", "design units"),
+    }
+}
+
 fn to_lsp_message_type(message_type: &vhdl_lang::MessageType) -> MessageType {
     match message_type {
         vhdl_lang::MessageType::Error => MessageType::Error,
@@ -79,6 +203,7 @@ fn to_lsp_message_type(message_type: &vhdl_lang::MessageType) -> MessageType {
 #[cfg(test)]
 pub mod test_support {
 
+    use lsp_server::RequestId;
     use pretty_assertions::assert_eq;
     use std::cell::RefCell;
     use std::collections::VecDeque;
@@ -101,12 +226,14 @@ pub mod test_support {
     #[derive(Clone)]
     pub struct RpcMock {
         expected: Rc<RefCell<VecDeque<RpcExpected>>>,
+        next_request_id: Rc<RefCell<u64>>,
     }
 
     impl RpcMock {
         pub fn new() -> RpcMock {
             RpcMock {
                 expected: Rc::new(RefCell::new(VecDeque::new())),
+                next_request_id: Rc::new(RefCell::new(0)),
             }
         }
 
@@ -240,7 +367,11 @@ pub mod test_support {
             }
         }
 
-        fn send_request(&self, method: impl Into<String>, params: impl serde::ser::Serialize) {
+        fn send_request(
+            &self,
+            method: impl Into<String>,
+            params: impl serde::ser::Serialize,
+        ) -> RequestId {
             let method = method.into();
             let params = serde_json::to_value(params).unwrap();
             let expected = self
@@ -267,6 +398,93 @@ pub mod test_support {
                     expected, method, params
                 ),
             }
+            RequestId::from(self.next_request_id.replace_with(|&mut id| id + 1))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::RpcMock;
+    use super::*;
+
+    #[test]
+    fn progress_channel_sends_work_done_progress_once_token_is_created() {
+        let mock = RpcMock::new();
+        mock.expect_request(
+            "window/workDoneProgress/create",
+            serde_json::json!({ "token": "vhdl_ls/parse" }),
+        );
+
+        let mut progress = ProgressChannel::new(mock.clone(), true);
+        progress.begin(ProgressPhase::Parse, 4);
+        progress.report(ProgressPhase::Parse, 3, 4);
+
+        // The progress is sent when the client has created the token
+        mock.expect_notification(
+            "$/progress",
+            serde_json::json!({
+                "token": "vhdl_ls/parse",
+                "value": {
+                    "kind": "begin",
+                    "title": "Parsing files",
+                    "cancellable": false,
+                    "message": "0/4 files",
+                    "percentage": 0,
+                },
+            }),
+        );
+        mock.expect_notification(
+            "$/progress",
+            serde_json::json!({
+                "token": "vhdl_ls/parse",
+                "value": {
+                    "kind": "report",
+                    "message": "3/4 files",
+                    "percentage": 75,
+                },
+            }),
+        );
+        assert!(progress.handle_response(&Response::new_ok(RequestId::from(0), ())));
+
+        mock.expect_notification(
+            "$/progress",
+            serde_json::json!({
+                "token": "vhdl_ls/parse",
+                "value": { "kind": "end" },
+            }),
+        );
+        progress.end(ProgressPhase::Parse);
+    }
+
+    #[test]
+    fn progress_channel_drops_progress_when_token_is_not_created() {
+        let mock = RpcMock::new();
+        mock.expect_request(
+            "window/workDoneProgress/create",
+            serde_json::json!({ "token": "vhdl_ls/analyze" }),
+        );
+
+        let mut progress = ProgressChannel::new(mock.clone(), true);
+        progress.begin(ProgressPhase::Analyze, 4);
+        progress.report(ProgressPhase::Analyze, 4, 4);
+        progress.end(ProgressPhase::Analyze);
+
+        // Responses to other requests are not handled
+        assert!(!progress.handle_response(&Response::new_ok(RequestId::from(1), ())));
+        assert!(progress.handle_response(&Response::new_err(
+            RequestId::from(0),
+            lsp_server::ErrorCode::InternalError as i32,
+            "failed".to_owned(),
+        )));
+    }
+
+    #[test]
+    fn progress_channel_is_silent_when_not_supported() {
+        let mock = RpcMock::new();
+        let mut progress = ProgressChannel::new(mock, false);
+        progress.begin(ProgressPhase::Analyze, 4);
+        progress.report(ProgressPhase::Analyze, 4, 4);
+        progress.end(ProgressPhase::Analyze);
+    }
+}
@@ -8,16 +8,22 @@
 //! It also contains the main event loop for handling incoming messages from the LSP client and
 //! dispatching them to the appropriate server methods.
 
+use crossbeam_channel::{after, never, select};
 use lsp_server::{Connection, Request, RequestId};
 use lsp_types::{
     notification::{self, Notification},
-    request, InitializeParams,
+    request, CancelParams, InitializeParams,
 };
+use std::collections::VecDeque;
+use std::time::Duration;
 use std::{cell::RefCell, rc::Rc};
 
 use crate::rpc_channel::RpcChannel;
 use crate::vhdl_server::VHDLServer;
 
+/// Time without incoming messages after a document change before the project is analyzed again.
+const ANALYSIS_DEBOUNCE: Duration = Duration::from_millis(200);
+
 /// Set up the IO channel for `stdio` and start the VHDL language server.
 pub fn start() {
     let (connection, io_threads) = Connection::stdio();
@@ -55,15 +61,21 @@ impl RpcChannel for ConnectionRpcChannel {
     }
 
     /// Send request to the client.
-    fn send_request(&self, method: impl Into<String>, params: impl serde::ser::Serialize) {
-        let request_id = self.next_outgoing_request_id.replace_with(|&mut id| id + 1);
+    fn send_request(
+        &self,
+        method: impl Into<String>,
+        params: impl serde::ser::Serialize,
+    ) -> RequestId {
+        let request_id =
+            RequestId::from(self.next_outgoing_request_id.replace_with(|&mut id| id + 1));
 
         let request = Request::new(
-            RequestId::from(request_id),
+            request_id.clone(),
             method.into(),
             serde_json::to_value(params).unwrap(),
         );
         self.connection.sender.send(request.into()).unwrap();
+        request_id
     }
 }
 
@@ -94,7 +106,24 @@ impl ConnectionRpcChannel {
     /// Main event loop handling incoming messages from the client.
     fn main_event_loop<T: RpcChannel + Clone>(&self, mut server: VHDLServer<T>) {
         info!("Language server initialized, waiting for messages ...");
-        while let Ok(message) = self.connection.receiver.recv() {
+        let mut queue = VecDeque::new();
+        loop {
+            if queue.is_empty() {
+                match self.next_message(&mut server) {
+                    Some(message) => queue.push_back(message),
+                    None => return,
+                }
+            }
+
+            // Take all messages that have already arrived such that queued requests can be cancelled
+            queue.extend(self.connection.receiver.try_iter());
+            self.cancel_requests(&mut queue);
+
+            let message = match queue.pop_front() {
+                Some(message) => message,
+                None => continue,
+            };
+
             trace!("Received message: {:?}", message);
             if let lsp_server::Message::Notification(notification) = &message {
                 if notification.method == notification::Exit::METHOD {
@@ -113,6 +142,76 @@ impl ConnectionRpcChannel {
         }
     }
 
+    /// Wait for the next message from the client while handling events of the analysis thread.
+    /// Pending document changes are analyzed once the client has been idle for a while,
+    /// such that a burst of edits only triggers one analysis.
+    /// Returns None when the connection has been closed.
+    fn next_message<T: RpcChannel + Clone>(
+        &self,
+        server: &mut VHDLServer<T>,
+    ) -> Option<lsp_server::Message> {
+        loop {
+            let analysis_events = server.analysis_events();
+            let debounce = if server.has_pending_changes() {
+                after(ANALYSIS_DEBOUNCE)
+            } else {
+                never()
+            };
+
+            select! {
+                recv(self.connection.receiver) -> message => return message.ok(),
+                recv(analysis_events) -> event => {
+                    if let Ok(event) = event {
+                        server.handle_analysis_event(event);
+                    }
+                }
+                recv(debounce) -> _ => server.analyze_pending_changes(),
+            }
+        }
+    }
+
+    /// Handle $/cancelRequest notifications by removing the cancelled requests from the queue.
+    /// Requests that have already been answered are not affected.
+    fn cancel_requests(&self, queue: &mut VecDeque<lsp_server::Message>) {
+        let mut cancelled_ids = Vec::new();
+        queue.retain(|message| {
+            if let lsp_server::Message::Notification(notification) = message {
+                if notification.method == notification::Cancel::METHOD {
+                    match serde_json::from_value::<CancelParams>(notification.params.clone()) {
+                        Ok(params) => cancelled_ids.push(serde_json::to_value(params.id).unwrap()),
+                        Err(err) => debug!("Invalid cancel notification: {:?}", err),
+                    }
+                    return false;
+                }
+            }
+            true
+        });
+
+        if cancelled_ids.is_empty() {
+            return;
+        }
+
+        let mut cancelled_requests = Vec::new();
+        queue.retain(|message| {
+            if let lsp_server::Message::Request(request) = message {
+                if cancelled_ids.contains(&serde_json::to_value(&request.id).unwrap()) {
+                    cancelled_requests.push(request.id.clone());
+                    return false;
+                }
+            }
+            true
+        });
+
+        for id in cancelled_requests {
+            debug!("Cancelled request: {:?}", id);
+            self.send_response(lsp_server::Response::new_err(
+                id,
+                lsp_server::ErrorCode::RequestCanceled as i32,
+                "Request was cancelled".to_string(),
+            ));
+        }
+    }
+
     /// Send responses (to requests sent by the client) back to the client.
     fn send_response(&self, response: lsp_server::Response) {
         trace!("Sending response: {:?}", response);
@@ -217,6 +316,16 @@ impl ConnectionRpcChannel {
             Ok(params) => return server.workspace_did_change_watched_files(&params),
             Err(notification) => notification,
         };
+        // workspace/didChangeConfiguration
+        let notification = match extract::<notification::DidChangeConfiguration>(notification) {
+            Ok(params) => return server.workspace_did_change_configuration(&params),
+            Err(notification) => notification,
+        };
+        // workspace/didChangeWorkspaceFolders
+        let notification = match extract::<notification::DidChangeWorkspaceFolders>(notification) {
+            Ok(params) => return server.workspace_did_change_workspace_folders(&params),
+            Err(notification) => notification,
+        };
         // exit
         let notification = match extract::<notification::Exit>(notification) {
             Ok(_params) => return server.exit_notification(),
@@ -231,11 +340,101 @@ impl ConnectionRpcChannel {
     /// Handle incoming responses (to requests sent by us) from the client.
     fn handle_response<T: RpcChannel + Clone>(
         &self,
-        _server: &mut VHDLServer<T>,
+        server: &mut VHDLServer<T>,
         response: lsp_server::Response,
     ) {
         trace!("Handling response: {:?}", response);
-        // We currently can ignore incoming responses as the implemented
-        // outgoing requests do not require confirmation by the client.
+        server.handle_response(&response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::{Message, Response};
+    use lsp_types::{request::Request as _, Url};
+
+    fn request(id: u64, method: &str, params: serde_json::Value) -> Message {
+        Request::new(RequestId::from(id), method.to_owned(), params).into()
+    }
+
+    fn notification(method: &str, params: serde_json::Value) -> Message {
+        lsp_server::Notification::new(method.to_owned(), params).into()
+    }
+
+    #[test]
+    fn cancel_request_while_analyzing() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("pkg.vhd"),
+            "\
+package pkg is
+  type typ_t is (foo, bar);
+  constant c : typ_t := bar;
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("vhdl_ls.toml"),
+            "
+[libraries]
+lib.files = ['*.vhd']
+",
+        )
+        .unwrap();
+        let root_uri = Url::from_file_path(&root).unwrap();
+        let file_uri = Url::from_file_path(root.join("pkg.vhd")).unwrap();
+
+        let (connection, client) = Connection::memory();
+        let hover_params = serde_json::json!({
+            "textDocument": { "uri": file_uri },
+            "position": { "line": 2, "character": 16 },
+        });
+        for message in [
+            request(
+                0,
+                request::Initialize::METHOD,
+                serde_json::json!({ "capabilities": {}, "rootUri": root_uri }),
+            ),
+            notification(notification::Initialized::METHOD, serde_json::json!({})),
+            request(1, request::HoverRequest::METHOD, hover_params.clone()),
+            notification(notification::Cancel::METHOD, serde_json::json!({ "id": 1 })),
+            request(2, request::HoverRequest::METHOD, hover_params),
+            notification(notification::Exit::METHOD, serde_json::Value::Null),
+        ] {
+            client.sender.send(message).unwrap();
+        }
+
+        let connection_rpc = ConnectionRpcChannel::new(connection);
+        let mut server = VHDLServer::new_external_config(connection_rpc.clone(), false);
+        // The project is loaded on the analysis thread after the initialized notification
+        connection_rpc.handle_initialization(&mut server);
+        connection_rpc.main_event_loop(server);
+
+        let responses: Vec<Response> = client
+            .receiver
+            .try_iter()
+            .filter_map(|message| match message {
+                Message::Response(response) => Some(response),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].id, RequestId::from(0));
+
+        // The cancelled request is answered with an error without waiting for the analysis
+        assert_eq!(responses[1].id, RequestId::from(1));
+        assert_eq!(
+            responses[1].error.as_ref().map(|error| error.code),
+            Some(lsp_server::ErrorCode::RequestCanceled as i32)
+        );
+
+        // The other request is answered from the last completed analysis,
+        // the project has not been loaded yet
+        assert_eq!(responses[2].id, RequestId::from(2));
+        assert!(responses[2].error.is_none());
+        assert_eq!(responses[2].result, Some(serde_json::Value::Null));
     }
 }
@@ -6,20 +6,29 @@
 
 use lsp_types::*;
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
 use std::collections::hash_map::Entry;
 
-use crate::rpc_channel::{MessageChannel, RpcChannel};
+use crate::analysis_thread::{
+    spawn_analysis, AnalysisEvent, AnalysisResult, ProgressEvent, ProjectChange,
+};
+use crate::rpc_channel::{MessageChannel, ProgressChannel, RpcChannel};
+use crossbeam_channel::{Receiver, Sender};
+use lsp_server::RequestId;
 use std::io;
 use std::path::{Path, PathBuf};
-use vhdl_lang::{Config, Diagnostic, Message, Project, Severity, Source, SrcPos};
+use std::sync::Arc;
+use vhdl_lang::{Config, Diagnostic, Message, ProgressHandler, Project, Severity, Source, SrcPos};
 
 pub struct VHDLServer<T: RpcChannel + Clone> {
     rpc_channel: T,
     // To have well defined unit tests that are not affected by environment
     use_external_config: bool,
     server: Option<InitializedVHDLServer<T>>,
-    config_file: Option<PathBuf>,
+    // The vhdl_ls.toml file of each workspace folder
+    config_files: Vec<PathBuf>,
+    // Configuration from initializationOptions or workspace/didChangeConfiguration
+    client_config: Config,
 }
 
 impl<T: RpcChannel + Clone> VHDLServer<T> {
@@ -27,24 +36,20 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
         Self::new_external_config(rpc_channel, true)
     }
 
-    fn new_external_config(rpc_channel: T, use_external_config: bool) -> VHDLServer<T> {
+    pub(crate) fn new_external_config(rpc_channel: T, use_external_config: bool) -> VHDLServer<T> {
         VHDLServer {
             rpc_channel,
             use_external_config,
             server: None,
-            config_file: None,
+            config_files: Vec::new(),
+            client_config: Config::default(),
         }
     }
 
-    /// Load the workspace root configuration file
-    fn load_root_uri_config(&self) -> io::Result<Config> {
-        let config_file = self.config_file.as_ref().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                "Workspace root configuration file not set",
-            )
-        })?;
-        let config = Config::read_file_path(config_file)?;
+    /// Load the configuration file of a workspace folder
+    fn load_root_uri_config(&self, config_file: &Path) -> io::Result<Config> {
+        let config =
+            Config::read_file_path(config_file, &mut MessageChannel::new(&self.rpc_channel))?;
 
         // Log which file was loaded
         self.rpc_channel.push_msg(Message::log(format!(
@@ -65,11 +70,32 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
             config.load_external_config(&mut message_chan);
         }
 
-        match self.load_root_uri_config() {
-            Ok(root_config) => {
-                config.append(&root_config, &mut message_chan);
+        let mut root_configs = Vec::new();
+        let mut root_errors = Vec::new();
+        let mut nested_configs = Vec::new();
+
+        if self.config_files.is_empty() {
+            root_errors.push(io::Error::new(
+                io::ErrorKind::Other,
+                "Workspace root configuration file not set",
+            ));
+        }
+
+        for config_file in self.config_files.iter() {
+            match self.load_root_uri_config(config_file) {
+                Ok(root_config) => root_configs.push(root_config),
+                Err(err) => root_errors.push(err),
             }
-            Err(ref err) => {
+            nested_configs.extend(self.load_nested_configs(config_file));
+        }
+
+        // A workspace folder does not need a configuration file when there are others
+        // or when the configuration is provided by the client
+        let loaded_any_config = !root_configs.is_empty()
+            || !nested_configs.is_empty()
+            || self.client_config != Config::default();
+        for err in root_errors.iter() {
+            if err.kind() != io::ErrorKind::NotFound || !loaded_any_config {
                 self.rpc_channel.push_msg(Message::error(format!(
                     "Library mapping is unknown due to missing vhdl_ls.toml config file in the workspace root path: {}",
                     err
@@ -78,19 +104,120 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
                     "Without library mapping semantic analysis might be incorrect",
                 ));
             }
-        };
+        }
+
+        // Sub projects take precedence over the workspace roots
+        for root_config in root_configs.iter().chain(nested_configs.iter()) {
+            config.append(root_config, &mut message_chan);
+        }
+
+        // Client settings take precedence over all configuration files
+        config.append(&self.client_config, &mut message_chan);
 
         config
     }
 
+    /// Create configuration from client settings with the same structure as vhdl_ls.toml
+    /// The settings might be within a vhdl_ls section
+    /// File names are relative to the first workspace folder
+    fn load_client_config(&self, settings: &serde_json::Value) -> Config {
+        let settings = settings.get("vhdl_ls").unwrap_or(settings);
+        if settings.is_null() {
+            return Config::default();
+        }
+
+        let parent = self
+            .config_files
+            .first()
+            .and_then(|config_file| config_file.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let config = toml::Value::try_from(settings)
+            .map_err(|err| err.to_string())
+            .and_then(|settings| Config::from_toml(&settings, &parent));
+
+        match config {
+            Ok(config) => {
+                self.rpc_channel
+                    .push_msg(Message::log("Loaded configuration from client settings"));
+                config.read_includes(&mut MessageChannel::new(&self.rpc_channel))
+            }
+            Err(ref err) => {
+                self.rpc_channel.push_msg(Message::error(format!(
+                    "Invalid configuration in client settings: {}",
+                    err
+                )));
+                Config::default()
+            }
+        }
+    }
+
+    /// Load the configuration files of sub projects within a workspace folder
+    fn load_nested_configs(&self, config_file: &Path) -> Vec<Config> {
+        let root = match config_file.parent() {
+            Some(root) => root,
+            None => return Vec::new(),
+        };
+
+        let mut configs = Vec::new();
+        for file_name in Config::find_nested_config_files(root) {
+            match Config::read_file_path(&file_name, &mut MessageChannel::new(&self.rpc_channel)) {
+                Ok(config) => {
+                    self.rpc_channel.push_msg(Message::log(format!(
+                        "Loaded nested configuration file: {}",
+                        file_name.to_string_lossy()
+                    )));
+                    configs.push(config);
+                }
+                Err(ref err) => {
+                    self.rpc_channel.push_msg(Message::error(format!(
+                        "Error while loading nested configuration file {}: {}",
+                        file_name.to_string_lossy(),
+                        err
+                    )));
+                }
+            }
+        }
+        configs
+    }
+
+    /// Returns true if the file is the configuration file of a workspace folder
+    /// or a configuration file of a sub project
+    fn is_config_file(&self, file_name: &Path) -> bool {
+        self.config_files.iter().any(|config_file| {
+            file_name == config_file
+                || (file_name.file_name() == config_file.file_name()
+                    && config_file
+                        .parent()
+                        .map(|root| file_name.starts_with(root))
+                        .unwrap_or(false))
+        })
+    }
+
     pub fn initialize_request(&mut self, params: InitializeParams) -> InitializeResult {
-        self.config_file = self.root_uri_config_file(&params);
+        self.config_files = self.workspace_config_files(&params);
+        if let Some(ref initialization_options) = params.initialization_options {
+            self.client_config = self.load_client_config(initialization_options);
+        }
         let config = self.load_config();
         let (server, result) = InitializedVHDLServer::new(self.rpc_channel.clone(), config, params);
         self.server = Some(server);
         result
     }
 
+    /// Extract the paths of the configuration files of all workspace folders from InitializeParams
+    /// The rootUri is used when the client does not support workspace folders
+    fn workspace_config_files(&self, params: &InitializeParams) -> Vec<PathBuf> {
+        match params.workspace_folders {
+            Some(ref workspace_folders) if !workspace_folders.is_empty() => workspace_folders
+                .iter()
+                .filter_map(|workspace_folder| self.folder_config_file(&workspace_folder.uri))
+                .collect(),
+            _ => self.root_uri_config_file(params).into_iter().collect(),
+        }
+    }
+
     /// Extract path of workspace root configuration file from InitializeParams
     fn root_uri_config_file(&self, params: &InitializeParams) -> Option<PathBuf> {
         match params.root_uri.clone() {
@@ -115,6 +242,92 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
         }
     }
 
+    /// Path of the configuration file of a workspace folder
+    fn folder_config_file(&self, folder_uri: &Url) -> Option<PathBuf> {
+        folder_uri
+            .to_file_path()
+            .map(|folder_path| folder_path.join("vhdl_ls.toml"))
+            .map_err(|_| {
+                self.rpc_channel.push_msg(Message::error(format!(
+                    "Cannot load workspace folder: {:?} is not a valid file path",
+                    folder_uri,
+                )))
+            })
+            .ok()
+    }
+
+    pub fn workspace_did_change_configuration(&mut self, params: &DidChangeConfigurationParams) {
+        self.client_config = self.load_client_config(&params.settings);
+        self.rpc_channel.push_msg(Message::log(
+            "Client settings have changed, reloading project...",
+        ));
+        let config = self.load_config();
+        self.mut_server().change_configuration(config);
+    }
+
+    pub fn workspace_did_change_workspace_folders(
+        &mut self,
+        params: &DidChangeWorkspaceFoldersParams,
+    ) {
+        for workspace_folder in params.event.removed.iter() {
+            if let Some(config_file) = self.folder_config_file(&workspace_folder.uri) {
+                self.config_files
+                    .retain(|known_config_file| *known_config_file != config_file);
+            }
+        }
+
+        for workspace_folder in params.event.added.iter() {
+            if let Some(config_file) = self.folder_config_file(&workspace_folder.uri) {
+                if !self.config_files.contains(&config_file) {
+                    self.config_files.push(config_file);
+                }
+            }
+        }
+
+        self.rpc_channel.push_msg(Message::log(
+            "Workspace folders have changed, reloading project...",
+        ));
+        let config = self.load_config();
+        self.mut_server().change_configuration(config);
+    }
+
+    /// Returns true if there are document changes which have not been analyzed yet
+    /// and no analysis is running
+    pub fn has_pending_changes(&self) -> bool {
+        self.server
+            .as_ref()
+            .map(|server| server.has_pending_changes())
+            .unwrap_or(false)
+    }
+
+    /// Analyze the document changes received since the last analysis on the analysis thread
+    pub fn analyze_pending_changes(&mut self) {
+        if let Some(server) = self.server.as_mut() {
+            server.analyze_pending_changes();
+        }
+    }
+
+    /// Events sent by the analysis thread which shall be passed to handle_analysis_event
+    pub fn analysis_events(&self) -> Receiver<AnalysisEvent> {
+        match self.server {
+            Some(ref server) => server.analysis_events.clone(),
+            None => crossbeam_channel::never(),
+        }
+    }
+
+    pub fn handle_analysis_event(&mut self, event: AnalysisEvent) {
+        if let Some(server) = self.server.as_mut() {
+            server.handle_analysis_event(event);
+        }
+    }
+
+    /// Handle the response of the client to a request sent by the server
+    pub fn handle_response(&mut self, response: &lsp_server::Response) {
+        if let Some(server) = self.server.as_mut() {
+            server.handle_response(response);
+        }
+    }
+
     pub fn shutdown_server(&mut self) {
         self.server = None;
     }
@@ -132,13 +345,20 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
 
     /// Register capabilities on the client side:
     /// - watch workspace config file for changes
+    /// - watch VHDL files being created or deleted
     fn register_capabilities(&mut self) {
         if self.mut_server().client_supports_did_change_watched_files() {
             let register_options = DidChangeWatchedFilesRegistrationOptions {
-                watchers: vec![FileSystemWatcher {
-                    glob_pattern: "**/vhdl_ls.toml".to_owned(),
-                    kind: None,
-                }],
+                watchers: vec![
+                    FileSystemWatcher {
+                        glob_pattern: "**/vhdl_ls.toml".to_owned(),
+                        kind: None,
+                    },
+                    FileSystemWatcher {
+                        glob_pattern: "**/*.{vhd,vhdl}".to_owned(),
+                        kind: Some(WatchKind::Create | WatchKind::Delete),
+                    },
+                ],
             };
             let params = RegistrationParams {
                 registrations: vec![Registration {
@@ -168,18 +388,18 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
     }
 
     pub fn workspace_did_change_watched_files(&mut self, params: &DidChangeWatchedFilesParams) {
-        if let Some(config_file) = &self.config_file {
-            let config_file_has_changed = params
-                .changes
-                .iter()
-                .any(|change| uri_to_file_name(&change.uri).as_path() == config_file);
-            if config_file_has_changed {
-                self.rpc_channel.push_msg(Message::log(
-                    "Configuration file has changed, reloading project...",
-                ));
-                let config = self.load_config();
-                self.mut_server().change_configuration(config);
-            }
+        self.mut_server().change_watched_files(&params.changes);
+
+        let config_file_has_changed = params
+            .changes
+            .iter()
+            .any(|change| self.is_config_file(&uri_to_file_name(&change.uri)));
+        if config_file_has_changed {
+            self.rpc_channel.push_msg(Message::log(
+                "Configuration file has changed, reloading project...",
+            ));
+            let config = self.load_config();
+            self.mut_server().change_configuration(config);
         }
     }
 
@@ -213,8 +433,28 @@ impl<T: RpcChannel + Clone> VHDLServer<T> {
 struct InitializedVHDLServer<T: RpcChannel> {
     rpc_channel: T,
     init_params: InitializeParams,
-    project: Project,
+    // The project to apply changes to, it is owned by the analysis thread while analyzing
+    project: Option<Box<Project>>,
+    // The last completed analysis which requests are answered from without waiting
+    snapshot: Arc<Project>,
     files_with_notifications: FnvHashMap<Url, ()>,
+    // Files with regions which are excluded by conditional analysis
+    files_with_inactive_regions: FnvHashSet<Url>,
+    // Changes which have not been analyzed yet
+    pending_changes: Vec<ProjectChange>,
+    // Analyze the pending changes without waiting for further document changes
+    analyze_immediately: bool,
+    is_analyzing: bool,
+    analysis_sender: Sender<AnalysisEvent>,
+    analysis_events: Receiver<AnalysisEvent>,
+    // The latest contents of documents opened or changed by the client
+    // The project keeps the contents it was analyzed with until the changes have been analyzed
+    documents: FnvHashMap<PathBuf, Source>,
+    // Documents which are part of the pending changes and can still be changed in place
+    pending_documents: FnvHashSet<PathBuf>,
+    // The project is loaded when the client is initialized to be able to report progress
+    config_to_load: Option<Config>,
+    progress: ProgressChannel<T>,
 }
 
 /// Allow VHDL Server to act as an RpcChannel
@@ -227,8 +467,12 @@ impl<T: RpcChannel> RpcChannel for InitializedVHDLServer<T> {
         self.rpc_channel.send_notification(method, notification);
     }
 
-    fn send_request(&self, method: impl Into<String>, params: impl serde::ser::Serialize) {
-        self.rpc_channel.send_request(method, params);
+    fn send_request(
+        &self,
+        method: impl Into<String>,
+        params: impl serde::ser::Serialize,
+    ) -> RequestId {
+        self.rpc_channel.send_request(method, params)
     }
 }
 
@@ -238,13 +482,27 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
         config: Config,
         init_params: InitializeParams,
     ) -> (InitializedVHDLServer<T>, InitializeResult) {
-        let project = Project::from_config(&config, &mut MessageChannel::new(&rpc_channel));
-
+        let (analysis_sender, analysis_events) = crossbeam_channel::unbounded();
+        let progress = ProgressChannel::new(
+            rpc_channel.clone(),
+            client_supports_work_done_progress(&init_params),
+        );
         let server = InitializedVHDLServer {
             rpc_channel,
             init_params,
-            project,
+            project: Some(Box::default()),
+            snapshot: Arc::default(),
             files_with_notifications: FnvHashMap::default(),
+            files_with_inactive_regions: FnvHashSet::default(),
+            pending_changes: Vec::new(),
+            analyze_immediately: false,
+            is_analyzing: false,
+            analysis_sender,
+            analysis_events,
+            documents: FnvHashMap::default(),
+            pending_documents: FnvHashSet::default(),
+            config_to_load: Some(config),
+            progress,
         };
 
         let capabilities = ServerCapabilities {
@@ -255,6 +513,14 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
             definition_provider: Some(true),
             hover_provider: Some(true),
             references_provider: Some(true),
+            workspace: Some(WorkspaceCapability {
+                workspace_folders: Some(WorkspaceFolderCapability {
+                    supported: Some(true),
+                    change_notifications: Some(WorkspaceFolderCapabilityChangeNotifications::Bool(
+                        true,
+                    )),
+                }),
+            }),
             ..Default::default()
         };
 
@@ -267,13 +533,40 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
     }
 
     pub fn change_configuration(&mut self, config: Config) {
-        self.project
-            .update_config(&config, &mut MessageChannel::new(&self.rpc_channel));
-        self.publish_diagnostics();
+        self.change_project(ProjectChange::Config(config));
     }
 
     pub fn initialized_notification(&mut self) {
-        self.publish_diagnostics();
+        if let Some(config) = self.config_to_load.take() {
+            self.change_configuration(config);
+        }
+    }
+
+    /// Change the project and analyze it without waiting for further changes
+    fn change_project(&mut self, change: ProjectChange) {
+        self.pending_changes.push(change);
+        self.analyze_immediately = true;
+        self.analyze_pending_changes();
+    }
+
+    /// Add created VHDL files to the project and remove deleted ones
+    pub fn change_watched_files(&mut self, changes: &[FileEvent]) {
+        for change in changes.iter() {
+            let file_name = uri_to_file_name(&change.uri);
+            if !is_vhdl_file(&file_name) {
+                continue;
+            }
+
+            match change.typ {
+                FileChangeType::Created => {
+                    self.change_project(ProjectChange::Create(file_name));
+                }
+                FileChangeType::Deleted => {
+                    self.change_project(ProjectChange::Delete(file_name));
+                }
+                FileChangeType::Changed => {}
+            }
+        }
     }
 
     fn client_supports_related_information(&self) -> bool {
@@ -302,9 +595,69 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
         try_fun().unwrap_or(false)
     }
 
-    fn publish_diagnostics(&mut self) {
+    fn has_pending_changes(&self) -> bool {
+        !self.pending_changes.is_empty() && !self.is_analyzing
+    }
+
+    /// Start analyzing the pending changes on the analysis thread
+    /// When an analysis is already running they are analyzed once it is done
+    fn analyze_pending_changes(&mut self) {
+        if self.is_analyzing || self.pending_changes.is_empty() {
+            return;
+        }
+
+        let project = match self.project.take() {
+            Some(project) => project,
+            None => return,
+        };
+        self.is_analyzing = true;
+        self.analyze_immediately = false;
+        // The documents are owned by the project from now on
+        self.pending_documents.clear();
+        spawn_analysis(
+            project,
+            std::mem::take(&mut self.pending_changes),
+            self.analysis_sender.clone(),
+        );
+    }
+
+    fn handle_response(&mut self, response: &lsp_server::Response) {
+        if !self.progress.handle_response(response) {
+            debug!("Unhandled response: {:?}", response);
+        }
+    }
+
+    fn handle_analysis_event(&mut self, event: AnalysisEvent) {
+        match event {
+            AnalysisEvent::Message(message) => self.push_msg(message),
+            AnalysisEvent::Progress(event) => match event {
+                ProgressEvent::Begin(phase, total) => self.progress.begin(phase, total),
+                ProgressEvent::Report(phase, done, total) => {
+                    self.progress.report(phase, done, total)
+                }
+                ProgressEvent::End(phase) => self.progress.end(phase),
+            },
+            AnalysisEvent::Done(project, result) => {
+                self.is_analyzing = false;
+                self.project = Some(project);
+                if let Some(result) = result {
+                    self.publish_diagnostics(result);
+                }
+                if self.analyze_immediately {
+                    self.analyze_pending_changes();
+                }
+            }
+        }
+    }
+
+    fn publish_diagnostics(&mut self, result: AnalysisResult) {
+        let AnalysisResult {
+            snapshot,
+            diagnostics,
+            inactive_regions,
+        } = result;
+        self.snapshot = snapshot;
         let supports_related_information = self.client_supports_related_information();
-        let diagnostics = self.project.analyse();
         let diagnostics = {
             if supports_related_information {
                 diagnostics
@@ -343,34 +696,77 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
                 self.send_notification("textDocument/publishDiagnostics", publish_diagnostics);
             }
         }
+
+        self.publish_inactive_regions(inactive_regions);
+    }
+
+    /// Publish the regions which are excluded by conditional analysis so that they can be dimmed
+    /// The notification is the same as the inactive regions extension of clangd
+    fn publish_inactive_regions(&mut self, files: Vec<(PathBuf, Vec<vhdl_lang::Range>)>) {
+        let mut files_with_inactive_regions = std::mem::take(&mut self.files_with_inactive_regions);
+
+        for (file_name, ranges) in files.into_iter() {
+            let file_uri = file_name_to_uri(&file_name);
+            let regions: Vec<_> = ranges.into_iter().map(to_lsp_range).collect();
+
+            // A file which no longer has any inactive regions is published to clear them
+            if files_with_inactive_regions.remove(&file_uri) || !regions.is_empty() {
+                self.send_notification(
+                    "textDocument/inactiveRegions",
+                    inactive_regions(&file_uri, regions.as_slice()),
+                );
+            }
+
+            if !regions.is_empty() {
+                self.files_with_inactive_regions.insert(file_uri);
+            }
+        }
+
+        // Files which were removed from the project
+        for file_uri in files_with_inactive_regions.drain() {
+            self.send_notification(
+                "textDocument/inactiveRegions",
+                inactive_regions(&file_uri, &[]),
+            );
+        }
     }
 
     fn open(&mut self, uri: &Url, code: &str) {
         let file_name = uri_to_file_name(uri);
-        if let Some(source) = self.project.get_source(&file_name) {
-            source.change(None, code);
-            self.project.update_source(&source);
-            self.publish_diagnostics();
-        } else {
-            self.push_msg(Message::warning(format!(
-                "Opening file {} that is not part of the project",
-                file_name.to_string_lossy()
-            )));
-            self.project
-                .update_source(&Source::inline(&file_name, code));
-            self.publish_diagnostics();
+        let source = Source::inline(&file_name, code);
+        self.documents.insert(file_name.clone(), source.clone());
+        self.pending_documents.insert(file_name);
+        self.change_project(ProjectChange::Open(source));
+    }
+
+    /// The source to apply changes of a document to
+    /// A copy is made of sources which are owned by the project
+    /// such that the analyzed design units keep the contents they were parsed from
+    /// Documents which were not opened are taken from the last completed analysis
+    fn document_to_change(&mut self, file_name: &Path) -> Option<Source> {
+        if self.pending_documents.contains(file_name) {
+            return self.documents.get(file_name).cloned();
         }
+
+        let source = match self.documents.get(file_name) {
+            Some(source) => source.snapshot(),
+            None => self.snapshot.get_source(file_name)?.snapshot(),
+        };
+        self.documents.insert(file_name.to_owned(), source.clone());
+        self.pending_documents.insert(file_name.to_owned());
+        self.pending_changes
+            .push(ProjectChange::Change(source.clone()));
+        Some(source)
     }
 
     pub fn text_document_did_change_notification(&mut self, params: &DidChangeTextDocumentParams) {
         let file_name = uri_to_file_name(&params.text_document.uri);
-        if let Some(source) = self.project.get_source(&file_name) {
+        if let Some(source) = self.document_to_change(&file_name) {
             for content_change in params.content_changes.iter() {
                 let range = content_change.range.map(from_lsp_range);
                 source.change(range.as_ref(), &content_change.text);
             }
-            self.project.update_source(&source);
-            self.publish_diagnostics();
+            // Analysis is deferred until the client has stopped sending changes
         } else {
             self.push_msg(Message::error(format!(
                 "Changing file {} that is not part of the project",
@@ -387,12 +783,10 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
         &mut self,
         params: &TextDocumentPositionParams,
     ) -> Option<Location> {
-        self.project
+        let project = &self.snapshot;
+        project
             .get_source(&uri_to_file_name(&params.text_document.uri))
-            .and_then(|source| {
-                self.project
-                    .search_reference(&source, from_lsp_pos(params.position))
-            })
+            .and_then(|source| project.search_reference(&source, from_lsp_pos(params.position)))
             .map(|result| srcpos_to_location(&result))
     }
 
@@ -405,13 +799,11 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
     }
 
     pub fn text_document_hover(&mut self, params: &TextDocumentPositionParams) -> Option<Hover> {
-        self.project
+        let project = &self.snapshot;
+        project
             .get_source(&uri_to_file_name(&params.text_document.uri))
-            .and_then(|source| {
-                self.project
-                    .search_reference(&source, from_lsp_pos(params.position))
-            })
-            .and_then(|decl_pos| self.project.format_declaration(&decl_pos))
+            .and_then(|source| project.search_reference(&source, from_lsp_pos(params.position)))
+            .and_then(|decl_pos| project.format_declaration(&decl_pos))
             .map(|result| Hover {
                 contents: HoverContents::Markup(MarkupContent {
                     kind: MarkupKind::Markdown,
@@ -422,20 +814,20 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
     }
 
     pub fn text_document_references(&mut self, params: &ReferenceParams) -> Vec<Location> {
-        let decl_pos = self
-            .project
+        let project = &self.snapshot;
+        let decl_pos = project
             .get_source(&uri_to_file_name(
                 &params.text_document_position.text_document.uri,
             ))
             .and_then(|source| {
-                self.project.search_reference(
+                project.search_reference(
                     &source,
                     from_lsp_pos(params.text_document_position.position),
                 )
             });
 
         if let Some(ref decl_pos) = decl_pos {
-            self.project
+            project
                 .find_all_references(decl_pos)
                 .iter()
                 .map(srcpos_to_location)
@@ -446,6 +838,11 @@ impl<T: RpcChannel + Clone> InitializedVHDLServer<T> {
     }
 }
 
+fn client_supports_work_done_progress(init_params: &InitializeParams) -> bool {
+    let try_fun = || init_params.capabilities.window.as_ref()?.work_done_progress;
+    try_fun().unwrap_or(false)
+}
+
 fn srcpos_to_location(pos: &SrcPos) -> Location {
     let uri = file_name_to_uri(pos.source.file_name());
     Location {
@@ -468,6 +865,13 @@ fn to_lsp_pos(position: vhdl_lang::Position) -> lsp_types::Position {
     }
 }
 
+fn inactive_regions(file_uri: &Url, regions: &[lsp_types::Range]) -> serde_json::Value {
+    serde_json::json!({
+        "textDocument": { "uri": file_uri },
+        "regions": regions,
+    })
+}
+
 fn to_lsp_range(range: vhdl_lang::Range) -> lsp_types::Range {
     lsp_types::Range {
         start: to_lsp_pos(range.start),
@@ -508,6 +912,19 @@ fn flatten_related(diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
     flat_diagnostics
 }
 
+fn is_vhdl_file(file_name: &Path) -> bool {
+    match file_name
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some(extension) => {
+            let extension = extension.to_ascii_lowercase();
+            extension == "vhd" || extension == "vhdl"
+        }
+        None => false,
+    }
+}
+
 fn file_name_to_uri(file_name: &Path) -> Url {
     // @TODO return error to client
     Url::from_file_path(file_name).unwrap()
@@ -546,7 +963,9 @@ fn to_lsp_diagnostic(diagnostic: Diagnostic) -> lsp_types::Diagnostic {
     lsp_types::Diagnostic {
         range: to_lsp_range(diagnostic.pos.range()),
         severity: Some(severity),
-        code: None,
+        code: diagnostic
+            .code
+            .map(|code| NumberOrString::String(code.to_string())),
         source: Some("vhdl ls".to_owned()),
         message: diagnostic.message,
         related_information,
@@ -576,6 +995,20 @@ mod tests {
 
         server.initialize_request(initialize_params);
         server.initialized_notification();
+        wait_for_analysis(server);
+    }
+
+    /// Handle the events of the analysis thread until all changes have been analyzed
+    fn wait_for_analysis(server: &mut VHDLServer<RpcMock>) {
+        while server
+            .server
+            .as_ref()
+            .map(|server| server.is_analyzing)
+            .unwrap_or(false)
+        {
+            let event = server.analysis_events().recv().unwrap();
+            server.handle_analysis_event(event);
+        }
     }
 
     fn temp_root_uri() -> (tempfile::TempDir, Url) {
@@ -645,6 +1078,7 @@ end entity ent;
         mock.expect_warning_contains("is not part of the project");
 
         server.text_document_did_open_notification(&did_open);
+        wait_for_analysis(&mut server);
     }
 
     #[test]
@@ -684,7 +1118,7 @@ end entity ent2;
                         character: "end entity ent2".len() as u64,
                     },
                 },
-                code: None,
+                code: Some(NumberOrString::String("syntax".to_owned())),
                 severity: Some(DiagnosticSeverity::Error),
                 source: Some("vhdl ls".to_owned()),
                 message: "End identifier mismatch, expected ent".to_owned(),
@@ -698,6 +1132,7 @@ end entity ent2;
 
         mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
         server.text_document_did_open_notification(&did_open);
+        wait_for_analysis(&mut server);
 
         let code = "
 entity ent is
@@ -723,8 +1158,13 @@ end entity ent;
             version: None,
         };
 
-        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
         server.text_document_did_change_notification(&did_change);
+        assert!(server.has_pending_changes());
+
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
+        server.analyze_pending_changes();
+        wait_for_analysis(&mut server);
+        assert!(!server.has_pending_changes());
     }
 
     fn write_file(root_uri: &Url, file_name: impl AsRef<str>, contents: impl AsRef<str>) -> Url {
@@ -777,7 +1217,7 @@ lib.files = [
                         character: "architecture rtl of ent2".len() as u64,
                     },
                 },
-                code: None,
+                code: Some(NumberOrString::String("unresolved".to_owned())),
                 severity: Some(DiagnosticSeverity::Error),
                 source: Some("vhdl ls".to_owned()),
                 message: "No entity \'ent2\' within library \'lib\'".to_owned(),
@@ -793,6 +1233,53 @@ lib.files = [
         initialize_server(&mut server, root_uri);
     }
 
+    #[test]
+    fn initialize_with_inactive_regions() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "file.vhd",
+            "\
+`if DEBUG = \"1\" then
+entity ent is
+end entity;
+`end if
+",
+        );
+
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = ['file.vhd']
+lib.standard = '2019'
+
+[conditional_analysis]
+DEBUG = '0'
+",
+        );
+
+        let regions = vec![Range {
+            start: lsp_types::Position {
+                line: 0,
+                character: "`if DEBUG = \"1\" then".len() as u64,
+            },
+            end: lsp_types::Position {
+                line: 3,
+                character: 0,
+            },
+        }];
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_notification(
+            "textDocument/inactiveRegions",
+            inactive_regions(&file_uri, &regions),
+        );
+
+        initialize_server(&mut server, root_uri);
+    }
+
     #[test]
     fn initialize_with_bad_config() {
         let (mock, mut server) = setup_server();
@@ -876,6 +1363,7 @@ lib.files = [
         };
 
         server.text_document_did_open_notification(&did_open);
+        wait_for_analysis(&mut server);
 
         let response = server.text_document_declaration(&TextDocumentPositionParams {
             text_document: TextDocumentIdentifier { uri: file_url2 },
@@ -903,64 +1391,274 @@ lib.files = [
     }
 
     #[test]
-    fn client_register_capability() {
+    fn requests_use_last_analysis_until_changes_are_analyzed() {
         let (mock, mut server) = setup_server();
         let (_tempdir, root_uri) = temp_root_uri();
 
+        let code = "\
+package pkg is
+  type typ_t is (foo, bar);
+  constant c : typ_t := bar;
+end package;
+"
+        .to_owned();
+        let file_url = write_file(&root_uri, "pkg.vhd", &code);
         let config_uri = write_config(
             &root_uri,
             "
 [libraries]
-        ",
+lib.files = [
+  '*.vhd'
+]
+",
         );
 
-        let register_options = DidChangeWatchedFilesRegistrationOptions {
-            watchers: vec![FileSystemWatcher {
-                glob_pattern: "**/vhdl_ls.toml".to_owned(),
-                kind: None,
-            }],
-        };
-        let register_capability = RegistrationParams {
-            registrations: vec![Registration {
-                id: "workspace/didChangeWatchedFiles".to_owned(),
-                method: "workspace/didChangeWatchedFiles".to_owned(),
-                register_options: serde_json::to_value(register_options).ok(),
-            }],
-        };
-
         expect_loaded_config_messages(&mock, &config_uri);
-        mock.expect_request("client/registerCapability", register_capability);
+        initialize_server(&mut server, root_uri);
 
-        let capabilities = ClientCapabilities {
-            workspace: Some(WorkspaceClientCapabilities {
-                did_change_watched_files: Some(GenericCapability {
-                    dynamic_registration: Some(true),
+        server.text_document_did_open_notification(&DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_url.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: code,
+            },
+        });
+        wait_for_analysis(&mut server);
+
+        // Insert an empty line before the package
+        server.text_document_did_change_notification(&DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: file_url.clone(),
+                version: None,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: Some(Range {
+                    start: lsp_types::Position::new(0, 0),
+                    end: lsp_types::Position::new(0, 0),
                 }),
-                ..WorkspaceClientCapabilities::default()
-            }),
-            ..ClientCapabilities::default()
+                range_length: None,
+                text: "\n".to_owned(),
+            }],
+        });
+
+        let declaration = |server: &mut VHDLServer<RpcMock>, line: u64| {
+            server.text_document_declaration(&TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: file_url.clone(),
+                },
+                position: lsp_types::Position {
+                    line,
+                    character: "  constant c : t".len() as u64,
+                },
+            })
         };
-        #[allow(deprecated)]
-        let initialize_params = InitializeParams {
-            process_id: None,
-            root_path: None,
-            root_uri: Some(root_uri),
-            initialization_options: None,
-            capabilities,
-            trace: None,
-            workspace_folders: None,
-            client_info: None,
+        let location = |line: u64| Location {
+            uri: file_url.clone(),
+            range: Range {
+                start: lsp_types::Position {
+                    line,
+                    character: "  type ".len() as u64,
+                },
+                end: lsp_types::Position {
+                    line,
+                    character: "  type typ_t".len() as u64,
+                },
+            },
         };
 
-        server.initialize_request(initialize_params);
-        server.initialized_notification();
+        // The analyzed design units keep the contents they were parsed from
+        assert_eq!(declaration(&mut server, 2), Some(location(1)));
+        let source = server
+            .mut_server()
+            .snapshot
+            .get_source(&uri_to_file_name(&file_url))
+            .unwrap();
+        assert_eq!(source.contents().get_line(0), Some("package pkg is\n"));
+
+        // Requests are answered from the last completed analysis while the changes are analyzed
+        server.analyze_pending_changes();
+        assert!(server.mut_server().is_analyzing);
+        assert_eq!(declaration(&mut server, 2), Some(location(1)));
+
+        wait_for_analysis(&mut server);
+        assert_eq!(declaration(&mut server, 3), Some(location(2)));
     }
 
-    #[test]
-    fn update_config_file() {
-        let (mock, mut server) = setup_server();
-        let (_tempdir, root_uri) = temp_root_uri();
-        let file1_uri = write_file(
+    fn expect_progress_token(mock: &RpcMock, token: &str) {
+        mock.expect_request(
+            "window/workDoneProgress/create",
+            serde_json::json!({ "token": token }),
+        );
+    }
+
+    /// Expect the progress of a phase with a single file or design unit
+    fn expect_progress(mock: &RpcMock, token: &str, title: &str, items: &str) {
+        mock.expect_notification(
+            "$/progress",
+            serde_json::json!({
+                "token": token,
+                "value": {
+                    "kind": "begin",
+                    "title": title,
+                    "cancellable": false,
+                    "message": format!("0/1 {}", items),
+                    "percentage": 0,
+                },
+            }),
+        );
+        mock.expect_notification(
+            "$/progress",
+            serde_json::json!({
+                "token": token,
+                "value": {
+                    "kind": "report",
+                    "message": format!("1/1 {}", items),
+                    "percentage": 100,
+                },
+            }),
+        );
+        mock.expect_notification(
+            "$/progress",
+            serde_json::json!({ "token": token, "value": { "kind": "end" } }),
+        );
+    }
+
+    #[test]
+    fn progress_is_only_reported_when_loading_the_project() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let code = "\
+package pkg is
+end package;
+";
+        let file_uri = write_file(&root_uri, "pkg.vhd", code);
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = [
+  '*.vhd'
+]
+",
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        // The progress is only sent when the client has created the tokens
+        expect_progress_token(&mock, "vhdl_ls/read");
+        expect_progress_token(&mock, "vhdl_ls/parse");
+        expect_progress_token(&mock, "vhdl_ls/analyze");
+
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            process_id: None,
+            root_path: None,
+            root_uri: Some(root_uri),
+            initialization_options: None,
+            capabilities: ClientCapabilities {
+                window: Some(WindowClientCapabilities {
+                    work_done_progress: Some(true),
+                }),
+                ..ClientCapabilities::default()
+            },
+            trace: None,
+            workspace_folders: None,
+            client_info: None,
+        };
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        wait_for_analysis(&mut server);
+
+        expect_progress(&mock, "vhdl_ls/read", "Reading files", "files");
+        expect_progress(&mock, "vhdl_ls/parse", "Parsing files", "files");
+        expect_progress(
+            &mock,
+            "vhdl_ls/analyze",
+            "Analyzing design units",
+            "design units",
+        );
+        for id in 0..3 {
+            server.handle_response(&lsp_server::Response::new_ok(RequestId::from(id), ()));
+        }
+
+        // Analyzing an edit does not report progress
+        server.text_document_did_open_notification(&DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_uri,
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: code.to_owned(),
+            },
+        });
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn client_register_capability() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+        ",
+        );
+
+        let register_options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![
+                FileSystemWatcher {
+                    glob_pattern: "**/vhdl_ls.toml".to_owned(),
+                    kind: None,
+                },
+                FileSystemWatcher {
+                    glob_pattern: "**/*.{vhd,vhdl}".to_owned(),
+                    kind: Some(WatchKind::Create | WatchKind::Delete),
+                },
+            ],
+        };
+        let register_capability = RegistrationParams {
+            registrations: vec![Registration {
+                id: "workspace/didChangeWatchedFiles".to_owned(),
+                method: "workspace/didChangeWatchedFiles".to_owned(),
+                register_options: serde_json::to_value(register_options).ok(),
+            }],
+        };
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_request("client/registerCapability", register_capability);
+
+        let capabilities = ClientCapabilities {
+            workspace: Some(WorkspaceClientCapabilities {
+                did_change_watched_files: Some(GenericCapability {
+                    dynamic_registration: Some(true),
+                }),
+                ..WorkspaceClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        };
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            process_id: None,
+            root_path: None,
+            root_uri: Some(root_uri),
+            initialization_options: None,
+            capabilities,
+            trace: None,
+            workspace_folders: None,
+            client_info: None,
+        };
+
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn update_config_file() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file1_uri = write_file(
             &root_uri,
             "file1.vhd",
             "\
@@ -1001,7 +1699,7 @@ lib.files = [
                         character: "architecture rtl of ent".len() as u64,
                     },
                 },
-                code: None,
+                code: Some(NumberOrString::String("unresolved".to_owned())),
                 severity: Some(DiagnosticSeverity::Error),
                 source: Some("vhdl ls".to_owned()),
                 message: "No entity \'ent\' within library \'lib\'".to_owned(),
@@ -1046,5 +1744,481 @@ lib.files = [
                 uri: config_uri,
             }],
         });
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn created_file_is_added_to_library() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file1_uri = write_file(
+            &root_uri,
+            "file1.vhd",
+            "\
+architecture rtl of ent is
+begin
+end;
+",
+        );
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = [
+  '*.vhd'
+]
+",
+        );
+
+        let publish_diagnostics1 = PublishDiagnosticsParams {
+            uri: file1_uri.clone(),
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ".len() as u64,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ent".len() as u64,
+                    },
+                },
+                code: Some(NumberOrString::String("unresolved".to_owned())),
+                severity: Some(DiagnosticSeverity::Error),
+                source: Some("vhdl ls".to_owned()),
+                message: "No entity \'ent\' within library \'lib\'".to_owned(),
+                related_information: None,
+                tags: None,
+            }],
+            version: None,
+        };
+
+        // after the entity has been created
+        let publish_diagnostics2 = PublishDiagnosticsParams {
+            uri: file1_uri,
+            diagnostics: vec![],
+            version: None,
+        };
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            publish_diagnostics1.clone(),
+        );
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics2);
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics1);
+
+        initialize_server(&mut server, root_uri.clone());
+
+        let file2_uri = write_file(
+            &root_uri,
+            "file2.vhd",
+            "\
+entity ent is
+end entity;
+",
+        );
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                typ: FileChangeType::Created,
+                uri: file2_uri.clone(),
+            }],
+        });
+        wait_for_analysis(&mut server);
+
+        std::fs::remove_file(uri_to_file_name(&file2_uri)).unwrap();
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                typ: FileChangeType::Deleted,
+                uri: file2_uri,
+            }],
+        });
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn created_file_not_mapped_by_config_is_ignored() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join("other")).unwrap();
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = [
+  '*.vhd'
+]
+",
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_warning_contains("did not match any file");
+        initialize_server(&mut server, root_uri.clone());
+
+        // The file has an error which would be published if it was added to the project
+        let file_uri = write_file(
+            &root_uri,
+            "other/file.vhd",
+            "\
+architecture rtl of ent is
+begin
+end;
+",
+        );
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                typ: FileChangeType::Created,
+                uri: file_uri,
+            }],
+        });
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn initialize_with_nested_config() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join("sub")).unwrap();
+
+        let file_uri = write_file(
+            &root_uri,
+            "sub/file.vhd",
+            "\
+architecture rtl of ent is
+begin
+end;
+",
+        );
+        let nested_config_uri = write_file(
+            &root_uri,
+            "sub/vhdl_ls.toml",
+            "
+[libraries]
+lib.files = [
+  'file.vhd'
+]
+",
+        );
+
+        let publish_diagnostics = PublishDiagnosticsParams {
+            uri: file_uri,
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ".len() as u64,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ent".len() as u64,
+                    },
+                },
+                code: Some(NumberOrString::String("unresolved".to_owned())),
+                severity: Some(DiagnosticSeverity::Error),
+                source: Some("vhdl ls".to_owned()),
+                message: "No entity \'ent\' within library \'lib\'".to_owned(),
+                related_information: None,
+                tags: None,
+            }],
+            version: None,
+        };
+
+        // The workspace root configuration file is missing but not required
+        mock.expect_message_contains(format!(
+            "Loaded nested configuration file: {}",
+            nested_config_uri.to_file_path().unwrap().to_str().unwrap()
+        ));
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
+
+        initialize_server(&mut server, root_uri);
+    }
+
+    fn initialize_server_with_folders(server: &mut VHDLServer<RpcMock>, folder_uris: &[Url]) {
+        let workspace_folders = folder_uris
+            .iter()
+            .map(|folder_uri| WorkspaceFolder {
+                uri: folder_uri.clone(),
+                name: "folder".to_owned(),
+            })
+            .collect();
+
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            process_id: None,
+            root_path: None,
+            root_uri: folder_uris.first().cloned(),
+            initialization_options: None,
+            capabilities: ClientCapabilities::default(),
+            trace: None,
+            workspace_folders: Some(workspace_folders),
+            client_info: None,
+        };
+
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        wait_for_analysis(server);
+    }
+
+    /// Write a package in library ip to one folder and a user of it in library top to another
+    fn write_ip_and_top_folders(ip_uri: &Url, top_uri: &Url) -> (Url, Url, Url) {
+        write_file(
+            ip_uri,
+            "ip.vhd",
+            "\
+package ip_pkg is
+end package;
+",
+        );
+        let ip_config_uri = write_config(
+            ip_uri,
+            "
+[libraries]
+ip.files = ['ip.vhd']
+",
+        );
+
+        let top_file_uri = write_file(
+            top_uri,
+            "top.vhd",
+            "\
+library ip;
+
+package top_pkg is
+end package;
+",
+        );
+        let top_config_uri = write_config(
+            top_uri,
+            "
+[libraries]
+top.files = ['top.vhd']
+",
+        );
+
+        (ip_config_uri, top_file_uri, top_config_uri)
+    }
+
+    #[test]
+    fn initialize_with_workspace_folders() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir1, ip_uri) = temp_root_uri();
+        let (_tempdir2, top_uri) = temp_root_uri();
+        let (ip_config_uri, _, top_config_uri) = write_ip_and_top_folders(&ip_uri, &top_uri);
+
+        // The libraries of both folders are visible to each other, no diagnostics are published
+        expect_loaded_config_messages(&mock, &ip_config_uri);
+        expect_loaded_config_messages(&mock, &top_config_uri);
+
+        initialize_server_with_folders(&mut server, &[ip_uri, top_uri]);
+    }
+
+    #[test]
+    fn did_change_workspace_folders() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir1, ip_uri) = temp_root_uri();
+        let (_tempdir2, top_uri) = temp_root_uri();
+        let (ip_config_uri, top_file_uri, top_config_uri) =
+            write_ip_and_top_folders(&ip_uri, &top_uri);
+
+        let publish_diagnostics = PublishDiagnosticsParams {
+            uri: top_file_uri.clone(),
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: "library ".len() as u64,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: "library ip".len() as u64,
+                    },
+                },
+                code: Some(NumberOrString::String("unresolved".to_owned())),
+                severity: Some(DiagnosticSeverity::Error),
+                source: Some("vhdl ls".to_owned()),
+                message: "No such library \'ip\'".to_owned(),
+                related_information: None,
+                tags: None,
+            }],
+            version: None,
+        };
+
+        expect_loaded_config_messages(&mock, &top_config_uri);
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
+        mock.expect_message_contains("Workspace folders have changed, reloading project...");
+        expect_loaded_config_messages(&mock, &top_config_uri);
+        expect_loaded_config_messages(&mock, &ip_config_uri);
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            PublishDiagnosticsParams {
+                uri: top_file_uri,
+                diagnostics: vec![],
+                version: None,
+            },
+        );
+
+        initialize_server_with_folders(&mut server, &[top_uri]);
+
+        server.workspace_did_change_workspace_folders(&DidChangeWorkspaceFoldersParams {
+            event: WorkspaceFoldersChangeEvent {
+                added: vec![WorkspaceFolder {
+                    uri: ip_uri,
+                    name: "ip".to_owned(),
+                }],
+                removed: vec![],
+            },
+        });
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn initialization_options_and_did_change_configuration() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "file.vhd",
+            "\
+architecture rtl of ent is
+begin
+end;
+",
+        );
+
+        let publish_diagnostics = PublishDiagnosticsParams {
+            uri: file_uri.clone(),
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ".len() as u64,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ent".len() as u64,
+                    },
+                },
+                code: Some(NumberOrString::String("unresolved".to_owned())),
+                severity: Some(DiagnosticSeverity::Error),
+                source: Some("vhdl ls".to_owned()),
+                message: "No entity \'ent\' within library \'lib\'".to_owned(),
+                related_information: None,
+                tags: None,
+            }],
+            version: None,
+        };
+
+        // The diagnostic is turned off by the changed settings
+        let publish_diagnostics_off = PublishDiagnosticsParams {
+            uri: file_uri,
+            diagnostics: vec![],
+            version: None,
+        };
+
+        mock.expect_message_contains("Loaded configuration from client settings");
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
+        mock.expect_message_contains("Loaded configuration from client settings");
+        mock.expect_message_contains("Client settings have changed, reloading project...");
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics_off);
+
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            process_id: None,
+            root_path: None,
+            root_uri: Some(root_uri),
+            initialization_options: Some(serde_json::json!({
+                "libraries": {
+                    "lib": {"files": ["file.vhd"]}
+                }
+            })),
+            capabilities: ClientCapabilities::default(),
+            trace: None,
+            workspace_folders: None,
+            client_info: None,
+        };
+
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        wait_for_analysis(&mut server);
+
+        server.workspace_did_change_configuration(&DidChangeConfigurationParams {
+            settings: serde_json::json!({
+                "vhdl_ls": {
+                    "libraries": {
+                        "lib": {"files": ["file.vhd"]}
+                    },
+                    "lint": {"unresolved": "off"}
+                }
+            }),
+        });
+        wait_for_analysis(&mut server);
+    }
+
+    #[test]
+    fn include_in_client_settings() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "file.vhd",
+            "\
+architecture rtl of ent is
+begin
+end;
+",
+        );
+        write_file(
+            &root_uri,
+            "libraries.toml",
+            "
+[libraries]
+lib.files = ['file.vhd']
+",
+        );
+
+        let publish_diagnostics = PublishDiagnosticsParams {
+            uri: file_uri,
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ".len() as u64,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: "architecture rtl of ent".len() as u64,
+                    },
+                },
+                code: Some(NumberOrString::String("unresolved".to_owned())),
+                severity: Some(DiagnosticSeverity::Error),
+                source: Some("vhdl ls".to_owned()),
+                message: "No entity \'ent\' within library \'lib\'".to_owned(),
+                related_information: None,
+                tags: None,
+            }],
+            version: None,
+        };
+
+        // The library is defined by the file included from the client settings
+        mock.expect_message_contains("Loaded configuration from client settings");
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
+
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            process_id: None,
+            root_path: None,
+            root_uri: Some(root_uri),
+            initialization_options: Some(serde_json::json!({
+                "vhdl_ls": {"include": "libraries.toml"}
+            })),
+            capabilities: ClientCapabilities::default(),
+            trace: None,
+            workspace_folders: None,
+            client_info: None,
+        };
+
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        wait_for_analysis(&mut server);
     }
 }